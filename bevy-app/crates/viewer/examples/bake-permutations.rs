@@ -0,0 +1,284 @@
+use std::{collections::HashSet, path::Path};
+
+use bevy::{
+    app::AppExit,
+    asset::LoadState,
+    core_pipeline::prepass::{DepthPrepass, NormalPrepass},
+    pbr::FogSettings,
+    prelude::{
+        default, error, info, shape::Cube, AlphaMode, App, AssetPlugin, AssetServer, Assets,
+        Camera, Camera3dBundle, Color, Commands, DefaultPlugins, DirectionalLightBundle,
+        EventWriter, Handle, Image, Local, MaterialMeshBundle, Mesh, PluginGroup, Res, ResMut,
+        Resource, StandardMaterial, Transform, Vec3,
+    },
+    render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        view::NoFrustumCulling,
+    },
+};
+
+use bevy_rust_gpu::{
+    prelude::{RustGpu, RustGpuMaterialPlugin, RustGpuPlugin},
+    RustGpuBuilderOutput,
+};
+use serde_json::Value;
+
+/// Workspace-relative path to SPIR-V shader
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
+
+/// Workspace-relative path to entry points file
+const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
+
+/// Frames to keep drawing after the shader loads, giving every pipeline time to specialize
+const SETTLE_FRAMES: u32 = 120;
+
+const ALPHA_MODES: [AlphaMode; 6] = [
+    AlphaMode::Opaque,
+    AlphaMode::Mask(0.5),
+    AlphaMode::Blend,
+    AlphaMode::Premultiplied,
+    AlphaMode::Add,
+    AlphaMode::Multiply,
+];
+
+/// Textures a `StandardMaterial` can carry, as bits of a combination index
+const BASE_COLOR_TEXTURE: u32 = 1 << 0;
+const EMISSIVE_TEXTURE: u32 = 1 << 1;
+const METALLIC_ROUGHNESS_TEXTURE: u32 = 1 << 2;
+const OCCLUSION_TEXTURE: u32 = 1 << 3;
+const NORMAL_MAP_TEXTURE: u32 = 1 << 4;
+const TEXTURE_COMBINATIONS: u32 = 1 << 5;
+
+/// Counts gathered while spawning, for the final report
+#[derive(Debug, Default, Resource)]
+struct BakeStats {
+    shader: Handle<RustGpuBuilderOutput>,
+    materials: usize,
+    cameras: usize,
+}
+
+fn main() {
+    let mut app = App::default();
+
+    // Add default plugins
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        watch_for_changes: true,
+        ..default()
+    }));
+
+    // Add the Rust-GPU plugin
+    app.add_plugin(RustGpuPlugin::default());
+
+    // Setup `RustGpu<StandardMaterial>`, recording every permutation it specializes
+    // so a shipped build never meets a combination missing from the shader
+    app.add_plugin(RustGpuMaterialPlugin::<StandardMaterial>::default());
+    RustGpu::<StandardMaterial>::export_to(ENTRY_POINTS_PATH);
+
+    app.init_resource::<BakeStats>();
+
+    app.add_startup_system(setup);
+    app.add_system(report_and_exit);
+
+    // Run
+    app.run();
+}
+
+/// Spawn one entity per combination of alpha mode, texture presence, unlit, depth bias and fog,
+/// in view of cameras covering HDR and prepass variants
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<RustGpu<StandardMaterial>>>,
+    mut stats: ResMut<BakeStats>,
+) {
+    // One camera per view variant the PBR fragment specializes on
+    for (order, (hdr, prepass)) in [(false, false), (true, false), (false, true), (true, true)]
+        .into_iter()
+        .enumerate()
+    {
+        let mut camera = commands.spawn((
+            Camera3dBundle {
+                camera: Camera {
+                    order: order as isize,
+                    hdr,
+                    ..default()
+                },
+                ..default()
+            },
+            FogSettings::default(),
+        ));
+
+        if prepass {
+            camera.insert((DepthPrepass, NormalPrepass));
+        }
+
+        stats.cameras += 1;
+    }
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::IDENTITY.looking_at(Vec3::new(0.0, -1.0, -1.0), Vec3::Y),
+        ..default()
+    });
+
+    // Normal maps need tangents, so they get a mesh of their own
+    let mesh = meshes.add(Cube { size: 0.5 }.into());
+    let mut tangent_mesh = Mesh::from(Cube { size: 0.5 });
+    tangent_mesh
+        .generate_tangents()
+        .expect("Cube mesh should support tangent generation");
+    let tangent_mesh = meshes.add(tangent_mesh);
+
+    let texture = images.add(Image::new_fill(
+        Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[255, 255, 255, 255],
+        TextureFormat::Rgba8UnormSrgb,
+    ));
+
+    let shader = asset_server.load::<RustGpuBuilderOutput, _>(SHADER_PATH);
+    stats.shader = shader.clone();
+
+    let texture_if = |textures: u32, bit: u32| (textures & bit != 0).then(|| texture.clone());
+
+    for alpha_mode in ALPHA_MODES {
+        for textures in 0..TEXTURE_COMBINATIONS {
+            for unlit in [false, true] {
+                for depth_bias in [0.0, 1.0] {
+                    for fog_enabled in [false, true] {
+                        let material = materials.add(RustGpu {
+                            base: StandardMaterial {
+                                base_color: Color::WHITE,
+                                base_color_texture: texture_if(textures, BASE_COLOR_TEXTURE),
+                                emissive_texture: texture_if(textures, EMISSIVE_TEXTURE),
+                                metallic_roughness_texture: texture_if(
+                                    textures,
+                                    METALLIC_ROUGHNESS_TEXTURE,
+                                ),
+                                occlusion_texture: texture_if(textures, OCCLUSION_TEXTURE),
+                                normal_map_texture: texture_if(textures, NORMAL_MAP_TEXTURE),
+                                unlit,
+                                depth_bias,
+                                fog_enabled,
+                                alpha_mode,
+                                ..default()
+                            },
+                            vertex_shader: Some(shader.clone()),
+                            fragment_shader: Some(shader.clone()),
+                            ..default()
+                        });
+
+                        let mesh = if textures & NORMAL_MAP_TEXTURE != 0 {
+                            tangent_mesh.clone()
+                        } else {
+                            mesh.clone()
+                        };
+
+                        // Lay entities out in a grid in front of the cameras
+                        let i = stats.materials as f32;
+                        commands.spawn((
+                            MaterialMeshBundle {
+                                mesh,
+                                material,
+                                transform: Transform::from_xyz(
+                                    (i % 48.0) - 24.0,
+                                    (i / 48.0).floor() - 16.0,
+                                    -40.0,
+                                ),
+                                ..default()
+                            },
+                            // Specialization happens at queue time, so every entity must be queued
+                            NoFrustumCulling,
+                        ));
+
+                        stats.materials += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    info!(
+        "Specializing {} materials for {} cameras",
+        stats.materials, stats.cameras
+    );
+}
+
+/// Distinct permutations in the entry points file at `path`, summed over its entry points
+fn count_exported_permutations(path: &Path) -> Result<usize, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let Value::Object(entry_points) =
+        serde_json::from_str::<Value>(&json).map_err(|e| e.to_string())?
+    else {
+        return Err("expected an object keyed by entry point".into());
+    };
+
+    Ok(entry_points
+        .values()
+        .map(|permutations| match permutations {
+            Value::Array(permutations) => permutations
+                .iter()
+                .map(Value::to_string)
+                .collect::<HashSet<_>>()
+                .len(),
+            _ => 1,
+        })
+        .sum())
+}
+
+/// Once the shader has loaded and every combination has had time to draw,
+/// report the totals and exit.
+///
+/// Exits early if the shader fails to load, as nothing would be specialized.
+fn report_and_exit(
+    asset_server: Res<AssetServer>,
+    stats: Res<BakeStats>,
+    mut frames: Local<u32>,
+    mut exit: EventWriter<AppExit>,
+) {
+    match asset_server.get_load_state(&stats.shader) {
+        LoadState::Loaded => (),
+        LoadState::Failed => {
+            error!("Failed to load {SHADER_PATH}, compile the rust-gpu workspace before baking permutations");
+            exit.send(AppExit);
+            return;
+        }
+        _ => return,
+    }
+
+    *frames += 1;
+    if *frames < SETTLE_FRAMES {
+        return;
+    }
+
+    let shader_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("assets")
+        .join(SHADER_PATH);
+
+    info!(
+        "Drew {} pipeline key combinations ({} materials x {} cameras)",
+        stats.materials * stats.cameras,
+        stats.materials,
+        stats.cameras,
+    );
+
+    match count_exported_permutations(Path::new(ENTRY_POINTS_PATH)) {
+        Ok(permutations) => {
+            info!("{ENTRY_POINTS_PATH} holds {permutations} distinct entry point permutations")
+        }
+        Err(e) => error!("Failed to read {ENTRY_POINTS_PATH}: {e}"),
+    }
+
+    info!(
+        "Recompile the rust-gpu workspace to build these permutations into {}; \
+         its size only reflects them after that rebuild",
+        shader_path.display(),
+    );
+
+    exit.send(AppExit);
+}
@@ -0,0 +1,92 @@
+use bevy::{
+    prelude::{
+        default, App, AssetPlugin, Camera2dBundle, ClearColor, Color, Commands, DefaultPlugins,
+        Msaa, PluginGroup, Res, ResMut, Vec2,
+    },
+    time::Time,
+};
+
+use bevy_rust_gpu::prelude::{RustGpu, RustGpuPlugin};
+use viewer::debug_draw::{DebugDrawPlugin, DebugShapes, DebugShapesMaterial};
+
+/// Workspace-relative path to SPIR-V shader
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
+
+const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
+
+fn main() {
+    let mut app = App::default();
+
+    // Add default plugins
+    app.add_plugins(DefaultPlugins.set(
+        // Configure the asset plugin to watch the workspace path for changes
+        AssetPlugin {
+            watch_for_changes: true,
+            ..default()
+        },
+    ));
+
+    // Add the Rust-GPU plugin
+    app.add_plugin(RustGpuPlugin::default());
+
+    // Setup debug drawing
+    app.add_plugin(DebugDrawPlugin {
+        shader: SHADER_PATH,
+    });
+    RustGpu::<DebugShapesMaterial>::export_to(ENTRY_POINTS_PATH);
+
+    // Set clear color to black
+    app.insert_resource(ClearColor(Color::BLACK));
+
+    app.insert_resource(Msaa::Off);
+
+    // Setup scene
+    app.add_startup_system(|mut commands: Commands| {
+        commands.spawn(Camera2dBundle::default());
+    });
+
+    app.add_system(draw_shapes);
+
+    // Run
+    app.run();
+}
+
+fn draw_shapes(time: Res<Time>, mut shapes: ResMut<DebugShapes>) {
+    let t = time.elapsed_seconds();
+
+    // Grid
+    for i in -5..=5 {
+        let offset = i as f32 * 80.0;
+        shapes.line(
+            Vec2::new(offset, -400.0),
+            Vec2::new(offset, 400.0),
+            1.0,
+            Color::rgba(1.0, 1.0, 1.0, 0.15),
+        );
+        shapes.line(
+            Vec2::new(-400.0, offset),
+            Vec2::new(400.0, offset),
+            1.0,
+            Color::rgba(1.0, 1.0, 1.0, 0.15),
+        );
+    }
+
+    // Orbiting shapes
+    let orbit = Vec2::new(t.cos(), t.sin()) * 200.0;
+    shapes.circle(Vec2::ZERO, 200.0, 2.0, Color::GRAY);
+    shapes.circle(orbit, 24.0, 0.0, Color::ORANGE);
+    shapes.arrow(Vec2::ZERO, orbit * 0.85, 4.0, 24.0, Color::CYAN);
+
+    shapes.rect(
+        Vec2::new(0.0, -300.0),
+        Vec2::new(200.0 + (t * 2.0).sin() * 100.0, 60.0),
+        3.0,
+        Color::LIME_GREEN,
+    );
+    shapes.rect(
+        Vec2::new(0.0, 300.0),
+        Vec2::new(120.0, 40.0),
+        0.0,
+        Color::PINK,
+    );
+}
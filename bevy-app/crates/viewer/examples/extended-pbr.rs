@@ -0,0 +1,144 @@
+use bevy::prelude::{
+    default,
+    shape::{Plane, Torus, UVSphere},
+    App, AssetPlugin, AssetServer, Assets, Camera3dBundle, Color, Commands, DefaultPlugins,
+    DirectionalLight, DirectionalLightBundle, MaterialMeshBundle, Mesh, PluginGroup, Res, ResMut,
+    StandardMaterial, Transform, Vec3,
+};
+
+use bevy_rust_gpu::{
+    prelude::{RustGpu, RustGpuMaterialPlugin, RustGpuPlugin},
+    RustGpuBuilderOutput,
+};
+use viewer::{
+    extended_pbr::{ExtendedStandardMaterial, HeightTint, NoiseTint},
+    specialize::RustGpuShadersPlugin,
+};
+
+/// Workspace-relative path to SPIR-V shader
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
+
+/// Workspace-relative path to entry points file
+const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
+
+fn main() {
+    let mut app = App::default();
+
+    // Add default plugins
+    app.add_plugins(DefaultPlugins.set(
+        // Configure the asset plugin to watch the workspace path for changes
+        AssetPlugin {
+            watch_for_changes: true,
+            ..default()
+        },
+    ));
+
+    // Add the Rust-GPU plugin
+    app.add_plugin(RustGpuPlugin::default());
+
+    // Setup a `RustGpu<ExtendedStandardMaterial<E>>` for each extension
+    app.add_plugin(RustGpuMaterialPlugin::<ExtendedStandardMaterial<NoiseTint>>::default());
+    app.add_plugin(RustGpuMaterialPlugin::<ExtendedStandardMaterial<HeightTint>>::default());
+    RustGpu::<ExtendedStandardMaterial<NoiseTint>>::export_to(ENTRY_POINTS_PATH);
+    RustGpu::<ExtendedStandardMaterial<HeightTint>>::export_to(ENTRY_POINTS_PATH);
+
+    // Check mesh attributes against the vertex entry points of the loaded shader
+    app.add_plugin(RustGpuShadersPlugin);
+
+    // Setup scene
+    app.add_startup_system(setup);
+
+    // Run
+    app.run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut noise_materials: ResMut<Assets<RustGpu<ExtendedStandardMaterial<NoiseTint>>>>,
+    mut height_materials: ResMut<Assets<RustGpu<ExtendedStandardMaterial<HeightTint>>>>,
+) {
+    // Spawn camera
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 3.0, 8.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    // Spawn light
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            illuminance: 10000.0,
+            ..default()
+        },
+        transform: Transform::IDENTITY.looking_at(Vec3::new(-0.5, -1.0, -0.7), Vec3::Y),
+        ..default()
+    });
+
+    let shader = asset_server.load::<RustGpuBuilderOutput, _>(SHADER_PATH);
+
+    // Spawn a metal sphere tinted by noise
+    commands.spawn(MaterialMeshBundle {
+        mesh: meshes.add(
+            UVSphere {
+                radius: 1.0,
+                ..default()
+            }
+            .into(),
+        ),
+        material: noise_materials.add(RustGpu {
+            base: ExtendedStandardMaterial::new(
+                StandardMaterial {
+                    base_color: Color::rgb(0.8, 0.8, 0.85),
+                    metallic: 1.0,
+                    perceptual_roughness: 0.3,
+                    ..default()
+                },
+                NoiseTint::default(),
+            ),
+            vertex_shader: Some(shader.clone()),
+            fragment_shader: Some(shader.clone()),
+            ..default()
+        }),
+        transform: Transform::from_xyz(-1.5, 1.0, 0.0),
+        ..default()
+    });
+
+    // Spawn a torus and ground plane shaded by height
+    let height_material = height_materials.add(RustGpu {
+        base: ExtendedStandardMaterial::new(
+            StandardMaterial {
+                perceptual_roughness: 0.2,
+                ..default()
+            },
+            HeightTint {
+                min_height: 0.0,
+                max_height: 2.0,
+                ..default()
+            },
+        ),
+        vertex_shader: Some(shader.clone()),
+        fragment_shader: Some(shader),
+        ..default()
+    });
+
+    commands.spawn(MaterialMeshBundle {
+        mesh: meshes.add(
+            Torus {
+                radius: 0.8,
+                ring_radius: 0.3,
+                ..default()
+            }
+            .into(),
+        ),
+        material: height_material.clone(),
+        transform: Transform::from_xyz(1.5, 1.0, 0.0).looking_at(Vec3::new(1.5, 1.0, 1.0), Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(MaterialMeshBundle {
+        mesh: meshes.add(Plane::from_size(10.0).into()),
+        material: height_material,
+        ..default()
+    });
+}
@@ -0,0 +1,438 @@
+use bevy::{
+    core_pipeline::core_3d::Opaque3d,
+    ecs::system::{
+        lifetimeless::{Read, SRes},
+        SystemParamItem,
+    },
+    pbr::{
+        MaterialPipeline, MaterialPipelineKey, MeshPipelineKey, MeshUniform, RenderMaterials,
+        SetMeshBindGroup, SetMeshViewBindGroup,
+    },
+    prelude::{
+        default, error, shape::Plane, App, AssetPlugin, AssetServer, Assets, Camera3dBundle,
+        ClearColor, Color, Commands, Component, DefaultPlugins, DirectionalLight,
+        DirectionalLightBundle, Entity, Handle, IntoSystemConfig, Material, Mesh, Msaa, PbrBundle,
+        Plugin, PluginGroup, Query, Res, ResMut, SpatialBundle, StandardMaterial, Transform, Vec2,
+        Vec3,
+    },
+    reflect::TypeUuid,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        mesh::{GpuBufferInfo, Indices, MeshVertexBufferLayout},
+        render_asset::RenderAssets,
+        render_phase::{
+            AddRenderCommand, DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult,
+            RenderPhase, SetItemPipeline, TrackedRenderPass,
+        },
+        render_resource::{
+            AsBindGroup, PipelineCache, PrimitiveTopology, RenderPipelineDescriptor, ShaderType,
+            SpecializedMeshPipelineError, SpecializedMeshPipelines,
+        },
+        view::{ExtractedView, NoFrustumCulling},
+        RenderApp, RenderSet,
+    },
+};
+
+use bevy_rust_gpu::{
+    prelude::{RustGpu, RustGpuMaterialPlugin, RustGpuPlugin},
+    EntryPoint, RustGpuBuilderOutput, RustGpuMaterial,
+};
+use viewer::specialize::{specialize_rust_gpu, RustGpuShadersPlugin};
+
+/// Workspace-relative path to SPIR-V shader
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
+
+const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
+
+/// Number of grass blades to scatter
+const BLADE_COUNT: u32 = 20000;
+
+/// Side length of the square field the blades are scattered over
+const FIELD_SIZE: f32 = 30.0;
+
+/// Marker type describing the `foliage::vertex_foliage` entrypoint from the shader crate
+pub enum VertexFoliage {}
+
+impl EntryPoint for VertexFoliage {
+    const NAME: &'static str = "foliage::vertex_foliage";
+}
+
+/// Marker type describing the `foliage::fragment_foliage` entrypoint from the shader crate
+pub enum FragmentFoliage {}
+
+impl EntryPoint for FragmentFoliage {
+    const NAME: &'static str = "foliage::fragment_foliage";
+}
+
+/// Placement of a single blade, as laid out in the instance storage buffer
+#[derive(Debug, Default, Copy, Clone, ShaderType)]
+pub struct FoliageInstance {
+    /// Root position, relative to the foliage entity
+    pub position: Vec3,
+    pub scale: f32,
+    /// Rotation around Y in radians
+    pub rotation: f32,
+    /// Offset for the blade's flutter, in radians
+    pub phase: f32,
+    /// Multiplier on wind displacement, so blades can differ in stiffness
+    pub bend: f32,
+    pub _padding: f32,
+}
+
+/// Foliage material tying together [`VertexFoliage`] and [`FragmentFoliage`]
+#[derive(Debug, Clone, AsBindGroup, TypeUuid)]
+#[uuid = "e2a7c41d-93b8-4f06-b5d2-7c18f9a36e4b"]
+pub struct FoliageMaterial {
+    #[uniform(0)]
+    pub base_color: Color,
+    #[uniform(0)]
+    pub tip_color: Color,
+    /// World-space XZ direction the wind blows towards
+    #[uniform(0)]
+    pub wind_direction: Vec2,
+    /// Tip displacement of a fully gusted blade, relative to its height
+    #[uniform(0)]
+    pub wind_strength: f32,
+    /// Speed at which gusts travel across the field, in world units per second
+    #[uniform(0)]
+    pub wind_speed: f32,
+    /// Frequency of the gust noise over the field
+    #[uniform(0)]
+    pub gust_scale: f32,
+    #[storage(1, read_only)]
+    pub instances: Vec<FoliageInstance>,
+}
+
+impl Default for FoliageMaterial {
+    fn default() -> Self {
+        FoliageMaterial {
+            base_color: Color::rgb(0.05, 0.2, 0.03),
+            tip_color: Color::rgb(0.5, 0.75, 0.2),
+            wind_direction: Vec2::new(1.0, 0.4),
+            wind_strength: 0.6,
+            wind_speed: 4.0,
+            gust_scale: 0.15,
+            instances: vec![FoliageInstance::default()],
+        }
+    }
+}
+
+impl Material for FoliageMaterial {
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        // Blades are flat, so draw both sides
+        descriptor.primitive.cull_mode = None;
+        specialize_rust_gpu::<Self>(descriptor, layout, &default())
+    }
+}
+
+impl RustGpuMaterial for FoliageMaterial {
+    type Vertex = VertexFoliage;
+    type Fragment = FragmentFoliage;
+}
+
+/// Tapered blade of unit height, as `(x, y)` pairs from root to tip
+const BLADE_VERTICES: [[f32; 2]; 7] = [
+    [-0.05, 0.0],
+    [0.05, 0.0],
+    [-0.04, 0.35],
+    [0.04, 0.35],
+    [-0.025, 0.7],
+    [0.025, 0.7],
+    [0.0, 1.0],
+];
+
+const BLADE_INDICES: [u32; 15] = [0, 1, 3, 0, 3, 2, 2, 3, 5, 2, 5, 4, 4, 5, 6];
+
+/// Single blade, drawn once per instance and placed by `vertex_foliage`
+fn blade_mesh() -> Mesh {
+    let positions = BLADE_VERTICES.map(|[x, y]| [x, y, 0.0]).to_vec();
+    let normals = BLADE_VERTICES.map(|_| [0.0, 0.0, 1.0]).to_vec();
+    let uvs = BLADE_VERTICES.map(|[x, y]| [x * 10.0 + 0.5, y]).to_vec();
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.set_indices(Some(Indices::U32(BLADE_INDICES.to_vec())));
+    mesh
+}
+
+/// Deterministic xorshift generator, so the field looks the same every run
+struct Scatter(u32);
+
+impl Scatter {
+    /// Uniform value in `[0, 1)`
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        (self.0 >> 8) as f32 / (1 << 24) as f32
+    }
+}
+
+fn scatter_instances(count: u32) -> Vec<FoliageInstance> {
+    let mut scatter = Scatter(0x9e37_79b9);
+    (0..count)
+        .map(|_| FoliageInstance {
+            position: Vec3::new(
+                (scatter.next() - 0.5) * FIELD_SIZE,
+                0.0,
+                (scatter.next() - 0.5) * FIELD_SIZE,
+            ),
+            scale: 0.6 + scatter.next() * 0.8,
+            rotation: scatter.next() * std::f32::consts::TAU,
+            phase: scatter.next() * std::f32::consts::TAU,
+            bend: 0.7 + scatter.next() * 0.6,
+            ..default()
+        })
+        .collect()
+}
+
+/// Blade mesh drawn once per instance of `material` in a single call.
+///
+/// Used in place of a `Handle<RustGpu<FoliageMaterial>>` so the default material queue
+/// doesn't also draw the mesh as a single blade.
+#[derive(Debug, Clone, Component)]
+pub struct FoliageField {
+    pub material: Handle<RustGpu<FoliageMaterial>>,
+    pub instance_count: u32,
+}
+
+impl ExtractComponent for FoliageField {
+    type Query = &'static Self;
+    type Filter = ();
+    type Out = Self;
+
+    fn extract_component(item: &Self) -> Option<Self> {
+        Some(item.clone())
+    }
+}
+
+/// Queues [`FoliageField`]s into the opaque phase with an instanced draw,
+/// reusing the pipeline and prepared bind groups of `RustGpuMaterialPlugin::<FoliageMaterial>`
+pub struct FoliagePlugin;
+
+impl Plugin for FoliagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(ExtractComponentPlugin::<FoliageField>::default());
+
+        app.sub_app_mut(RenderApp)
+            .add_render_command::<Opaque3d, DrawFoliage>()
+            .add_system(queue_foliage.in_set(RenderSet::Queue));
+    }
+}
+
+type FoliagePipeline = MaterialPipeline<RustGpu<FoliageMaterial>>;
+
+#[allow(clippy::too_many_arguments)]
+fn queue_foliage(
+    opaque_3d_draw_functions: Res<DrawFunctions<Opaque3d>>,
+    foliage_pipeline: Res<FoliagePipeline>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<FoliagePipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    msaa: Res<Msaa>,
+    meshes: Res<RenderAssets<Mesh>>,
+    materials: Res<RenderMaterials<RustGpu<FoliageMaterial>>>,
+    fields: Query<(Entity, &MeshUniform, &Handle<Mesh>, &FoliageField)>,
+    mut views: Query<(&ExtractedView, &mut RenderPhase<Opaque3d>)>,
+) {
+    let draw_foliage = opaque_3d_draw_functions.read().id::<DrawFoliage>();
+    let msaa_key = MeshPipelineKey::from_msaa_samples(msaa.samples());
+
+    for (view, mut opaque_phase) in &mut views {
+        let view_key = msaa_key | MeshPipelineKey::from_hdr(view.hdr);
+        let rangefinder = view.rangefinder3d();
+
+        for (entity, mesh_uniform, mesh_handle, field) in &fields {
+            let (Some(mesh), Some(material)) =
+                (meshes.get(mesh_handle), materials.get(&field.material))
+            else {
+                continue;
+            };
+
+            let key = MaterialPipelineKey {
+                mesh_key: view_key
+                    | MeshPipelineKey::from_primitive_topology(mesh.primitive_topology),
+                bind_group_data: material.key.clone(),
+            };
+
+            let pipeline =
+                match pipelines.specialize(&pipeline_cache, &foliage_pipeline, key, &mesh.layout) {
+                    Ok(pipeline) => pipeline,
+                    Err(e) => {
+                        error!("{e}");
+                        continue;
+                    }
+                };
+
+            opaque_phase.add(Opaque3d {
+                entity,
+                pipeline,
+                draw_function: draw_foliage,
+                distance: rangefinder.distance(&mesh_uniform.transform),
+            });
+        }
+    }
+}
+
+type DrawFoliage = (
+    SetItemPipeline,
+    SetMeshViewBindGroup<0>,
+    SetFoliageMaterialBindGroup<1>,
+    SetMeshBindGroup<2>,
+    DrawFoliageInstanced,
+);
+
+/// Binds the prepared bind group of a [`FoliageField`]'s material
+pub struct SetFoliageMaterialBindGroup<const I: usize>;
+
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetFoliageMaterialBindGroup<I> {
+    type Param = SRes<RenderMaterials<RustGpu<FoliageMaterial>>>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Read<FoliageField>;
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        field: &'w FoliageField,
+        materials: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(material) = materials.into_inner().get(&field.material) else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_bind_group(I, &material.bind_group, &[]);
+        RenderCommandResult::Success
+    }
+}
+
+/// Draws a [`FoliageField`]'s blade mesh once per instance
+pub struct DrawFoliageInstanced;
+
+impl<P: PhaseItem> RenderCommand<P> for DrawFoliageInstanced {
+    type Param = SRes<RenderAssets<Mesh>>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = (Read<Handle<Mesh>>, Read<FoliageField>);
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        (mesh_handle, field): (&'w Handle<Mesh>, &'w FoliageField),
+        meshes: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(gpu_mesh) = meshes.into_inner().get(mesh_handle) else {
+            return RenderCommandResult::Failure;
+        };
+
+        pass.set_vertex_buffer(0, gpu_mesh.vertex_buffer.slice(..));
+        match &gpu_mesh.buffer_info {
+            GpuBufferInfo::Indexed {
+                buffer,
+                index_format,
+                count,
+            } => {
+                pass.set_index_buffer(buffer.slice(..), 0, *index_format);
+                pass.draw_indexed(0..*count, 0, 0..field.instance_count);
+            }
+            GpuBufferInfo::NonIndexed { vertex_count } => {
+                pass.draw(0..*vertex_count, 0..field.instance_count);
+            }
+        }
+        RenderCommandResult::Success
+    }
+}
+
+fn main() {
+    let mut app = App::default();
+
+    // Add default plugins
+    app.add_plugins(DefaultPlugins.set(
+        // Configure the asset plugin to watch the workspace path for changes
+        AssetPlugin {
+            watch_for_changes: true,
+            ..default()
+        },
+    ));
+
+    // Add the Rust-GPU plugin
+    app.add_plugin(RustGpuPlugin::default());
+
+    // Setup `RustGpu<FoliageMaterial>`
+    app.add_plugin(RustGpuMaterialPlugin::<FoliageMaterial>::default());
+    RustGpu::<FoliageMaterial>::export_to(ENTRY_POINTS_PATH);
+
+    // Check mesh attributes against the vertex entry points of the loaded shader
+    app.add_plugin(RustGpuShadersPlugin);
+
+    // Draw foliage fields with a single instanced call
+    app.add_plugin(FoliagePlugin);
+
+    app.insert_resource(ClearColor(Color::rgb(0.6, 0.75, 0.9)));
+
+    app.insert_resource(Msaa::Off);
+
+    // Setup scene
+    app.add_startup_system(setup);
+
+    // Run
+    app.run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+    mut foliage_materials: ResMut<Assets<RustGpu<FoliageMaterial>>>,
+) {
+    // Spawn camera
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 4.0, 18.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    // Spawn lights
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            illuminance: 20000.0,
+            ..default()
+        },
+        transform: Transform::IDENTITY.looking_at(Vec3::new(-0.3, -1.0, -0.2), Vec3::Y),
+        ..default()
+    });
+
+    // Spawn ground
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Plane::from_size(FIELD_SIZE).into()),
+        material: standard_materials.add(Color::rgb(0.2, 0.15, 0.08).into()),
+        ..default()
+    });
+
+    // Spawn every blade as an instance of a single blade mesh
+    let shader = asset_server.load::<RustGpuBuilderOutput, _>(SHADER_PATH);
+
+    commands.spawn((
+        meshes.add(blade_mesh()),
+        FoliageField {
+            material: foliage_materials.add(RustGpu {
+                base: FoliageMaterial {
+                    instances: scatter_instances(BLADE_COUNT),
+                    ..default()
+                },
+                vertex_shader: Some(shader.clone()),
+                fragment_shader: Some(shader),
+                ..default()
+            }),
+            instance_count: BLADE_COUNT,
+        },
+        SpatialBundle::default(),
+        // Blades are placed on the GPU, so the bounds of a single blade are meaningless
+        NoFrustumCulling,
+    ));
+}
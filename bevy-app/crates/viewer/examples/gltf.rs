@@ -0,0 +1,75 @@
+use bevy::prelude::{
+    default, App, AssetPlugin, AssetServer, Camera3dBundle, Commands, DefaultPlugins,
+    DirectionalLight, DirectionalLightBundle, PluginGroup, Res, SceneBundle, StandardMaterial,
+    Transform, Vec3,
+};
+
+use bevy_rust_gpu::prelude::{RustGpu, RustGpuPlugin};
+use viewer::gltf::RustGpuGltfPlugin;
+
+/// Workspace-relative path to SPIR-V shader
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
+
+/// Workspace-relative path to entry points file
+const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
+
+fn main() {
+    let Some(scene) = std::env::args().nth(1) else {
+        eprintln!("Usage: cargo run --example gltf -- <workspace-relative path to .gltf or .glb>");
+        return;
+    };
+
+    let mut app = App::default();
+
+    // Add default plugins
+    app.add_plugins(DefaultPlugins.set(
+        // Configure the asset plugin to watch the workspace path for changes
+        AssetPlugin {
+            watch_for_changes: true,
+            ..default()
+        },
+    ));
+
+    // Add the Rust-GPU plugin
+    app.add_plugin(RustGpuPlugin::default());
+
+    // Draw loaded glTF scenes with `RustGpu<StandardMaterial>`
+    app.add_plugin(RustGpuGltfPlugin {
+        shader: SHADER_PATH,
+    });
+    RustGpu::<StandardMaterial>::export_to(ENTRY_POINTS_PATH);
+
+    // Setup scene
+    app.add_startup_system(
+        move |mut commands: Commands, asset_server: Res<AssetServer>| {
+            setup(&mut commands, &asset_server, &scene)
+        },
+    );
+
+    // Run
+    app.run();
+}
+
+fn setup(commands: &mut Commands, asset_server: &AssetServer, scene: &str) {
+    // Spawn camera
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 1.5, 4.0).looking_at(Vec3::Y * 0.5, Vec3::Y),
+        ..default()
+    });
+
+    // Spawn light
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            illuminance: 10000.0,
+            ..default()
+        },
+        transform: Transform::IDENTITY.looking_at(Vec3::new(-1.0, -2.0, -1.0), Vec3::Y),
+        ..default()
+    });
+
+    // Spawn the file's first scene
+    commands.spawn(SceneBundle {
+        scene: asset_server.load(format!("{scene}#Scene0")),
+        ..default()
+    });
+}
@@ -0,0 +1,283 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    prelude::{
+        default, shape::UVSphere, AmbientLight, App, AssetPlugin, AssetServer, Assets,
+        Camera3dBundle, ClearColor, Color, Commands, Component, DefaultPlugins, DirectionalLight,
+        DirectionalLightBundle, Material, MaterialMeshBundle, Mesh, Msaa, PluginGroup, Quat, Query,
+        Res, ResMut, Transform, Vec3, With,
+    },
+    reflect::TypeUuid,
+    render::render_resource::AsBindGroup,
+    time::Time,
+    utils::Uuid,
+};
+
+use bevy_rust_gpu::{
+    prelude::{RustGpu, RustGpuMaterialPlugin, RustGpuPlugin},
+    EntryPoint, EntryPointTypes, RustGpuBuilderOutput, RustGpuMaterial,
+};
+use shader_shared::procedural::{Marble, Pattern, Rust, Strata, Wood};
+use viewer::specialize::{specialize_rust_gpu, RustGpuShadersPlugin};
+
+/// Workspace-relative path to SPIR-V shader
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
+
+const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
+
+/// Marker type describing the `procedural::vertex_procedural` entrypoint from the shader crate
+pub enum VertexProcedural {}
+
+impl EntryPoint for VertexProcedural {
+    const NAME: &'static str = "procedural::vertex_procedural";
+}
+
+/// Marker type describing the `procedural::fragment_procedural` entrypoint from the shader crate,
+/// permutated over its `Pattern` type
+pub struct FragmentProcedural<T> {
+    pub _phantom: PhantomData<T>,
+}
+
+impl<T> EntryPoint for FragmentProcedural<T>
+where
+    T: Pattern + Send + Sync + 'static,
+{
+    const NAME: &'static str = "procedural::fragment_procedural";
+
+    fn types() -> EntryPointTypes {
+        vec![("Pattern".to_string(), T::NAME.to_string())]
+    }
+}
+
+/// Procedural material tying together [`VertexProcedural`] and [`FragmentProcedural`]
+#[derive(Debug, Copy, Clone, AsBindGroup)]
+pub struct ProceduralMaterial<T> {
+    /// Object-space frequency of the pattern
+    #[uniform(0)]
+    pub scale: f32,
+    /// Strength of the normal perturbation from the pattern's height field
+    #[uniform(0)]
+    pub bump_strength: f32,
+    pub _phantom: PhantomData<T>,
+}
+
+impl<T> Default for ProceduralMaterial<T> {
+    fn default() -> Self {
+        ProceduralMaterial {
+            scale: 1.0,
+            bump_strength: 0.02,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+// Each pattern is a distinct asset type, so needs its own UUID
+impl TypeUuid for ProceduralMaterial<Marble> {
+    const TYPE_UUID: Uuid = Uuid::from_u128(0x3c9a_51e2_7d04_4b8f_a6e1_0f52_d8c3_b701);
+}
+
+impl TypeUuid for ProceduralMaterial<Wood> {
+    const TYPE_UUID: Uuid = Uuid::from_u128(0x3c9a_51e2_7d04_4b8f_a6e1_0f52_d8c3_b702);
+}
+
+impl TypeUuid for ProceduralMaterial<Rust> {
+    const TYPE_UUID: Uuid = Uuid::from_u128(0x3c9a_51e2_7d04_4b8f_a6e1_0f52_d8c3_b703);
+}
+
+impl TypeUuid for ProceduralMaterial<Strata> {
+    const TYPE_UUID: Uuid = Uuid::from_u128(0x3c9a_51e2_7d04_4b8f_a6e1_0f52_d8c3_b704);
+}
+
+impl<T> Material for ProceduralMaterial<T>
+where
+    T: Pattern + Clone + Send + Sync + 'static,
+    Self: TypeUuid,
+{
+    fn specialize(
+        _pipeline: &bevy::pbr::MaterialPipeline<Self>,
+        descriptor: &mut bevy::render::render_resource::RenderPipelineDescriptor,
+        layout: &bevy::render::mesh::MeshVertexBufferLayout,
+        _key: bevy::pbr::MaterialPipelineKey<Self>,
+    ) -> Result<(), bevy::render::render_resource::SpecializedMeshPipelineError> {
+        specialize_rust_gpu::<Self>(descriptor, layout, &default())
+    }
+}
+
+impl<T> RustGpuMaterial for ProceduralMaterial<T>
+where
+    T: Pattern + Clone + Send + Sync + 'static,
+    Self: TypeUuid,
+{
+    type Vertex = VertexProcedural;
+    type Fragment = FragmentProcedural<T>;
+}
+
+#[derive(Debug, Default, Copy, Clone, Component)]
+pub struct Rotate;
+
+fn main() {
+    let mut app = App::default();
+
+    // Add default plugins
+    app.add_plugins(DefaultPlugins.set(
+        // Configure the asset plugin to watch the workspace path for changes
+        AssetPlugin {
+            watch_for_changes: true,
+            ..default()
+        },
+    ));
+
+    // Add the Rust-GPU plugin
+    app.add_plugin(RustGpuPlugin::default());
+
+    // Setup a `RustGpu<ProceduralMaterial<T>>` for each pattern
+    app.add_plugin(RustGpuMaterialPlugin::<ProceduralMaterial<Marble>>::default());
+    app.add_plugin(RustGpuMaterialPlugin::<ProceduralMaterial<Wood>>::default());
+    app.add_plugin(RustGpuMaterialPlugin::<ProceduralMaterial<Rust>>::default());
+    app.add_plugin(RustGpuMaterialPlugin::<ProceduralMaterial<Strata>>::default());
+    RustGpu::<ProceduralMaterial<Marble>>::export_to(ENTRY_POINTS_PATH);
+    RustGpu::<ProceduralMaterial<Wood>>::export_to(ENTRY_POINTS_PATH);
+    RustGpu::<ProceduralMaterial<Rust>>::export_to(ENTRY_POINTS_PATH);
+    RustGpu::<ProceduralMaterial<Strata>>::export_to(ENTRY_POINTS_PATH);
+
+    // Check mesh attributes against the vertex entry points of the loaded shader
+    app.add_plugin(RustGpuShadersPlugin);
+
+    app.insert_resource(ClearColor(Color::rgb(0.3, 0.35, 0.45)));
+
+    // Sky-tinted ambient light, read by `fragment_procedural` from the lights uniform
+    app.insert_resource(AmbientLight {
+        color: Color::rgb(0.3, 0.35, 0.45),
+        brightness: 0.8,
+    });
+
+    app.insert_resource(Msaa::Off);
+
+    // Setup scene
+    app.add_startup_system(setup);
+
+    app.add_system(
+        |time: Res<Time>, mut query: Query<&mut Transform, With<Rotate>>| {
+            for mut transform in query.iter_mut() {
+                transform.rotate_y(time.delta_seconds() * 0.3);
+            }
+        },
+    );
+
+    // Run
+    app.run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut marble_materials: ResMut<Assets<RustGpu<ProceduralMaterial<Marble>>>>,
+    mut wood_materials: ResMut<Assets<RustGpu<ProceduralMaterial<Wood>>>>,
+    mut rust_materials: ResMut<Assets<RustGpu<ProceduralMaterial<Rust>>>>,
+    mut strata_materials: ResMut<Assets<RustGpu<ProceduralMaterial<Strata>>>>,
+) {
+    // Spawn camera
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 1.5, 9.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    // Spawn lights
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            illuminance: 20000.0,
+            ..default()
+        },
+        transform: Transform::IDENTITY.looking_at(-Vec3::new(0.4, 1.0, 0.6), Vec3::Y),
+        ..default()
+    });
+
+    // Load mesh and shader
+    let mesh = meshes.add(
+        UVSphere {
+            radius: 1.0,
+            sectors: 64,
+            stacks: 32,
+        }
+        .into(),
+    );
+
+    let shader = asset_server.load::<RustGpuBuilderOutput, _>(SHADER_PATH);
+
+    // Spawn one sphere per pattern
+    let transform =
+        |x: f32| Transform::from_xyz(x, 0.0, 0.0).with_rotation(Quat::from_rotation_z(0.3));
+
+    commands.spawn((
+        MaterialMeshBundle {
+            mesh: mesh.clone(),
+            material: marble_materials.add(RustGpu {
+                base: ProceduralMaterial {
+                    bump_strength: 0.005,
+                    ..default()
+                },
+                vertex_shader: Some(shader.clone()),
+                fragment_shader: Some(shader.clone()),
+                ..default()
+            }),
+            transform: transform(-3.75),
+            ..default()
+        },
+        Rotate,
+    ));
+
+    commands.spawn((
+        MaterialMeshBundle {
+            mesh: mesh.clone(),
+            material: wood_materials.add(RustGpu {
+                base: ProceduralMaterial {
+                    scale: 0.8,
+                    ..default()
+                },
+                vertex_shader: Some(shader.clone()),
+                fragment_shader: Some(shader.clone()),
+                ..default()
+            }),
+            transform: transform(-1.25),
+            ..default()
+        },
+        Rotate,
+    ));
+
+    commands.spawn((
+        MaterialMeshBundle {
+            mesh: mesh.clone(),
+            material: rust_materials.add(RustGpu {
+                base: ProceduralMaterial {
+                    bump_strength: 0.08,
+                    ..default()
+                },
+                vertex_shader: Some(shader.clone()),
+                fragment_shader: Some(shader.clone()),
+                ..default()
+            }),
+            transform: transform(1.25),
+            ..default()
+        },
+        Rotate,
+    ));
+
+    commands.spawn((
+        MaterialMeshBundle {
+            mesh,
+            material: strata_materials.add(RustGpu {
+                base: ProceduralMaterial {
+                    bump_strength: 0.05,
+                    ..default()
+                },
+                vertex_shader: Some(shader.clone()),
+                fragment_shader: Some(shader),
+                ..default()
+            }),
+            transform: transform(3.75),
+            ..default()
+        },
+        Rotate,
+    ));
+}
@@ -18,6 +18,7 @@ use bevy_rust_gpu::{
     prelude::{RustGpu, RustGpuMaterialPlugin, RustGpuPlugin},
     EntryPoint, RustGpuBuilderOutput, RustGpuMaterial, RustGpuMaterial2dPlugin,
 };
+use viewer::specialize::{specialize_rust_gpu, RustGpuShadersPlugin};
 
 /// Workspace-relative path to SPIR-V shader
 const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
@@ -46,11 +47,11 @@ pub struct ExampleMaterial {}
 impl Material2d for ExampleMaterial {
     fn specialize(
         descriptor: &mut bevy::render::render_resource::RenderPipelineDescriptor,
-        _layout: &bevy::render::mesh::MeshVertexBufferLayout,
+        layout: &bevy::render::mesh::MeshVertexBufferLayout,
         _key: bevy::sprite::Material2dKey<Self>,
     ) -> Result<(), bevy::render::render_resource::SpecializedMeshPipelineError> {
         descriptor.primitive.cull_mode = None;
-        Ok(())
+        specialize_rust_gpu::<Self>(descriptor, layout, &default())
     }
 }
 
@@ -81,6 +82,9 @@ fn main() {
     app.add_plugin(RustGpuMaterial2dPlugin::<ExampleMaterial>::default());
     RustGpu::<ExampleMaterial>::export_to(ENTRY_POINTS_PATH);
 
+    // Check mesh attributes against the vertex entry points of the loaded shader
+    app.add_plugin(RustGpuShadersPlugin);
+
     // Set clear color to black
     app.insert_resource(ClearColor(Color::BLACK));
 
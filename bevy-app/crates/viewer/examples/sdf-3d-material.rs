@@ -1,10 +1,10 @@
 use std::marker::PhantomData;
 
 use bevy::{
-    core_pipeline::prepass::DepthPrepass,
+    core_pipeline::{bloom::BloomSettings, prepass::DepthPrepass},
     prelude::{
-        default, shape::Cube, AlphaMode, App, AssetPlugin, AssetServer, Assets, Camera3dBundle,
-        ClearColor, Color, Commands, Component, DefaultPlugins, DirectionalLight,
+        default, shape::Cube, AlphaMode, App, AssetPlugin, AssetServer, Assets, Camera,
+        Camera3dBundle, ClearColor, Color, Commands, Component, DefaultPlugins, DirectionalLight,
         DirectionalLightBundle, Material, MaterialMeshBundle, Mesh, Msaa, PluginGroup, PointLight,
         PointLightBundle, Quat, Query, Res, ResMut, Transform, Vec3, With,
     },
@@ -24,12 +24,19 @@ use rust_gpu_sdf::prelude::{
     FieldOperator, IsosurfaceOp, Position, Raycast, ScaleUv, Sphere, SphereTraceLipschitz,
     UvTangent,
 };
+use viewer::{
+    prepass::RustGpuPrepassMaterial,
+    specialize::{specialize_rust_gpu, specialize_rust_gpu_prepass, RustGpuShadersPlugin},
+};
 
 /// Workspace-relative path to SPIR-V shader
 const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
 
 const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
 
+/// Command-line flag rendering through an HDR camera with bloom
+const HDR_FLAG: &'static str = "--hdr";
+
 /// Marker type describing the `vertex_warp` entrypoint from the shader crate
 pub enum VertexSdf3d {}
 
@@ -64,10 +71,27 @@ where
 }
 
 /// Example RustGpu material tying together [`VertexWarp`] and [`FragmentNormal`]
-#[derive(Debug, Default, Copy, Clone, AsBindGroup)]
+#[derive(Debug, Copy, Clone, AsBindGroup)]
 pub struct Sdf3dMaterial<T> {
     pub sdf: T,
     pub alpha_mode: AlphaMode,
+    /// Emissive radiance of the glow along the SDF's silhouette,
+    /// bright enough by default to bloom on HDR cameras
+    #[uniform(0)]
+    pub glow_intensity: f32,
+}
+
+impl<T> Default for Sdf3dMaterial<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Sdf3dMaterial {
+            sdf: default(),
+            alpha_mode: default(),
+            glow_intensity: 400.0,
+        }
+    }
 }
 
 impl<T> TypeUuid for Sdf3dMaterial<T> {
@@ -91,11 +115,12 @@ where
     fn specialize(
         _pipeline: &bevy::pbr::MaterialPipeline<Self>,
         descriptor: &mut bevy::render::render_resource::RenderPipelineDescriptor,
-        _layout: &bevy::render::mesh::MeshVertexBufferLayout,
+        layout: &bevy::render::mesh::MeshVertexBufferLayout,
         _key: bevy::pbr::MaterialPipelineKey<Self>,
     ) -> Result<(), bevy::render::render_resource::SpecializedMeshPipelineError> {
         descriptor.primitive.cull_mode = None;
-        Ok(())
+        specialize_rust_gpu::<Self>(descriptor, layout, &default())?;
+        specialize_rust_gpu_prepass::<Self>(descriptor, layout)
     }
 
     fn alpha_mode(&self) -> AlphaMode {
@@ -121,6 +146,50 @@ where
     type Fragment = FragmentSdf3d<T>;
 }
 
+/// Marker type describing the `fragment_sdf_3d_prepass` entrypoint from the shader crate
+pub struct FragmentSdf3dPrepass<T> {
+    pub _phantom: PhantomData<T>,
+}
+
+impl<T> EntryPoint for FragmentSdf3dPrepass<T>
+where
+    T: Named
+        + Field<AttrDistance<Vec3>>
+        + Field<AttrNormal<Vec3>>
+        + Field<AttrTangent<Vec3>>
+        + Field<AttrUv<Vec3>>
+        + Field<AttrColor<Vec3>>
+        + Field<Raycast>
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    const NAME: &'static str = "fragment_sdf_3d_prepass";
+
+    fn types() -> EntryPointTypes {
+        vec![("Sdf".to_string(), T::name())]
+    }
+}
+
+impl<T> RustGpuPrepassMaterial for Sdf3dMaterial<T>
+where
+    T: Named
+        + Field<AttrDistance<Vec3>>
+        + Field<AttrNormal<Vec3>>
+        + Field<AttrTangent<Vec3>>
+        + Field<AttrUv<Vec3>>
+        + Field<AttrColor<Vec3>>
+        + Field<Raycast>
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    type PrepassVertex = VertexSdf3d;
+    type PrepassFragment = FragmentSdf3dPrepass<T>;
+}
+
 #[derive(Debug, Default, Copy, Clone, Component)]
 pub struct Rotate;
 
@@ -145,6 +214,9 @@ fn main() {
     app.add_plugin(RustGpuMaterialPlugin::<Sdf3dMaterial<Sdf>>::default());
     RustGpu::<Sdf3dMaterial<Sdf>>::export_to(ENTRY_POINTS_PATH);
 
+    // Check mesh attributes against `vertex_sdf_3d`, and draw the raymarched surface into the depth prepass
+    app.add_plugin(RustGpuShadersPlugin);
+
     // Set clear color to black
     app.insert_resource(ClearColor(Color::BLACK));
 
@@ -173,8 +245,20 @@ fn setup(
     mut meshes: ResMut<Assets<Mesh>>,
     mut sdf_3d_materials: ResMut<Assets<RustGpu<Sdf3dMaterial<Sdf>>>>,
 ) {
-    // Spawn camera
-    commands.spawn((Camera3dBundle::default(), DepthPrepass::default()));
+    // Spawn camera, rendering to HDR with bloom when passed `--hdr`
+    let hdr = std::env::args().any(|arg| arg == HDR_FLAG);
+
+    let mut camera = commands.spawn((
+        Camera3dBundle {
+            camera: Camera { hdr, ..default() },
+            ..default()
+        },
+        DepthPrepass::default(),
+    ));
+
+    if hdr {
+        camera.insert(BloomSettings::default());
+    }
 
     // Spawn lights
     commands.spawn(DirectionalLightBundle {
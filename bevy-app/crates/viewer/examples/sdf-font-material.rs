@@ -0,0 +1,206 @@
+use std::path::Path;
+
+use bevy::{
+    prelude::{
+        default, App, AssetPlugin, AssetServer, Assets, Camera2dBundle, ClearColor, Color,
+        Commands, DefaultPlugins, Handle, Image, Mesh, Msaa, PluginGroup, Res, ResMut, Transform,
+        Vec2,
+    },
+    reflect::TypeUuid,
+    render::render_resource::AsBindGroup,
+    sprite::{Material2d, MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+use bevy_rust_gpu::{
+    prelude::{RustGpu, RustGpuPlugin},
+    EntryPoint, RustGpuBuilderOutput, RustGpuMaterial, RustGpuMaterial2dPlugin,
+};
+use viewer::msdf::{MsdfAtlas, MsdfAtlasSettings};
+use viewer::specialize::{specialize_rust_gpu, RustGpuShadersPlugin};
+
+/// Workspace-relative path to SPIR-V shader
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
+
+const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
+
+/// Path to the font baked into the MSDF atlas, relative to the viewer crate
+const FONT_PATH: &'static str = "assets/fonts/FiraSans-Medium.ttf";
+
+/// Marker type describing the `vertex_uv_2d` entrypoint from the shader crate
+pub enum VertexUv2d {}
+
+impl EntryPoint for VertexUv2d {
+    const NAME: &'static str = "vertex_uv_2d";
+}
+
+/// Marker type describing the `msdf_text::fragment_msdf_text` entrypoint from the shader crate
+pub enum FragmentMsdfText {}
+
+impl EntryPoint for FragmentMsdfText {
+    const NAME: &'static str = "msdf_text::fragment_msdf_text";
+}
+
+/// RustGpu material drawing glyph quads from an MSDF atlas, tying together [`VertexUv2d`] and [`FragmentMsdfText`]
+#[derive(Debug, Clone, AsBindGroup, TypeUuid)]
+#[uuid = "5b1e4c0e-3f0a-4f57-9d8c-2a4d7e1f9b36"]
+pub struct MsdfTextMaterial {
+    #[texture(0)]
+    #[sampler(1)]
+    pub atlas: Handle<Image>,
+    #[uniform(2)]
+    pub fill_color: Color,
+    #[uniform(2)]
+    pub outline_color: Color,
+    #[uniform(2)]
+    pub shadow_color: Color,
+    /// Shadow offset in atlas texels
+    #[uniform(2)]
+    pub shadow_offset: Vec2,
+    /// Atlas dimensions in texels
+    #[uniform(2)]
+    pub atlas_size: Vec2,
+    /// Distance range the atlas was baked with, in atlas texels
+    #[uniform(2)]
+    pub distance_range: f32,
+    /// Outline width in atlas texels
+    #[uniform(2)]
+    pub outline_width: f32,
+    /// Shadow edge softness in atlas texels
+    #[uniform(2)]
+    pub shadow_softness: f32,
+}
+
+impl Default for MsdfTextMaterial {
+    fn default() -> Self {
+        MsdfTextMaterial {
+            atlas: default(),
+            fill_color: Color::WHITE,
+            outline_color: Color::BLACK,
+            shadow_color: Color::rgba(0.0, 0.0, 0.0, 0.5),
+            shadow_offset: Vec2::new(2.0, -2.0),
+            atlas_size: Vec2::ONE,
+            distance_range: 1.0,
+            outline_width: 0.0,
+            shadow_softness: 1.0,
+        }
+    }
+}
+
+impl Material2d for MsdfTextMaterial {
+    fn specialize(
+        descriptor: &mut bevy::render::render_resource::RenderPipelineDescriptor,
+        layout: &bevy::render::mesh::MeshVertexBufferLayout,
+        _key: bevy::sprite::Material2dKey<Self>,
+    ) -> Result<(), bevy::render::render_resource::SpecializedMeshPipelineError> {
+        specialize_rust_gpu::<Self>(descriptor, layout, &default())
+    }
+}
+
+impl RustGpuMaterial for MsdfTextMaterial {
+    type Vertex = VertexUv2d;
+    type Fragment = FragmentMsdfText;
+}
+
+fn main() {
+    let mut app = App::default();
+
+    // Add default plugins
+    app.add_plugins(DefaultPlugins.set(
+        // Configure the asset plugin to watch the workspace path for changes
+        AssetPlugin {
+            watch_for_changes: true,
+            ..default()
+        },
+    ));
+
+    // Add the Rust-GPU plugin
+    app.add_plugin(RustGpuPlugin::default());
+
+    // Setup `RustGpu<MsdfTextMaterial>`
+    app.add_plugin(RustGpuMaterial2dPlugin::<MsdfTextMaterial>::default());
+    RustGpu::<MsdfTextMaterial>::export_to(ENTRY_POINTS_PATH);
+
+    // Check mesh attributes against the vertex entry points of the loaded shader
+    app.add_plugin(RustGpuShadersPlugin);
+
+    // Set clear color to a mid-tone so both outline and shadow are visible
+    app.insert_resource(ClearColor(Color::rgb(0.3, 0.4, 0.5)));
+
+    app.insert_resource(Msaa::Off);
+
+    // Setup scene
+    app.add_startup_system(setup);
+
+    // Run
+    app.run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    mut text_materials: ResMut<Assets<RustGpu<MsdfTextMaterial>>>,
+) {
+    // Spawn camera
+    commands.spawn(Camera2dBundle::default());
+
+    // Bake atlas
+    let font_path = Path::new(env!("CARGO_MANIFEST_DIR")).join(FONT_PATH);
+    let font = std::fs::read(&font_path)
+        .unwrap_or_else(|e| panic!("Failed to read font {}: {e}", font_path.display()));
+    let atlas = MsdfAtlas::from_ttf(&font, &MsdfAtlasSettings::default())
+        .unwrap_or_else(|e| panic!("Failed to bake font atlas: {e}"));
+
+    // Load meshes and shader
+    let small = Mesh2dHandle::from(meshes.add(atlas.text_mesh("Multi-channel SDF text", 48.0)));
+    let large = Mesh2dHandle::from(meshes.add(atlas.text_mesh("rust-gpu", 160.0)));
+
+    let shader = asset_server.load::<RustGpuBuilderOutput, _>(SHADER_PATH);
+
+    // Create materials
+    let base = MsdfTextMaterial {
+        atlas_size: atlas.size,
+        distance_range: atlas.distance_range,
+        atlas: images.add(atlas.image),
+        ..default()
+    };
+
+    let plain = text_materials.add(RustGpu {
+        vertex_shader: Some(shader.clone()),
+        fragment_shader: Some(shader.clone()),
+        base: MsdfTextMaterial {
+            shadow_color: Color::NONE,
+            ..base.clone()
+        },
+        ..default()
+    });
+
+    let outlined = text_materials.add(RustGpu {
+        vertex_shader: Some(shader.clone()),
+        fragment_shader: Some(shader),
+        base: MsdfTextMaterial {
+            fill_color: Color::ORANGE,
+            outline_width: 2.0,
+            shadow_offset: Vec2::new(3.0, -3.0),
+            shadow_softness: 4.0,
+            ..base
+        },
+        ..default()
+    });
+
+    // Spawn text
+    commands.spawn(MaterialMesh2dBundle {
+        transform: Transform::from_xyz(-300.0, -150.0, 0.0),
+        mesh: small,
+        material: plain,
+        ..default()
+    });
+
+    commands.spawn(MaterialMesh2dBundle {
+        transform: Transform::from_xyz(-320.0, 0.0, 0.0),
+        mesh: large,
+        material: outlined,
+        ..default()
+    });
+}
@@ -0,0 +1,263 @@
+use std::f32::consts::TAU;
+
+use bevy::{
+    prelude::{
+        default, shape::Quad, App, AssetPlugin, AssetServer, Assets, Camera2dBundle, ClearColor,
+        Color, Commands, Component, DefaultPlugins, Handle, Image, Mesh, Msaa, PluginGroup, Query,
+        Res, ResMut, Transform, Vec2, With,
+    },
+    reflect::TypeUuid,
+    render::render_resource::{AsBindGroup, Extent3d, TextureDimension, TextureFormat},
+    sprite::{Material2d, MaterialMesh2dBundle, Mesh2dHandle},
+    time::Time,
+};
+
+use bevy_rust_gpu::{
+    prelude::{RustGpu, RustGpuPlugin},
+    EntryPoint, RustGpuBuilderOutput, RustGpuMaterial, RustGpuMaterial2dPlugin,
+};
+use viewer::sdf_bake::{bake_sdf, SdfBakeSettings};
+use viewer::specialize::{specialize_rust_gpu, RustGpuShadersPlugin};
+
+/// Workspace-relative path to SPIR-V shader
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
+
+const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
+
+/// Width and height of the generated sprite in texels
+const SPRITE_SIZE: u32 = 128;
+
+/// Marker type describing the `vertex_uv_2d` entrypoint from the shader crate
+pub enum VertexUv2d {}
+
+impl EntryPoint for VertexUv2d {
+    const NAME: &'static str = "vertex_uv_2d";
+}
+
+/// Marker type describing the `sdf_sprite::fragment_sdf_sprite` entrypoint from the shader crate
+pub enum FragmentSdfSprite {}
+
+impl EntryPoint for FragmentSdfSprite {
+    const NAME: &'static str = "sdf_sprite::fragment_sdf_sprite";
+}
+
+/// RustGpu material drawing a baked SDF sprite, tying together [`VertexUv2d`] and [`FragmentSdfSprite`]
+#[derive(Debug, Clone, AsBindGroup, TypeUuid)]
+#[uuid = "0e6f3d9a-8c41-4b5e-a7f2-6d1c93b8e4a5"]
+pub struct SdfSpriteMaterial {
+    #[texture(0)]
+    #[sampler(1)]
+    pub texture: Handle<Image>,
+    #[uniform(2)]
+    pub outline_color: Color,
+    #[uniform(2)]
+    pub glow_color: Color,
+    #[uniform(2)]
+    pub dissolve_color: Color,
+    /// Distance range the texture was baked with, in texels
+    #[uniform(2)]
+    pub spread: f32,
+    /// Outline width in texels
+    #[uniform(2)]
+    pub outline_width: f32,
+    /// Distance past the outline over which glow fades out, in texels
+    #[uniform(2)]
+    pub glow_radius: f32,
+    /// Dissolve progress, from 0 (intact) to 1 (gone)
+    #[uniform(2)]
+    pub dissolve: f32,
+    /// Frequency of the dissolve noise over the sprite's UV space
+    #[uniform(2)]
+    pub dissolve_scale: f32,
+    /// Width of the burning edge that leads the dissolve, in noise units
+    #[uniform(2)]
+    pub dissolve_edge_width: f32,
+}
+
+impl Default for SdfSpriteMaterial {
+    fn default() -> Self {
+        SdfSpriteMaterial {
+            texture: default(),
+            outline_color: Color::BLACK,
+            glow_color: Color::NONE,
+            dissolve_color: Color::ORANGE_RED,
+            spread: 1.0,
+            outline_width: 0.0,
+            glow_radius: 0.0,
+            dissolve: 0.0,
+            dissolve_scale: 16.0,
+            dissolve_edge_width: 0.1,
+        }
+    }
+}
+
+impl Material2d for SdfSpriteMaterial {
+    fn specialize(
+        descriptor: &mut bevy::render::render_resource::RenderPipelineDescriptor,
+        layout: &bevy::render::mesh::MeshVertexBufferLayout,
+        _key: bevy::sprite::Material2dKey<Self>,
+    ) -> Result<(), bevy::render::render_resource::SpecializedMeshPipelineError> {
+        specialize_rust_gpu::<Self>(descriptor, layout, &default())
+    }
+}
+
+impl RustGpuMaterial for SdfSpriteMaterial {
+    type Vertex = VertexUv2d;
+    type Fragment = FragmentSdfSprite;
+}
+
+/// Marker for sprites whose material animates its dissolve parameter
+#[derive(Debug, Default, Copy, Clone, Component)]
+pub struct Dissolve;
+
+fn main() {
+    let mut app = App::default();
+
+    // Add default plugins
+    app.add_plugins(DefaultPlugins.set(
+        // Configure the asset plugin to watch the workspace path for changes
+        AssetPlugin {
+            watch_for_changes: true,
+            ..default()
+        },
+    ));
+
+    // Add the Rust-GPU plugin
+    app.add_plugin(RustGpuPlugin::default());
+
+    // Setup `RustGpu<SdfSpriteMaterial>`
+    app.add_plugin(RustGpuMaterial2dPlugin::<SdfSpriteMaterial>::default());
+    RustGpu::<SdfSpriteMaterial>::export_to(ENTRY_POINTS_PATH);
+
+    // Check mesh attributes against the vertex entry points of the loaded shader
+    app.add_plugin(RustGpuShadersPlugin);
+
+    // Set clear color to a mid-tone so outline and glow are both visible
+    app.insert_resource(ClearColor(Color::rgb(0.2, 0.25, 0.3)));
+
+    app.insert_resource(Msaa::Off);
+
+    // Setup scene
+    app.add_startup_system(setup);
+
+    // Animate dissolve
+    app.add_system(
+        |time: Res<Time>,
+         query: Query<&Handle<RustGpu<SdfSpriteMaterial>>, With<Dissolve>>,
+         mut materials: ResMut<Assets<RustGpu<SdfSpriteMaterial>>>| {
+            for handle in query.iter() {
+                if let Some(material) = materials.get_mut(handle) {
+                    material.base.dissolve = (time.elapsed_seconds() * 0.5).sin() * 0.5 + 0.5;
+                }
+            }
+        },
+    );
+
+    // Run
+    app.run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut sprite_materials: ResMut<Assets<RustGpu<SdfSpriteMaterial>>>,
+) {
+    // Spawn camera
+    commands.spawn(Camera2dBundle::default());
+
+    let shader = asset_server.load::<RustGpuBuilderOutput, _>(SHADER_PATH);
+
+    // Bake the sprite
+    let settings = SdfBakeSettings::default();
+    let baked = bake_sdf(&star_sprite(SPRITE_SIZE), &settings).expect("Sprite is 8-bit RGBA");
+    let size = baked.size();
+    let texture = images.add(baked);
+
+    // Create materials
+    let outlined = sprite_materials.add(RustGpu {
+        vertex_shader: Some(shader.clone()),
+        fragment_shader: Some(shader.clone()),
+        base: SdfSpriteMaterial {
+            texture: texture.clone(),
+            spread: settings.spread,
+            outline_width: 4.0,
+            outline_color: Color::WHITE,
+            glow_color: Color::CYAN,
+            glow_radius: 12.0,
+            ..default()
+        },
+        ..default()
+    });
+
+    let dissolving = sprite_materials.add(RustGpu {
+        vertex_shader: Some(shader.clone()),
+        fragment_shader: Some(shader),
+        base: SdfSpriteMaterial {
+            texture,
+            spread: settings.spread,
+            outline_width: 2.0,
+            ..default()
+        },
+        ..default()
+    });
+
+    // Spawn one quad per material
+    let mesh = Mesh2dHandle::from(meshes.add(Quad { size, flip: false }.into()));
+
+    let materials = [(outlined, false), (dissolving, true)];
+    let count = materials.len();
+    for (i, (material, dissolve)) in materials.into_iter().enumerate() {
+        let x = (i as f32 - (count - 1) as f32 * 0.5) * (size.x + 32.0);
+
+        let mut entity = commands.spawn(MaterialMesh2dBundle {
+            transform: Transform::from_xyz(x, 0.0, 0.0),
+            mesh: mesh.clone(),
+            material,
+            ..default()
+        });
+
+        if dissolve {
+            entity.insert(Dissolve);
+        }
+    }
+}
+
+/// Five-pointed star on a transparent background, shaded from gold at its center to red at its tips
+fn star_sprite(size: u32) -> Image {
+    const POINTS: f32 = 5.0;
+    const INNER_RADIUS: f32 = 0.45;
+
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let p = (Vec2::new(x as f32, y as f32) + 0.5) / size as f32 * 2.0 - 1.0;
+            let p = Vec2::new(p.x, -p.y);
+
+            // Star radius at this angle, from a tip pointing up to the inner vertices either side of it
+            let sector = TAU / POINTS;
+            let angle = p.x.atan2(p.y).rem_euclid(sector) / sector;
+            let t = 1.0 - (angle * 2.0 - 1.0).abs();
+            let radius = INNER_RADIUS / (INNER_RADIUS + (1.0 - INNER_RADIUS) * t);
+
+            let r = p.length();
+            let color = Color::rgb(1.0, 0.85, 0.2) * (1.0 - r) + Color::rgb(0.9, 0.2, 0.1) * r;
+            let alpha = if r <= radius { 255 } else { 0 };
+
+            let [r, g, b, _] = color.as_rgba_u8();
+            data.extend([r, g, b, alpha]);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
@@ -0,0 +1,179 @@
+use bevy::{
+    prelude::{
+        default, shape::Quad, App, AssetPlugin, AssetServer, Assets, Camera2dBundle, Commands,
+        DefaultPlugins, Handle, Input, Mesh, MouseButton, Msaa, PluginGroup, Query, Res, ResMut,
+        Vec2, Vec4, With,
+    },
+    reflect::TypeUuid,
+    render::render_resource::AsBindGroup,
+    sprite::{Material2d, MaterialMesh2dBundle, Mesh2dHandle},
+    window::{PrimaryWindow, Window},
+};
+
+use bevy_rust_gpu::{
+    prelude::{RustGpu, RustGpuPlugin},
+    EntryPoint, RustGpuBuilderOutput, RustGpuMaterial, RustGpuMaterial2dPlugin,
+};
+use viewer::specialize::{specialize_rust_gpu, RustGpuShadersPlugin};
+
+/// Workspace-relative path to SPIR-V shader
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
+
+const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
+
+/// Marker type describing the `vertex_sdf_2d` entrypoint from the shader crate
+pub enum VertexSdf2d {}
+
+impl EntryPoint for VertexSdf2d {
+    const NAME: &'static str = "vertex_sdf_2d";
+}
+
+/// Marker type describing the `shadertoy::fragment_shadertoy` entrypoint from the shader crate
+pub enum FragmentShadertoy {}
+
+impl EntryPoint for FragmentShadertoy {
+    const NAME: &'static str = "shadertoy::fragment_shadertoy";
+}
+
+/// Fullscreen Shadertoy-style material tying together [`VertexSdf2d`] and [`FragmentShadertoy`]
+#[derive(Debug, Default, Copy, Clone, AsBindGroup, TypeUuid)]
+#[uuid = "7d3c2a9e-51f4-4c86-b0e1-9a6f2d4c8b17"]
+pub struct ShadertoyMaterial {
+    /// Shadertoy's `iMouse`, in physical pixels from the bottom-left of the window
+    #[uniform(0)]
+    pub mouse: Vec4,
+}
+
+impl Material2d for ShadertoyMaterial {
+    fn specialize(
+        descriptor: &mut bevy::render::render_resource::RenderPipelineDescriptor,
+        layout: &bevy::render::mesh::MeshVertexBufferLayout,
+        _key: bevy::sprite::Material2dKey<Self>,
+    ) -> Result<(), bevy::render::render_resource::SpecializedMeshPipelineError> {
+        descriptor.primitive.cull_mode = None;
+        specialize_rust_gpu::<Self>(descriptor, layout, &default())
+    }
+}
+
+impl RustGpuMaterial for ShadertoyMaterial {
+    type Vertex = VertexSdf2d;
+    type Fragment = FragmentShadertoy;
+}
+
+fn main() {
+    let mut app = App::default();
+
+    // Add default plugins
+    app.add_plugins(DefaultPlugins.set(
+        // Configure the asset plugin to watch the workspace path for changes
+        AssetPlugin {
+            watch_for_changes: true,
+            ..default()
+        },
+    ));
+
+    // Add the Rust-GPU plugin
+    app.add_plugin(RustGpuPlugin::default());
+
+    // Setup `RustGpu<ShadertoyMaterial>`
+    app.add_plugin(RustGpuMaterial2dPlugin::<ShadertoyMaterial>::default());
+    RustGpu::<ShadertoyMaterial>::export_to(ENTRY_POINTS_PATH);
+
+    // Check mesh attributes against the vertex entry points of the loaded shader
+    app.add_plugin(RustGpuShadersPlugin);
+
+    app.insert_resource(Msaa::Off);
+
+    // Setup scene
+    app.add_startup_system(setup);
+
+    app.add_system(update_mouse);
+
+    // Run
+    app.run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut shadertoy_materials: ResMut<Assets<RustGpu<ShadertoyMaterial>>>,
+) {
+    // Spawn camera
+    commands.spawn(Camera2dBundle::default());
+
+    // Create a quad large enough to cover the screen,
+    // since `vertex_sdf_2d` passes its positions through as clip coordinates
+    let mesh = meshes.add(
+        Quad {
+            size: Vec2::ONE * 2.0,
+            flip: false,
+        }
+        .into(),
+    );
+
+    let shader = asset_server.load::<RustGpuBuilderOutput, _>(SHADER_PATH);
+
+    // Create material
+    let material = shadertoy_materials.add(RustGpu {
+        vertex_shader: Some(shader.clone()),
+        fragment_shader: Some(shader),
+        ..default()
+    });
+
+    // Spawn fullscreen quad
+    commands.spawn(MaterialMesh2dBundle {
+        mesh: Mesh2dHandle::from(mesh),
+        material,
+        ..default()
+    });
+}
+
+/// Update `iMouse` following Shadertoy's conventions
+fn update_mouse(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    buttons: Res<Input<MouseButton>>,
+    query: Query<&Handle<RustGpu<ShadertoyMaterial>>>,
+    mut shadertoy_materials: ResMut<Assets<RustGpu<ShadertoyMaterial>>>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    let cursor = window.physical_cursor_position();
+
+    for handle in query.iter() {
+        let Some(material) = shadertoy_materials.get(handle) else {
+            continue;
+        };
+
+        let mut mouse = material.base.mouse;
+
+        if let Some(cursor) = cursor {
+            if buttons.pressed(MouseButton::Left) {
+                mouse.x = cursor.x;
+                mouse.y = cursor.y;
+            }
+
+            if buttons.just_pressed(MouseButton::Left) {
+                mouse.z = cursor.x;
+                mouse.w = cursor.y;
+            }
+        }
+
+        if !buttons.pressed(MouseButton::Left) {
+            mouse.z = -mouse.z.abs();
+        }
+
+        if !buttons.just_pressed(MouseButton::Left) {
+            mouse.w = -mouse.w.abs();
+        }
+
+        // Only touch the asset on change to avoid re-preparing its bind group every frame
+        if mouse != material.base.mouse {
+            if let Some(material) = shadertoy_materials.get_mut(handle) {
+                material.base.mouse = mouse;
+            }
+        }
+    }
+}
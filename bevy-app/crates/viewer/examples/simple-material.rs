@@ -1,22 +1,35 @@
 use bevy::{
+    pbr::{MaterialPipeline, MaterialPipelineKey},
     prelude::{
         default, shape::Cube, App, AssetPlugin, AssetServer, Assets, Camera3dBundle, Color,
-        Commands, DefaultPlugins, DirectionalLight, DirectionalLightBundle, Material,
-        MaterialMeshBundle, Mesh, PluginGroup, PointLight, PointLightBundle, Quat, Res, ResMut,
-        Transform, Vec3,
+        Commands, Component, DefaultPlugins, DirectionalLight, DirectionalLightBundle, Handle,
+        Material, MaterialMeshBundle, Mesh, PluginGroup, PointLight, PointLightBundle, Quat, Query,
+        Res, ResMut, Transform, Vec3, With,
     },
     reflect::TypeUuid,
-    render::render_resource::AsBindGroup,
+    render::{
+        mesh::MeshVertexBufferLayout,
+        render_resource::{AsBindGroup, RenderPipelineDescriptor, SpecializedMeshPipelineError},
+    },
+    time::Time,
 };
 
 use bevy_rust_gpu::{
     prelude::{RustGpu, RustGpuMaterialPlugin, RustGpuPlugin},
     EntryPoint, RustGpuMaterial,
 };
+use shader_shared::warp::Warp;
+use viewer::{
+    deformed_bounds::{DeformedBoundsPlugin, VertexDeformation},
+    prepass::{FragmentNormalPrepass, RustGpuPrepassMaterial, VertexWarpPrepass},
+    specialize::{
+        specialize_rust_gpu, specialize_rust_gpu_prepass, EntryPointOverride, EntryPointOverrides,
+        RustGpuShadersPlugin,
+    },
+};
 
 /// Workspace-relative path to SPIR-V shader
-const SHADER_PATH: &'static str =
-    "rust-gpu/shader.rust-gpu.msgpack";
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
 
 const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
 
@@ -34,18 +47,101 @@ impl EntryPoint for FragmentNormal {
     const NAME: &'static str = "fragment_normal";
 }
 
-/// Example RustGpu material tying together [`VertexWarp`] and [`FragmentNormal`]
-#[derive(Debug, Default, Copy, Clone, AsBindGroup, TypeUuid)]
+/// Example RustGpu material tying together [`VertexWarp`] and [`FragmentNormal`],
+/// unless overridden per instance
+#[derive(Debug, Clone, AsBindGroup, TypeUuid)]
 #[uuid = "cbeff76a-27e9-42c8-bb17-73e81ba62a36"]
-pub struct ExampleMaterial {}
+#[bind_group_data(ExampleMaterialKey)]
+pub struct ExampleMaterial {
+    /// Per-axis weight of the deformation
+    #[uniform(0)]
+    pub axis: Vec3,
+    #[uniform(0)]
+    pub amplitude: f32,
+    /// Oscillation rate in radians per second
+    #[uniform(0)]
+    pub frequency: f32,
+    /// Oscillation offset in radians
+    #[uniform(0)]
+    pub phase: f32,
+    /// Entry points replacing [`VertexWarp`] or [`FragmentNormal`] for this instance
+    pub entry_points: EntryPointOverrides,
+}
+
+/// Pipeline key for [`ExampleMaterial`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExampleMaterialKey {
+    entry_points: EntryPointOverrides,
+    /// Specializes again once the overrides' builder outputs load
+    entry_points_loaded: bool,
+}
+
+impl From<&ExampleMaterial> for ExampleMaterialKey {
+    fn from(material: &ExampleMaterial) -> Self {
+        ExampleMaterialKey {
+            entry_points: material.entry_points.clone(),
+            entry_points_loaded: material.entry_points.is_loaded(),
+        }
+    }
+}
+
+impl Default for ExampleMaterial {
+    fn default() -> Self {
+        Warp::default().into()
+    }
+}
 
-impl Material for ExampleMaterial {}
+impl From<Warp> for ExampleMaterial {
+    fn from(warp: Warp) -> Self {
+        ExampleMaterial {
+            axis: warp.axis,
+            amplitude: warp.amplitude,
+            frequency: warp.frequency,
+            phase: warp.phase,
+            entry_points: EntryPointOverrides::default(),
+        }
+    }
+}
+
+impl From<&ExampleMaterial> for Warp {
+    fn from(material: &ExampleMaterial) -> Self {
+        Warp {
+            axis: material.axis,
+            amplitude: material.amplitude,
+            frequency: material.frequency,
+            phase: material.phase,
+        }
+    }
+}
+
+impl Material for ExampleMaterial {
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        specialize_rust_gpu::<Self>(descriptor, layout, &key.bind_group_data.entry_points)?;
+        specialize_rust_gpu_prepass::<Self>(descriptor, layout)
+    }
+}
 
 impl RustGpuMaterial for ExampleMaterial {
     type Vertex = VertexWarp;
     type Fragment = FragmentNormal;
 }
 
+impl RustGpuPrepassMaterial for ExampleMaterial {
+    type PrepassVertex = VertexWarpPrepass;
+    type PrepassFragment = FragmentNormalPrepass;
+}
+
+impl VertexDeformation for ExampleMaterial {
+    fn deform_position(&self, position: Vec3, time: f32) -> Vec3 {
+        Warp::from(self).position(position, time)
+    }
+}
+
 fn main() {
     let mut app = App::default();
 
@@ -65,9 +161,17 @@ fn main() {
     app.add_plugin(RustGpuMaterialPlugin::<ExampleMaterial>::default());
     RustGpu::<ExampleMaterial>::export_to(ENTRY_POINTS_PATH);
 
+    // Keep culling bounds in sync with `vertex_warp`
+    app.add_plugin(DeformedBoundsPlugin::<ExampleMaterial>::default());
+
+    // Make builder output modules available to entry point overrides, vertex input checks and the prepass
+    app.add_plugin(RustGpuShadersPlugin);
+
     // Setup scene
     app.add_startup_system(setup);
 
+    app.add_system(pulse_amplitude);
+
     // Run
     app.run();
 }
@@ -106,19 +210,80 @@ fn setup(
     let mesh = meshes.add(Cube { size: 1.0 }.into());
     let shader = asset_server.load(SHADER_PATH);
 
-    // Create material
-    let material = example_materials.add(RustGpu {
-        vertex_shader: Some(shader.clone()),
-        fragment_shader: Some(shader),
-        ..default()
-    });
+    let rotation = Quat::from_axis_angle(Vec3::new(-1.0, 1.0, 1.0), -45.0).normalize();
 
-    // Spawn example cubes
-    commands.spawn(MaterialMeshBundle {
-        transform: Transform::from_xyz(0.0, 0.0, -6.0)
-            .with_rotation(Quat::from_axis_angle(Vec3::new(-1.0, 1.0, 1.0), -45.0).normalize()),
-        mesh,
-        material,
-        ..default()
-    });
+    // Spawn example cubes, each with its own warp parameters
+    for (x, base, pulse) in [
+        (-3.75, ExampleMaterial::default(), false),
+        (
+            -1.25,
+            ExampleMaterial {
+                axis: Vec3::X,
+                frequency: 3.0,
+                ..default()
+            },
+            false,
+        ),
+        (
+            1.25,
+            ExampleMaterial {
+                phase: std::f32::consts::PI,
+                ..default()
+            },
+            true,
+        ),
+        // Same material type, drawn with the prepass fragment's remapped normal instead
+        (
+            3.75,
+            ExampleMaterial {
+                entry_points: EntryPointOverrides {
+                    fragment: Some(EntryPointOverride::new(
+                        shader.clone(),
+                        FragmentNormalPrepass::NAME,
+                    )),
+                    ..default()
+                },
+                ..default()
+            },
+            false,
+        ),
+    ] {
+        // Leave overridden stages unset, so `RustGpu` doesn't replace them with its own
+        let vertex_shader = base.entry_points.vertex.is_none().then(|| shader.clone());
+        let fragment_shader = base.entry_points.fragment.is_none().then(|| shader.clone());
+
+        let material = example_materials.add(RustGpu {
+            base,
+            vertex_shader,
+            fragment_shader,
+            ..default()
+        });
+
+        let mut entity = commands.spawn(MaterialMeshBundle {
+            transform: Transform::from_xyz(x, 0.0, -6.0).with_rotation(rotation),
+            mesh: mesh.clone(),
+            material,
+            ..default()
+        });
+
+        if pulse {
+            entity.insert(PulseAmplitude);
+        }
+    }
+}
+
+/// Marker for cubes whose warp amplitude is animated from the CPU
+#[derive(Debug, Default, Copy, Clone, Component)]
+pub struct PulseAmplitude;
+
+fn pulse_amplitude(
+    time: Res<Time>,
+    query: Query<&Handle<RustGpu<ExampleMaterial>>, With<PulseAmplitude>>,
+    mut example_materials: ResMut<Assets<RustGpu<ExampleMaterial>>>,
+) {
+    for handle in query.iter() {
+        if let Some(material) = example_materials.get_mut(handle) {
+            material.base.amplitude = (time.elapsed_seconds() * 0.5).sin().abs();
+        }
+    }
 }
@@ -0,0 +1,123 @@
+use bevy::{
+    prelude::{
+        default,
+        shape::{Plane, UVSphere},
+        App, AssetPlugin, Assets, Camera3dBundle, Color, Commands, Component, DefaultPlugins,
+        DirectionalLight, DirectionalLightBundle, Mesh, Msaa, PbrBundle, PluginGroup, Quat, Query,
+        Res, ResMut, StandardMaterial, Transform, Vec3, With,
+    },
+    time::Time,
+};
+
+use bevy_rust_gpu::prelude::{RustGpu, RustGpuPlugin};
+use viewer::sky::{SkyMaterial, SkyPlugin};
+
+/// Workspace-relative path to SPIR-V shader
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
+
+const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
+
+/// Length of a full day, in seconds
+const DAY_LENGTH: f32 = 40.0;
+
+/// Marker for the light whose direction the sky follows
+#[derive(Debug, Default, Copy, Clone, Component)]
+pub struct Sun;
+
+fn main() {
+    let mut app = App::default();
+
+    // Add default plugins
+    app.add_plugins(DefaultPlugins.set(
+        // Configure the asset plugin to watch the workspace path for changes
+        AssetPlugin {
+            watch_for_changes: true,
+            ..default()
+        },
+    ));
+
+    // Add the Rust-GPU plugin
+    app.add_plugin(RustGpuPlugin::default());
+
+    // Setup the sky, also lighting the scene's ambient term
+    app.add_plugin(SkyPlugin {
+        shader: SHADER_PATH,
+        ambient_strength: Some(1.0),
+    });
+    RustGpu::<SkyMaterial>::export_to(ENTRY_POINTS_PATH);
+
+    app.insert_resource(Msaa::Off);
+
+    // Setup scene
+    app.add_startup_system(setup);
+
+    app.add_system(rotate_sun);
+
+    // Run
+    app.run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Spawn camera
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 1.5, 8.0).looking_at(Vec3::Y, Vec3::Y),
+        ..default()
+    });
+
+    // Spawn sun
+    commands.spawn((
+        DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                illuminance: 20000.0,
+                ..default()
+            },
+            ..default()
+        },
+        Sun,
+    ));
+
+    // Spawn ground
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Plane::from_size(40.0).into()),
+        material: materials.add(Color::rgb(0.4, 0.4, 0.35).into()),
+        ..default()
+    });
+
+    // Spawn spheres of varying roughness to show the sky's ambient contribution
+    let sphere = meshes.add(
+        UVSphere {
+            radius: 0.8,
+            ..default()
+        }
+        .into(),
+    );
+
+    for i in 0..4 {
+        commands.spawn(PbrBundle {
+            mesh: sphere.clone(),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgb(0.9, 0.9, 0.9),
+                perceptual_roughness: 0.2 + i as f32 * 0.25,
+                ..default()
+            }),
+            transform: Transform::from_xyz((i as f32 - 1.5) * 2.0, 0.8, 0.0),
+            ..default()
+        });
+    }
+}
+
+/// Swing the sun through a full day, along an arc tilted towards the camera
+fn rotate_sun(time: Res<Time>, mut query: Query<&mut Transform, With<Sun>>) {
+    // Start just before sunrise
+    let elevation = time.elapsed_seconds() / DAY_LENGTH * std::f32::consts::TAU - 0.1;
+    let to_sun = Quat::from_rotation_x(0.3) * Vec3::new(-elevation.cos(), elevation.sin(), 0.0);
+
+    for mut transform in query.iter_mut() {
+        // The arc never lines up with Z, so it makes a stable up vector
+        *transform = Transform::IDENTITY.looking_at(-to_sun, Vec3::Z);
+    }
+}
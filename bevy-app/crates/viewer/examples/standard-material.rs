@@ -1,11 +1,12 @@
 use bevy::prelude::{
     default, shape::Cube, App, AssetPlugin, AssetServer, Assets, Camera3dBundle, Color, Commands,
-    DefaultPlugins, DirectionalLight, DirectionalLightBundle, MaterialMeshBundle, Mesh,
+    DefaultPlugins, DirectionalLight, DirectionalLightBundle, KeyCode, MaterialMeshBundle, Mesh,
     PluginGroup, PointLight, PointLightBundle, Quat, Res, ResMut, StandardMaterial, Transform,
     Vec3,
 };
 
 use bevy_rust_gpu::prelude::{RustGpu, RustGpuMaterialPlugin, RustGpuPlugin};
+use viewer::material_swap::MaterialSwapPlugin;
 
 /// Workspace-relative path to SPIR-V shader
 const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
@@ -32,6 +33,12 @@ fn main() {
     app.add_plugin(RustGpuMaterialPlugin::<StandardMaterial>::default());
     RustGpu::<StandardMaterial>::export_to(ENTRY_POINTS_PATH);
 
+    // Press space to swap every entity between WGSL and Rust StandardMaterial
+    app.add_plugin(MaterialSwapPlugin {
+        shader: SHADER_PATH,
+        key: KeyCode::Space,
+    });
+
     // Setup scene
     app.add_startup_system(setup);
 
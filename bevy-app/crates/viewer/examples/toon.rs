@@ -0,0 +1,326 @@
+use bevy::{
+    pbr::{MaterialPipeline, MaterialPipelineKey},
+    prelude::{
+        default,
+        shape::{Capsule, Plane, Torus, UVSphere},
+        App, AssetPlugin, AssetServer, Assets, BuildChildren, Camera3dBundle, ClearColor, Color,
+        Commands, Component, DefaultPlugins, DirectionalLight, DirectionalLightBundle, Handle,
+        Material, MaterialMeshBundle, Mesh, Msaa, PluginGroup, Quat, Query, Res, ResMut, Transform,
+        Vec3, With,
+    },
+    reflect::TypeUuid,
+    render::{
+        mesh::MeshVertexBufferLayout,
+        render_resource::{
+            AsBindGroup, Face, RenderPipelineDescriptor, SpecializedMeshPipelineError,
+        },
+    },
+    time::Time,
+};
+
+use bevy_rust_gpu::{
+    prelude::{RustGpu, RustGpuMaterialPlugin, RustGpuPlugin},
+    EntryPoint, RustGpuBuilderOutput, RustGpuMaterial,
+};
+use viewer::specialize::{specialize_rust_gpu, RustGpuShadersPlugin};
+
+/// Workspace-relative path to SPIR-V shader
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
+
+const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
+
+/// Marker type describing the `toon::vertex_toon` entrypoint from the shader crate
+pub enum VertexToon {}
+
+impl EntryPoint for VertexToon {
+    const NAME: &'static str = "toon::vertex_toon";
+}
+
+/// Marker type describing the `toon::fragment_toon` entrypoint from the shader crate
+pub enum FragmentToon {}
+
+impl EntryPoint for FragmentToon {
+    const NAME: &'static str = "toon::fragment_toon";
+}
+
+/// Marker type describing the `toon::vertex_toon_outline` entrypoint from the shader crate
+pub enum VertexToonOutline {}
+
+impl EntryPoint for VertexToonOutline {
+    const NAME: &'static str = "toon::vertex_toon_outline";
+}
+
+/// Marker type describing the `toon::fragment_toon_outline` entrypoint from the shader crate
+pub enum FragmentToonOutline {}
+
+impl EntryPoint for FragmentToonOutline {
+    const NAME: &'static str = "toon::fragment_toon_outline";
+}
+
+/// Cel shading pass tying together [`VertexToon`] and [`FragmentToon`]
+#[derive(Debug, Copy, Clone, AsBindGroup, TypeUuid)]
+#[uuid = "8d4f2b6e-1a93-4c57-b0e8-6f3d9a21c7e4"]
+pub struct ToonMaterial {
+    /// Color of fully lit bands
+    #[uniform(0)]
+    pub base_color: Color,
+    /// Color of the unlit band
+    #[uniform(0)]
+    pub shadow_color: Color,
+    /// Number of bands between `shadow_color` and `base_color`
+    #[uniform(0)]
+    pub ramp_steps: u32,
+    /// Cosine of the angle around the reflected light inside which the hard highlight shows,
+    /// with `1.0` disabling it
+    #[uniform(0)]
+    pub specular_threshold: f32,
+}
+
+impl Default for ToonMaterial {
+    fn default() -> Self {
+        ToonMaterial {
+            base_color: Color::rgb(0.9, 0.45, 0.3),
+            shadow_color: Color::rgb(0.2, 0.1, 0.25),
+            ramp_steps: 3,
+            specular_threshold: 0.97,
+        }
+    }
+}
+
+impl Material for ToonMaterial {
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        specialize_rust_gpu::<Self>(descriptor, layout, &default())
+    }
+}
+
+impl RustGpuMaterial for ToonMaterial {
+    type Vertex = VertexToon;
+    type Fragment = FragmentToon;
+}
+
+/// Inverted-hull outline pass tying together [`VertexToonOutline`] and [`FragmentToonOutline`]
+#[derive(Debug, Copy, Clone, AsBindGroup, TypeUuid)]
+#[uuid = "2e7b9c14-5f08-4d3a-8c6b-a1f04e93d258"]
+pub struct ToonOutlineMaterial {
+    #[uniform(0)]
+    pub outline_color: Color,
+    /// Outline thickness in pixels
+    #[uniform(0)]
+    pub outline_width: f32,
+}
+
+impl Default for ToonOutlineMaterial {
+    fn default() -> Self {
+        ToonOutlineMaterial {
+            outline_color: Color::rgb(0.05, 0.03, 0.08),
+            outline_width: 3.0,
+        }
+    }
+}
+
+impl Material for ToonOutlineMaterial {
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        // Only the far side of the extruded hull shows, framing the cel-shaded pass
+        descriptor.primitive.cull_mode = Some(Face::Front);
+        specialize_rust_gpu::<Self>(descriptor, layout, &default())
+    }
+}
+
+impl RustGpuMaterial for ToonOutlineMaterial {
+    type Vertex = VertexToonOutline;
+    type Fragment = FragmentToonOutline;
+}
+
+#[derive(Debug, Default, Copy, Clone, Component)]
+pub struct Rotate;
+
+fn main() {
+    let mut app = App::default();
+
+    // Add default plugins
+    app.add_plugins(DefaultPlugins.set(
+        // Configure the asset plugin to watch the workspace path for changes
+        AssetPlugin {
+            watch_for_changes: true,
+            ..default()
+        },
+    ));
+
+    // Add the Rust-GPU plugin
+    app.add_plugin(RustGpuPlugin::default());
+
+    // Setup one material per pass
+    app.add_plugin(RustGpuMaterialPlugin::<ToonMaterial>::default());
+    app.add_plugin(RustGpuMaterialPlugin::<ToonOutlineMaterial>::default());
+    RustGpu::<ToonMaterial>::export_to(ENTRY_POINTS_PATH);
+    RustGpu::<ToonOutlineMaterial>::export_to(ENTRY_POINTS_PATH);
+
+    // Check mesh attributes against the vertex entry points of the loaded shader
+    app.add_plugin(RustGpuShadersPlugin);
+
+    app.insert_resource(ClearColor(Color::rgb(0.95, 0.9, 0.8)));
+
+    app.insert_resource(Msaa::Off);
+
+    // Setup scene
+    app.add_startup_system(setup);
+
+    app.add_system(
+        |time: Res<Time>, mut query: Query<&mut Transform, With<Rotate>>| {
+            for mut transform in query.iter_mut() {
+                transform.rotate_y(time.delta_seconds() * 0.5);
+            }
+        },
+    );
+
+    // Run
+    app.run();
+}
+
+/// Spawn `mesh` cel-shaded, with a child entity drawing its outline from the same mesh
+fn spawn_toon(
+    commands: &mut Commands,
+    mesh: Handle<Mesh>,
+    transform: Transform,
+    toon: Handle<RustGpu<ToonMaterial>>,
+    outline: Handle<RustGpu<ToonOutlineMaterial>>,
+) {
+    commands
+        .spawn((
+            MaterialMeshBundle {
+                mesh: mesh.clone(),
+                material: toon,
+                transform,
+                ..default()
+            },
+            Rotate,
+        ))
+        .with_children(|parent| {
+            parent.spawn(MaterialMeshBundle {
+                mesh,
+                material: outline,
+                ..default()
+            });
+        });
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut toon_materials: ResMut<Assets<RustGpu<ToonMaterial>>>,
+    mut outline_materials: ResMut<Assets<RustGpu<ToonOutlineMaterial>>>,
+) {
+    // Spawn camera
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 2.5, 8.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    // Spawn lights, which drive the cel-shaded bands
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            color: Color::rgb(1.0, 0.95, 0.85),
+            illuminance: 20000.0,
+            ..default()
+        },
+        transform: Transform::IDENTITY.looking_at(Vec3::new(-0.6, -1.0, -0.4), Vec3::Y),
+        ..default()
+    });
+
+    let shader = asset_server.load::<RustGpuBuilderOutput, _>(SHADER_PATH);
+
+    let outline = outline_materials.add(RustGpu {
+        vertex_shader: Some(shader.clone()),
+        fragment_shader: Some(shader.clone()),
+        ..default()
+    });
+
+    // Spawn shapes with differing ramps and colors, sharing one outline
+    let shapes = [
+        (
+            meshes.add(
+                UVSphere {
+                    radius: 0.9,
+                    ..default()
+                }
+                .into(),
+            ),
+            ToonMaterial::default(),
+        ),
+        (
+            meshes.add(
+                Torus {
+                    radius: 0.8,
+                    ring_radius: 0.3,
+                    ..default()
+                }
+                .into(),
+            ),
+            ToonMaterial {
+                base_color: Color::rgb(0.35, 0.7, 0.9),
+                shadow_color: Color::rgb(0.1, 0.15, 0.35),
+                ramp_steps: 2,
+                ..default()
+            },
+        ),
+        (
+            meshes.add(
+                Capsule {
+                    radius: 0.5,
+                    depth: 0.8,
+                    ..default()
+                }
+                .into(),
+            ),
+            ToonMaterial {
+                base_color: Color::rgb(0.55, 0.85, 0.4),
+                shadow_color: Color::rgb(0.1, 0.25, 0.15),
+                ramp_steps: 4,
+                specular_threshold: 1.0,
+            },
+        ),
+    ];
+
+    for (i, (mesh, toon)) in shapes.into_iter().enumerate() {
+        let toon = toon_materials.add(RustGpu {
+            base: toon,
+            vertex_shader: Some(shader.clone()),
+            fragment_shader: Some(shader.clone()),
+            ..default()
+        });
+
+        let transform = Transform::from_xyz((i as f32 - 1.0) * 2.6, 0.0, 0.0)
+            .with_rotation(Quat::from_rotation_x(0.4));
+
+        spawn_toon(&mut commands, mesh, transform, toon, outline.clone());
+    }
+
+    // Spawn ground, cel-shaded without an outline
+    commands.spawn(MaterialMeshBundle {
+        mesh: meshes.add(Plane::from_size(20.0).into()),
+        material: toon_materials.add(RustGpu {
+            base: ToonMaterial {
+                base_color: Color::rgb(0.9, 0.85, 0.7),
+                shadow_color: Color::rgb(0.45, 0.4, 0.45),
+                ramp_steps: 2,
+                specular_threshold: 1.0,
+            },
+            vertex_shader: Some(shader.clone()),
+            fragment_shader: Some(shader),
+            ..default()
+        }),
+        transform: Transform::from_xyz(0.0, -1.2, 0.0),
+        ..default()
+    });
+}
@@ -0,0 +1,124 @@
+use bevy::prelude::{
+    default, shape::Cube, App, AssetPlugin, AssetServer, Assets, Camera3dBundle, Color, Commands,
+    DefaultPlugins, DirectionalLight, DirectionalLightBundle, MaterialMeshBundle, Mesh,
+    PluginGroup, PointLight, PointLightBundle, Quat, Res, ResMut, StandardMaterial, Transform,
+    Vec3,
+};
+
+use bevy_rust_gpu::prelude::{RustGpu, RustGpuMaterialPlugin, RustGpuPlugin};
+use viewer::{
+    deformed_bounds::DeformedBoundsPlugin,
+    pbr_vertex::{StandardMaterialWithVertex, VertexWarpPbr},
+    specialize::RustGpuShadersPlugin,
+};
+
+/// Workspace-relative path to SPIR-V shader
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
+
+/// Workspace-relative path to entry points file
+const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
+
+type WarpedStandardMaterial = StandardMaterialWithVertex<VertexWarpPbr>;
+
+fn main() {
+    let mut app = App::default();
+
+    // Add default plugins
+    app.add_plugins(DefaultPlugins.set(
+        // Configure the asset plugin to watch the workspace path for changes
+        AssetPlugin {
+            watch_for_changes: true,
+            ..default()
+        },
+    ));
+
+    // Add the Rust-GPU plugin
+    app.add_plugin(RustGpuPlugin::default());
+
+    // Setup `RustGpu<StandardMaterialWithVertex<VertexWarpPbr>>`
+    app.add_plugin(RustGpuMaterialPlugin::<WarpedStandardMaterial>::default());
+    RustGpu::<WarpedStandardMaterial>::export_to(ENTRY_POINTS_PATH);
+
+    // Keep culling bounds in sync with `vertex_warp_pbr`
+    app.add_plugin(DeformedBoundsPlugin::<WarpedStandardMaterial>::default());
+
+    // Report meshes lacking attributes that `vertex_warp_pbr` reads
+    app.add_plugin(RustGpuShadersPlugin);
+
+    // Setup scene
+    app.add_startup_system(setup);
+
+    // Run
+    app.run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<RustGpu<WarpedStandardMaterial>>>,
+) {
+    // Spawn camera
+    commands.spawn(Camera3dBundle::default());
+
+    // Spawn lights
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            illuminance: 5000.0,
+            ..default()
+        },
+        transform: Transform::IDENTITY.looking_at(Vec3::new(0.0, -1.0, -1.0), Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(PointLightBundle {
+        point_light: PointLight {
+            intensity: 400.0,
+            range: 4.0,
+            color: Color::BLUE,
+            ..default()
+        },
+        transform: Transform::from_xyz(0.0, -2.0, -4.0),
+        ..default()
+    });
+
+    // Load mesh and shader
+    let mesh = meshes.add(Cube { size: 1.0 }.into());
+    let shader = asset_server.load(SHADER_PATH);
+
+    let rotation = Quat::from_axis_angle(Vec3::new(-1.0, 1.0, 1.0), -45.0).normalize();
+
+    // Spawn warped cubes lit by the regular PBR fragment
+    for (x, base) in [
+        (-2.5, StandardMaterial::default()),
+        (
+            0.0,
+            StandardMaterial {
+                base_color: Color::rgb(0.9, 0.3, 0.2),
+                perceptual_roughness: 0.3,
+                ..default()
+            },
+        ),
+        (
+            2.5,
+            StandardMaterial {
+                base_color: Color::rgb(0.9, 0.8, 0.5),
+                metallic: 1.0,
+                perceptual_roughness: 0.2,
+                ..default()
+            },
+        ),
+    ] {
+        commands.spawn(MaterialMeshBundle {
+            transform: Transform::from_xyz(x, 0.0, -6.0).with_rotation(rotation),
+            mesh: mesh.clone(),
+            material: materials.add(RustGpu {
+                base: base.into(),
+                vertex_shader: Some(shader.clone()),
+                fragment_shader: Some(shader.clone()),
+                ..default()
+            }),
+            ..default()
+        });
+    }
+}
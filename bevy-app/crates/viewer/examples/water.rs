@@ -0,0 +1,314 @@
+use bevy::{
+    core_pipeline::prepass::DepthPrepass,
+    prelude::{
+        default,
+        shape::{Cube, Plane},
+        AlphaMode, App, AssetPlugin, AssetServer, Assets, Camera3dBundle, ClearColor, Color,
+        Commands, Component, DefaultPlugins, DirectionalLight, DirectionalLightBundle, Handle,
+        Material, MaterialMeshBundle, Mesh, Msaa, PbrBundle, PluginGroup, Quat, Query, Res, ResMut,
+        StandardMaterial, Transform, Vec2, Vec3, Without,
+    },
+    reflect::TypeUuid,
+    render::render_resource::{AsBindGroup, ShaderType},
+    time::Time,
+};
+
+use bevy_rust_gpu::{
+    prelude::{RustGpu, RustGpuMaterialPlugin, RustGpuPlugin},
+    EntryPoint, RustGpuBuilderOutput, RustGpuMaterial,
+};
+use shader_shared::water::{gerstner, height, GerstnerWave, MAX_WAVES};
+use viewer::{
+    deformed_bounds::{DeformedBoundsPlugin, VertexDeformation},
+    specialize::{specialize_rust_gpu, RustGpuShadersPlugin},
+};
+
+/// Workspace-relative path to SPIR-V shader
+const SHADER_PATH: &'static str = "rust-gpu/shader.rust-gpu.msgpack";
+
+const ENTRY_POINTS_PATH: &'static str = "crates/viewer/entry_points.json";
+
+/// Marker type describing the `water::vertex_water` entrypoint from the shader crate
+pub enum VertexWater {}
+
+impl EntryPoint for VertexWater {
+    const NAME: &'static str = "water::vertex_water";
+}
+
+/// Marker type describing the `water::fragment_water` entrypoint from the shader crate
+pub enum FragmentWater {}
+
+impl EntryPoint for FragmentWater {
+    const NAME: &'static str = "water::fragment_water";
+}
+
+/// A single Gerstner wave, as laid out in the material uniform
+#[derive(Debug, Default, Copy, Clone, PartialEq, ShaderType)]
+pub struct Wave {
+    pub direction: Vec2,
+    pub steepness: f32,
+    pub wavelength: f32,
+}
+
+impl From<Wave> for GerstnerWave {
+    fn from(wave: Wave) -> Self {
+        GerstnerWave {
+            direction: wave.direction,
+            steepness: wave.steepness,
+            wavelength: wave.wavelength,
+        }
+    }
+}
+
+/// Gerstner wave water material tying together [`VertexWater`] and [`FragmentWater`]
+#[derive(Debug, Copy, Clone, AsBindGroup, TypeUuid)]
+#[uuid = "5b0e8f3a-2c71-4d6e-9a14-c3f7e2b8d905"]
+pub struct WaterMaterial {
+    /// Color of thin water, alpha controls its opacity
+    #[uniform(0)]
+    pub shallow_color: Color,
+    /// Color of thick water, alpha controls its opacity
+    #[uniform(0)]
+    pub deep_color: Color,
+    /// Color reflected at grazing angles
+    #[uniform(0)]
+    pub sky_color: Color,
+    /// Foam color, alpha controls its strength
+    #[uniform(0)]
+    pub foam_color: Color,
+    #[uniform(0)]
+    pub waves: [Wave; MAX_WAVES],
+    /// Number of entries in `waves` in use
+    #[uniform(0)]
+    pub wave_count: u32,
+    /// Water thickness over which `shallow_color` fades to `deep_color`
+    #[uniform(0)]
+    pub absorption_depth: f32,
+    /// Water thickness under which shoreline foam appears
+    #[uniform(0)]
+    pub foam_depth: f32,
+    /// Crest height at which wave foam appears, relative to the summed wave steepness
+    #[uniform(0)]
+    pub foam_crest: f32,
+    /// Strength of the small scrolling ripples added to the wave normal
+    #[uniform(0)]
+    pub ripple_strength: f32,
+}
+
+impl Default for WaterMaterial {
+    fn default() -> Self {
+        let mut waves = [Wave::default(); MAX_WAVES];
+        let defaults = [
+            (Vec2::new(1.0, 0.2), 0.25, 16.0),
+            (Vec2::new(0.6, 1.0), 0.2, 9.0),
+            (Vec2::new(-0.3, 1.0), 0.15, 5.0),
+            (Vec2::new(1.0, -0.8), 0.1, 2.5),
+        ];
+
+        for (wave, (direction, steepness, wavelength)) in waves.iter_mut().zip(defaults) {
+            *wave = Wave {
+                direction,
+                steepness,
+                wavelength,
+            };
+        }
+
+        WaterMaterial {
+            shallow_color: Color::rgba(0.1, 0.6, 0.55, 0.3),
+            deep_color: Color::rgba(0.0, 0.1, 0.25, 0.95),
+            sky_color: Color::rgb(0.6, 0.75, 0.9),
+            foam_color: Color::rgba(0.95, 0.97, 1.0, 0.9),
+            waves,
+            wave_count: defaults.len() as u32,
+            absorption_depth: 2.5,
+            foam_depth: 0.3,
+            foam_crest: 0.45,
+            ripple_strength: 0.05,
+        }
+    }
+}
+
+impl WaterMaterial {
+    /// Height of the water surface above world-space `xz` at shader time `time`,
+    /// matching `vertex_water` for a water plane at the origin
+    pub fn height(&self, xz: Vec2, time: f32) -> f32 {
+        height(&self.waves.map(Into::into), self.wave_count, xz, time)
+    }
+}
+
+impl Material for WaterMaterial {
+    fn alpha_mode(&self) -> AlphaMode {
+        // Keep the water out of the depth prepass, so the fragment shader sees the floor beneath it
+        AlphaMode::Blend
+    }
+
+    fn specialize(
+        _pipeline: &bevy::pbr::MaterialPipeline<Self>,
+        descriptor: &mut bevy::render::render_resource::RenderPipelineDescriptor,
+        layout: &bevy::render::mesh::MeshVertexBufferLayout,
+        _key: bevy::pbr::MaterialPipelineKey<Self>,
+    ) -> Result<(), bevy::render::render_resource::SpecializedMeshPipelineError> {
+        specialize_rust_gpu::<Self>(descriptor, layout, &default())
+    }
+}
+
+impl RustGpuMaterial for WaterMaterial {
+    type Vertex = VertexWater;
+    type Fragment = FragmentWater;
+}
+
+impl VertexDeformation for WaterMaterial {
+    fn deform_position(&self, position: Vec3, time: f32) -> Vec3 {
+        gerstner(&self.waves.map(Into::into), self.wave_count, position, time).position
+    }
+}
+
+/// Bobs on the water surface, sampling the same waves as the GPU
+#[derive(Debug, Default, Copy, Clone, Component)]
+pub struct Buoyant {
+    /// Half of the footprint used to estimate the surface slope
+    pub half_extent: f32,
+}
+
+fn main() {
+    let mut app = App::default();
+
+    // Add default plugins
+    app.add_plugins(DefaultPlugins.set(
+        // Configure the asset plugin to watch the workspace path for changes
+        AssetPlugin {
+            watch_for_changes: true,
+            ..default()
+        },
+    ));
+
+    // Add the Rust-GPU plugin
+    app.add_plugin(RustGpuPlugin::default());
+
+    // Setup `RustGpu<WaterMaterial>`
+    app.add_plugin(RustGpuMaterialPlugin::<WaterMaterial>::default());
+    RustGpu::<WaterMaterial>::export_to(ENTRY_POINTS_PATH);
+
+    // Keep culling bounds in sync with `vertex_water`
+    app.add_plugin(DeformedBoundsPlugin::<WaterMaterial>::default());
+
+    // Check mesh attributes against the vertex entry points of the loaded shader
+    app.add_plugin(RustGpuShadersPlugin);
+
+    app.insert_resource(ClearColor(Color::rgb(0.6, 0.75, 0.9)));
+
+    app.insert_resource(Msaa::Off);
+
+    // Setup scene
+    app.add_startup_system(setup);
+
+    app.add_system(float_buoyant);
+
+    // Run
+    app.run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+    mut water_materials: ResMut<Assets<RustGpu<WaterMaterial>>>,
+) {
+    // Spawn camera, with a depth prepass for water absorption
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6.0, 16.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        DepthPrepass::default(),
+    ));
+
+    // Spawn lights
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            illuminance: 20000.0,
+            ..default()
+        },
+        transform: Transform::IDENTITY.looking_at(Vec3::new(-0.5, -1.0, -0.8), Vec3::Y),
+        ..default()
+    });
+
+    // Spawn a sloping sea floor, surfacing towards the back of the scene
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Plane::from_size(60.0).into()),
+        material: standard_materials.add(Color::rgb(0.76, 0.7, 0.5).into()),
+        transform: Transform::from_xyz(0.0, -2.5, 0.0).with_rotation(Quat::from_rotation_x(0.12)),
+        ..default()
+    });
+
+    // Spawn water
+    let shader = asset_server.load::<RustGpuBuilderOutput, _>(SHADER_PATH);
+
+    commands.spawn(MaterialMeshBundle {
+        mesh: meshes.add(
+            Plane {
+                size: 60.0,
+                subdivisions: 240,
+            }
+            .into(),
+        ),
+        material: water_materials.add(RustGpu {
+            vertex_shader: Some(shader.clone()),
+            fragment_shader: Some(shader),
+            ..default()
+        }),
+        ..default()
+    });
+
+    // Spawn floating crates
+    let crate_mesh = meshes.add(Cube { size: 1.0 }.into());
+    let crate_material = standard_materials.add(Color::rgb(0.55, 0.35, 0.2).into());
+
+    for position in [
+        Vec3::new(-4.0, 0.0, 2.0),
+        Vec3::new(0.0, 0.0, -3.0),
+        Vec3::new(5.0, 0.0, 4.0),
+    ] {
+        commands.spawn((
+            PbrBundle {
+                mesh: crate_mesh.clone(),
+                material: crate_material.clone(),
+                transform: Transform::from_translation(position),
+                ..default()
+            },
+            Buoyant { half_extent: 0.5 },
+        ));
+    }
+}
+
+/// Move [`Buoyant`] entities onto the water surface and tilt them to its slope
+fn float_buoyant(
+    time: Res<Time>,
+    water: Query<&Handle<RustGpu<WaterMaterial>>>,
+    water_materials: Res<Assets<RustGpu<WaterMaterial>>>,
+    mut buoyant: Query<(&Buoyant, &mut Transform), Without<Handle<RustGpu<WaterMaterial>>>>,
+) {
+    let Some(water) = water
+        .iter()
+        .next()
+        .and_then(|handle| water_materials.get(handle))
+    else {
+        return;
+    };
+
+    // Matches the `time` field of the `Globals` uniform
+    let t = time.elapsed_seconds_wrapped();
+
+    for (buoyant, mut transform) in buoyant.iter_mut() {
+        let xz = Vec2::new(transform.translation.x, transform.translation.z);
+        let e = buoyant.half_extent;
+
+        let dx = water.base.height(xz + Vec2::X * e, t) - water.base.height(xz - Vec2::X * e, t);
+        let dz = water.base.height(xz + Vec2::Y * e, t) - water.base.height(xz - Vec2::Y * e, t);
+        let normal = Vec3::new(-dx, 2.0 * e, -dz).normalize();
+
+        transform.translation.y = water.base.height(xz, t);
+        transform.rotation = Quat::from_rotation_arc(Vec3::Y, normal);
+    }
+}
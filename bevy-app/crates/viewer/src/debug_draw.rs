@@ -0,0 +1,203 @@
+//! Immediate-mode debug shapes, drawn with rust-gpu SDF shading.
+//!
+//! Systems push lines, circles, rectangles and arrows into the [`DebugShapes`] resource each frame.
+//! [`DebugDrawPlugin`] uploads them to a storage buffer and draws them all in one call
+//! through `debug_draw::vertex_debug_shapes` and `debug_draw::fragment_debug_shapes`.
+
+use bevy::{
+    prelude::{
+        default, App, AssetServer, Assets, Color, Commands, Component, CoreSet, Handle,
+        IntoSystemConfig, Mesh, Plugin, Query, Res, ResMut, Resource, Transform, Vec2, With,
+    },
+    reflect::TypeUuid,
+    render::{
+        mesh::MeshVertexBufferLayout,
+        render_resource::{
+            AsBindGroup, PrimitiveTopology, RenderPipelineDescriptor, SpecializedMeshPipelineError,
+        },
+    },
+    sprite::{Material2d, Material2dKey, MaterialMesh2dBundle, Mesh2dHandle},
+};
+use bevy_rust_gpu::{
+    prelude::RustGpu, EntryPoint, RustGpuBuilderOutput, RustGpuMaterial, RustGpuMaterial2dPlugin,
+};
+
+use crate::specialize::{specialize_rust_gpu, RustGpuShadersPlugin};
+
+pub use shader_shared::debug_draw::{
+    DebugShape, KIND_ARROW, KIND_CIRCLE, KIND_LINE, KIND_NONE, KIND_RECT,
+};
+
+/// Marker type describing the `debug_draw::vertex_debug_shapes` entrypoint from the shader crate
+pub enum VertexDebugShapes {}
+
+impl EntryPoint for VertexDebugShapes {
+    const NAME: &'static str = "debug_draw::vertex_debug_shapes";
+}
+
+/// Marker type describing the `debug_draw::fragment_debug_shapes` entrypoint from the shader crate
+pub enum FragmentDebugShapes {}
+
+impl EntryPoint for FragmentDebugShapes {
+    const NAME: &'static str = "debug_draw::fragment_debug_shapes";
+}
+
+/// Debug shapes to draw this frame, cleared once uploaded
+#[derive(Debug, Default, Clone, Resource)]
+pub struct DebugShapes {
+    shapes: Vec<DebugShape>,
+}
+
+impl DebugShapes {
+    fn push(&mut self, kind: u32, a: Vec2, b: Vec2, stroke: f32, size: f32, color: Color) {
+        self.shapes.push(DebugShape {
+            color: color.as_linear_rgba_f32().into(),
+            a,
+            b,
+            kind,
+            stroke,
+            size,
+            ..default()
+        });
+    }
+
+    /// Line from `a` to `b`
+    pub fn line(&mut self, a: Vec2, b: Vec2, width: f32, color: Color) {
+        self.push(KIND_LINE, a, b, width, 0.0, color);
+    }
+
+    /// Circle outline of the given `stroke` width, or a filled disc if `stroke` is zero
+    pub fn circle(&mut self, center: Vec2, radius: f32, stroke: f32, color: Color) {
+        self.push(KIND_CIRCLE, center, Vec2::ZERO, stroke, radius, color);
+    }
+
+    /// Axis-aligned rectangle outline of the given `stroke` width, or a filled rectangle if `stroke` is zero
+    pub fn rect(&mut self, center: Vec2, size: Vec2, stroke: f32, color: Color) {
+        self.push(KIND_RECT, center, size * 0.5, stroke, 0.0, color);
+    }
+
+    /// Line from `a` to `b` with a head of length `head` at `b`
+    pub fn arrow(&mut self, a: Vec2, b: Vec2, width: f32, head: f32, color: Color) {
+        self.push(KIND_ARROW, a, b, width, head, color);
+    }
+}
+
+/// Material binding the frame's shapes as a storage buffer
+#[derive(Debug, Default, Clone, AsBindGroup, TypeUuid)]
+#[uuid = "a4f1c7e2-6b93-4d08-8e5a-1f2b7c9d3e60"]
+pub struct DebugShapesMaterial {
+    #[storage(0, read_only)]
+    pub shapes: Vec<DebugShape>,
+}
+
+impl Material2d for DebugShapesMaterial {
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        _key: Material2dKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.primitive.cull_mode = None;
+        specialize_rust_gpu::<Self>(descriptor, layout, &default())
+    }
+}
+
+impl RustGpuMaterial for DebugShapesMaterial {
+    type Vertex = VertexDebugShapes;
+    type Fragment = FragmentDebugShapes;
+}
+
+/// Marker for the entity drawing the frame's debug shapes
+#[derive(Debug, Default, Copy, Clone, Component)]
+pub struct DebugShapesBatch;
+
+/// Sets up [`DebugShapes`] and the entity that draws them, using the shader asset at `shader`
+pub struct DebugDrawPlugin {
+    pub shader: &'static str,
+}
+
+impl Plugin for DebugDrawPlugin {
+    fn build(&self, app: &mut App) {
+        let shader = self.shader;
+
+        if !app.is_plugin_added::<RustGpuShadersPlugin>() {
+            app.add_plugin(RustGpuShadersPlugin);
+        }
+
+        app.add_plugin(RustGpuMaterial2dPlugin::<DebugShapesMaterial>::default())
+            .init_resource::<DebugShapes>()
+            .add_startup_system(
+                move |mut commands: Commands,
+                      asset_server: Res<AssetServer>,
+                      mut meshes: ResMut<Assets<Mesh>>,
+                      mut materials: ResMut<Assets<RustGpu<DebugShapesMaterial>>>| {
+                    let shader = asset_server.load::<RustGpuBuilderOutput, _>(shader);
+
+                    commands.spawn((
+                        MaterialMesh2dBundle {
+                            // Only orders the batch after other `Transparent2d` items,
+                            // as `vertex_debug_shapes` ignores the mesh transform
+                            transform: Transform::from_xyz(0.0, 0.0, 999.0),
+                            mesh: Mesh2dHandle::from(meshes.add(batch_mesh(1))),
+                            material: materials.add(RustGpu {
+                                vertex_shader: Some(shader.clone()),
+                                fragment_shader: Some(shader),
+                                ..default()
+                            }),
+                            ..default()
+                        },
+                        DebugShapesBatch,
+                    ));
+                },
+            )
+            .add_system(upload_debug_shapes.in_base_set(CoreSet::PostUpdate));
+    }
+}
+
+/// Vertex buffer with six vertices per shape slot.
+///
+/// Positions are unused, as `vertex_debug_shapes` builds each quad from its vertex index.
+fn batch_mesh(capacity: usize) -> Mesh {
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; capacity * 6]);
+    mesh
+}
+
+fn upload_debug_shapes(
+    mut shapes: ResMut<DebugShapes>,
+    query: Query<(&Handle<RustGpu<DebugShapesMaterial>>, &Mesh2dHandle), With<DebugShapesBatch>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<RustGpu<DebugShapesMaterial>>>,
+) {
+    let mut frame = std::mem::take(&mut shapes.shapes);
+
+    // Storage buffers can't be empty
+    if frame.is_empty() {
+        frame.push(default());
+    }
+
+    for (material, mesh) in query.iter() {
+        // Grow the batch mesh if the frame has more shapes than slots
+        let capacity = meshes
+            .get(&mesh.0)
+            .map(|mesh| mesh.count_vertices() / 6)
+            .unwrap_or_default();
+
+        if capacity < frame.len() {
+            if let Some(mesh) = meshes.get_mut(&mesh.0) {
+                *mesh = batch_mesh(frame.len().next_power_of_two());
+            }
+        }
+
+        // Skip re-uploading if nothing changed since last frame
+        let unchanged = materials
+            .get(material)
+            .map(|material| material.base.shapes == frame)
+            .unwrap_or_default();
+
+        if !unchanged {
+            if let Some(material) = materials.get_mut(material) {
+                material.base.shapes = frame.clone();
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Frustum culling bounds for materials whose vertex shader displaces mesh positions.
+//!
+//! Bevy computes each mesh's [`Aabb`] once from its undeformed vertices,
+//! so vertex-animated meshes can be culled while still partially on screen.
+//! [`DeformedBoundsPlugin`] replays the deformation on the CPU each frame and refits the [`Aabb`] to it.
+
+use std::marker::PhantomData;
+
+use bevy::{
+    prelude::{
+        App, Assets, CoreSet, Handle, IntoSystemConfig, Material, Mesh, Plugin, Query, Res, Vec3,
+    },
+    render::{mesh::VertexAttributeValues, primitives::Aabb, view::VisibilitySystems},
+    time::Time,
+};
+use bevy_rust_gpu::{prelude::RustGpu, RustGpuMaterial};
+
+/// A material whose vertex entry point moves mesh positions over time
+pub trait VertexDeformation: Material + RustGpuMaterial {
+    /// Deform a local-space position, matching the material's vertex shader at shader time `time`
+    fn deform_position(&self, position: Vec3, time: f32) -> Vec3;
+}
+
+/// Keeps the [`Aabb`] of each `RustGpu<M>` entity fitted to its deformed vertices
+pub struct DeformedBoundsPlugin<M>(PhantomData<M>);
+
+impl<M> Default for DeformedBoundsPlugin<M> {
+    fn default() -> Self {
+        DeformedBoundsPlugin(PhantomData)
+    }
+}
+
+impl<M> Plugin for DeformedBoundsPlugin<M>
+where
+    M: VertexDeformation,
+{
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            update_deformed_bounds::<M>
+                .in_base_set(CoreSet::PostUpdate)
+                .after(VisibilitySystems::CalculateBoundsFlush)
+                .before(VisibilitySystems::CheckVisibility),
+        );
+    }
+}
+
+fn update_deformed_bounds<M: VertexDeformation>(
+    time: Res<Time>,
+    meshes: Res<Assets<Mesh>>,
+    materials: Res<Assets<RustGpu<M>>>,
+    mut query: Query<(&Handle<Mesh>, &Handle<RustGpu<M>>, &mut Aabb)>,
+) {
+    // Matches the `time` field of the `Globals` uniform
+    let t = time.elapsed_seconds_wrapped();
+
+    for (mesh, material, mut aabb) in query.iter_mut() {
+        let (Some(mesh), Some(material)) = (meshes.get(mesh), materials.get(material)) else {
+            continue;
+        };
+
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            continue;
+        };
+
+        let (min, max) = positions.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), position| {
+                let position = material.base.deform_position(Vec3::from(*position), t);
+                (min.min(position), max.max(position))
+            },
+        );
+
+        if min.cmple(max).all() {
+            *aabb = Aabb::from_min_max(min, max);
+        }
+    }
+}
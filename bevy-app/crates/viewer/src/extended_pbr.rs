@@ -0,0 +1,278 @@
+//! `StandardMaterial` with a shader-side hook between PBR input preparation and lighting.
+//!
+//! [`ExtendedStandardMaterial<E>`] carries the usual `StandardMaterial` parameters and textures,
+//! plus an extension uniform `E` at binding 11.
+//! Drawn as a `RustGpu<ExtendedStandardMaterial<E>>`, it selects the `extended_pbr::fragment_extended_pbr`
+//! permutation whose `Extension` type matches [`PbrExtension::NAME`],
+//! and the normal-mapped permutations of both stages when it has a normal map and the mesh has tangents.
+
+use std::marker::PhantomData;
+
+use bevy::{
+    pbr::{MaterialPipeline, MaterialPipelineKey, StandardMaterialUniform},
+    prelude::{AlphaMode, Color, Handle, Image, Material, Mesh, StandardMaterial},
+    reflect::TypeUuid,
+    render::{
+        mesh::MeshVertexBufferLayout,
+        render_asset::RenderAssets,
+        render_resource::{
+            encase::private::WriteInto, AsBindGroup, AsBindGroupShaderType, Face,
+            RenderPipelineDescriptor, ShaderType, SpecializedMeshPipelineError,
+        },
+    },
+    utils::Uuid,
+};
+use bevy_rust_gpu::{EntryPoint, EntryPointParameters, EntryPointTypes, RustGpuMaterial};
+
+use crate::specialize::{specialize_rust_gpu, EntryPointOverrides};
+
+/// Shader def selecting the `normal_map = some` permutation of the extended PBR entry points
+const NORMAL_MAP_DEF: &str = "STANDARDMATERIAL_NORMAL_MAP";
+
+const NORMAL_MAP_PARAMETERS: EntryPointParameters = &[(&[(NORMAL_MAP_DEF, "some")], "none")];
+
+/// Uniform parameters for a shader-side `extended_pbr::PbrExtension`
+pub trait PbrExtension: ShaderType + WriteInto + Clone + Send + Sync + 'static {
+    /// Name of the shader-side type implementing `PbrExtension`
+    const NAME: &'static str;
+}
+
+/// Marker type describing the `extended_pbr::vertex_extended_pbr` entrypoint from the shader crate
+pub enum VertexExtendedPbr {}
+
+impl EntryPoint for VertexExtendedPbr {
+    const NAME: &'static str = "extended_pbr::vertex_extended_pbr";
+    const PARAMETERS: EntryPointParameters = NORMAL_MAP_PARAMETERS;
+}
+
+/// Marker type describing the `extended_pbr::fragment_extended_pbr` entrypoint from the shader crate,
+/// permutated over its `Extension` type
+pub struct FragmentExtendedPbr<E> {
+    pub _phantom: PhantomData<E>,
+}
+
+impl<E> EntryPoint for FragmentExtendedPbr<E>
+where
+    E: PbrExtension,
+{
+    const NAME: &'static str = "extended_pbr::fragment_extended_pbr";
+    const PARAMETERS: EntryPointParameters = NORMAL_MAP_PARAMETERS;
+
+    fn types() -> EntryPointTypes {
+        vec![("Extension".to_string(), E::NAME.to_string())]
+    }
+}
+
+/// `StandardMaterial` parameters followed by the uniform of extension `E`
+#[derive(Debug, Clone, AsBindGroup)]
+#[uniform(0, StandardMaterialUniform)]
+#[bind_group_data(ExtendedStandardMaterialKey)]
+pub struct ExtendedStandardMaterial<E: PbrExtension> {
+    pub base_color: Color,
+    #[texture(1)]
+    #[sampler(2)]
+    pub base_color_texture: Option<Handle<Image>>,
+    pub emissive: Color,
+    #[texture(3)]
+    #[sampler(4)]
+    pub emissive_texture: Option<Handle<Image>>,
+    pub perceptual_roughness: f32,
+    pub metallic: f32,
+    #[texture(5)]
+    #[sampler(6)]
+    pub metallic_roughness_texture: Option<Handle<Image>>,
+    pub reflectance: f32,
+    #[texture(7)]
+    #[sampler(8)]
+    pub occlusion_texture: Option<Handle<Image>>,
+    #[texture(9)]
+    #[sampler(10)]
+    pub normal_map_texture: Option<Handle<Image>>,
+    pub flip_normal_map_y: bool,
+    pub double_sided: bool,
+    pub cull_mode: Option<Face>,
+    pub unlit: bool,
+    pub alpha_mode: AlphaMode,
+    pub depth_bias: f32,
+    #[uniform(11)]
+    pub extension: E,
+}
+
+impl<E: PbrExtension> ExtendedStandardMaterial<E> {
+    pub fn new(base: StandardMaterial, extension: E) -> Self {
+        ExtendedStandardMaterial {
+            base_color: base.base_color,
+            base_color_texture: base.base_color_texture,
+            emissive: base.emissive,
+            emissive_texture: base.emissive_texture,
+            perceptual_roughness: base.perceptual_roughness,
+            metallic: base.metallic,
+            metallic_roughness_texture: base.metallic_roughness_texture,
+            reflectance: base.reflectance,
+            occlusion_texture: base.occlusion_texture,
+            normal_map_texture: base.normal_map_texture,
+            flip_normal_map_y: base.flip_normal_map_y,
+            double_sided: base.double_sided,
+            cull_mode: base.cull_mode,
+            unlit: base.unlit,
+            alpha_mode: base.alpha_mode,
+            depth_bias: base.depth_bias,
+            extension,
+        }
+    }
+
+    /// The `StandardMaterial` this material extends
+    pub fn base(&self) -> StandardMaterial {
+        StandardMaterial {
+            base_color: self.base_color,
+            base_color_texture: self.base_color_texture.clone(),
+            emissive: self.emissive,
+            emissive_texture: self.emissive_texture.clone(),
+            perceptual_roughness: self.perceptual_roughness,
+            metallic: self.metallic,
+            metallic_roughness_texture: self.metallic_roughness_texture.clone(),
+            reflectance: self.reflectance,
+            occlusion_texture: self.occlusion_texture.clone(),
+            normal_map_texture: self.normal_map_texture.clone(),
+            flip_normal_map_y: self.flip_normal_map_y,
+            double_sided: self.double_sided,
+            cull_mode: self.cull_mode,
+            unlit: self.unlit,
+            alpha_mode: self.alpha_mode,
+            depth_bias: self.depth_bias,
+            ..Default::default()
+        }
+    }
+}
+
+impl<E: PbrExtension + Default> Default for ExtendedStandardMaterial<E> {
+    fn default() -> Self {
+        ExtendedStandardMaterial::new(StandardMaterial::default(), E::default())
+    }
+}
+
+impl<E: PbrExtension> AsBindGroupShaderType<StandardMaterialUniform>
+    for ExtendedStandardMaterial<E>
+{
+    fn as_bind_group_shader_type(&self, images: &RenderAssets<Image>) -> StandardMaterialUniform {
+        self.base().as_bind_group_shader_type(images)
+    }
+}
+
+/// Pipeline key for [`ExtendedStandardMaterial`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ExtendedStandardMaterialKey {
+    normal_map: bool,
+    cull_mode: Option<Face>,
+}
+
+impl<E: PbrExtension> From<&ExtendedStandardMaterial<E>> for ExtendedStandardMaterialKey {
+    fn from(material: &ExtendedStandardMaterial<E>) -> Self {
+        ExtendedStandardMaterialKey {
+            normal_map: material.normal_map_texture.is_some(),
+            cull_mode: material.cull_mode,
+        }
+    }
+}
+
+impl<E> Material for ExtendedStandardMaterial<E>
+where
+    E: PbrExtension,
+    Self: TypeUuid,
+{
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    fn depth_bias(&self) -> f32 {
+        self.depth_bias
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        // Like `StandardMaterial`, skip normal mapping on meshes without tangents
+        if key.bind_group_data.normal_map && layout.contains(Mesh::ATTRIBUTE_TANGENT) {
+            descriptor.vertex.shader_defs.push(NORMAL_MAP_DEF.into());
+            if let Some(fragment) = descriptor.fragment.as_mut() {
+                fragment.shader_defs.push(NORMAL_MAP_DEF.into());
+            }
+        }
+
+        descriptor.primitive.cull_mode = key.bind_group_data.cull_mode;
+        specialize_rust_gpu::<Self>(descriptor, layout, &EntryPointOverrides::default())
+    }
+}
+
+impl<E> RustGpuMaterial for ExtendedStandardMaterial<E>
+where
+    E: PbrExtension,
+    Self: TypeUuid,
+{
+    type Vertex = VertexExtendedPbr;
+    type Fragment = FragmentExtendedPbr<E>;
+}
+
+/// Blends base color towards `color` by fractal noise over world position.
+///
+/// Mirrors `extended_pbr::NoiseTintParams` in the shader crate.
+#[derive(Debug, Copy, Clone, PartialEq, ShaderType)]
+pub struct NoiseTint {
+    pub color: Color,
+    /// World-space frequency of the noise
+    pub scale: f32,
+    /// Blend factor towards `color` where the noise peaks
+    pub strength: f32,
+}
+
+impl Default for NoiseTint {
+    fn default() -> Self {
+        NoiseTint {
+            color: Color::rgb(0.3, 0.15, 0.05),
+            scale: 2.0,
+            strength: 1.0,
+        }
+    }
+}
+
+impl PbrExtension for NoiseTint {
+    const NAME: &'static str = "NoiseTint";
+}
+
+impl TypeUuid for ExtendedStandardMaterial<NoiseTint> {
+    const TYPE_UUID: Uuid = Uuid::from_u128(0x8e41_f2a7_0c5d_4e93_b6a8_1d7c_93f0_2e01);
+}
+
+/// Multiplies base color by a gradient from `low_color` to `high_color` over world-space height,
+/// roughening the surface towards `min_height`.
+///
+/// Mirrors `extended_pbr::HeightTintParams` in the shader crate.
+#[derive(Debug, Copy, Clone, PartialEq, ShaderType)]
+pub struct HeightTint {
+    pub low_color: Color,
+    pub high_color: Color,
+    pub min_height: f32,
+    pub max_height: f32,
+}
+
+impl Default for HeightTint {
+    fn default() -> Self {
+        HeightTint {
+            low_color: Color::rgb(0.2, 0.3, 0.15),
+            high_color: Color::WHITE,
+            min_height: -1.0,
+            max_height: 1.0,
+        }
+    }
+}
+
+impl PbrExtension for HeightTint {
+    const NAME: &'static str = "HeightTint";
+}
+
+impl TypeUuid for ExtendedStandardMaterial<HeightTint> {
+    const TYPE_UUID: Uuid = Uuid::from_u128(0x8e41_f2a7_0c5d_4e93_b6a8_1d7c_93f0_2e02);
+}
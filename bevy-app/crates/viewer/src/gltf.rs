@@ -0,0 +1,199 @@
+//! glTF scenes drawn with the `bevy-pbr-rust` port of `StandardMaterial`.
+//!
+//! [`RustGpuGltfPlugin`] rewrites each loaded glTF's scenes in place,
+//! replacing every `Handle<StandardMaterial>` with a `Handle<RustGpu<StandardMaterial>>`
+//! built from the same material, so spawned scenes never touch the WGSL pipeline.
+//! Combined with `RustGpu::<StandardMaterial>::export_to`, this requests the entry point permutation
+//! for every texture, alpha and normal map combination the file uses.
+
+use bevy::{
+    gltf::Gltf,
+    prelude::{
+        default, App, AssetEvent, AssetServer, Assets, Entity, EventReader, Handle, Plugin, Res,
+        ResMut, Resource, Scene, StandardMaterial,
+    },
+    utils::HashMap,
+};
+use bevy_rust_gpu::{
+    prelude::{RustGpu, RustGpuMaterialPlugin},
+    RustGpuBuilderOutput,
+};
+
+/// `RustGpu<StandardMaterial>` assets created for glTF materials, keyed by the material they copy
+#[derive(Debug, Resource)]
+pub struct RustGpuGltfMaterials {
+    /// Shader asset path for newly created `RustGpu<StandardMaterial>` assets
+    pub shader: &'static str,
+    materials: HashMap<Handle<StandardMaterial>, Handle<RustGpu<StandardMaterial>>>,
+    /// Loaded or reloaded glTFs whose scenes or materials weren't all available yet
+    pending: Vec<Handle<Gltf>>,
+}
+
+impl RustGpuGltfMaterials {
+    /// The `RustGpu<StandardMaterial>` replacing `handle`, if it has been converted
+    pub fn get(
+        &self,
+        handle: &Handle<StandardMaterial>,
+    ) -> Option<&Handle<RustGpu<StandardMaterial>>> {
+        self.materials.get(handle)
+    }
+}
+
+/// Converts the materials of loaded glTF scenes to `RustGpu<StandardMaterial>`,
+/// using the shader asset at `shader`
+pub struct RustGpuGltfPlugin {
+    pub shader: &'static str,
+}
+
+impl Plugin for RustGpuGltfPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<RustGpuMaterialPlugin<StandardMaterial>>() {
+            app.add_plugin(RustGpuMaterialPlugin::<StandardMaterial>::default());
+        }
+
+        app.insert_resource(RustGpuGltfMaterials {
+            shader: self.shader,
+            materials: default(),
+            pending: default(),
+        })
+        .add_system(convert_gltf_scenes)
+        .add_system(sync_gltf_materials);
+    }
+}
+
+/// Replace the `StandardMaterial` handles in the scenes of each newly loaded or reloaded glTF.
+///
+/// A glTF stays pending, and is retried each frame, until its scenes and materials have all loaded.
+/// Modifying a scene makes the scene spawner respawn its instances,
+/// so scenes spawned before conversion are picked up too.
+fn convert_gltf_scenes(
+    mut events: EventReader<AssetEvent<Gltf>>,
+    asset_server: Res<AssetServer>,
+    gltfs: Res<Assets<Gltf>>,
+    mut scenes: ResMut<Assets<Scene>>,
+    standard_materials: Res<Assets<StandardMaterial>>,
+    mut rust_gpu_materials: ResMut<Assets<RustGpu<StandardMaterial>>>,
+    mut converted: ResMut<RustGpuGltfMaterials>,
+) {
+    for event in events.iter() {
+        let (AssetEvent::Created { handle } | AssetEvent::Modified { handle }) = event else {
+            continue;
+        };
+
+        if !converted.pending.contains(handle) {
+            converted.pending.push(handle.clone_weak());
+        }
+    }
+
+    let mut pending = std::mem::take(&mut converted.pending);
+    pending.retain(|handle| {
+        // Drop glTFs that were unloaded while pending
+        let Some(gltf) = gltfs.get(handle) else {
+            return false;
+        };
+
+        // Wait for every sub-asset before touching any scene, so scenes are only modified once
+        let loaded = gltf.scenes.iter().all(|scene| scenes.contains(scene))
+            && gltf
+                .materials
+                .iter()
+                .all(|material| standard_materials.contains(material));
+        if !loaded {
+            return true;
+        }
+
+        !convert_gltf(
+            gltf,
+            &asset_server,
+            &mut scenes,
+            &standard_materials,
+            &mut rust_gpu_materials,
+            &mut converted,
+        )
+    });
+    converted.pending = pending;
+}
+
+/// Replace the `StandardMaterial` handles in the scenes of `gltf`.
+///
+/// Returns whether every material was loaded and replaced.
+fn convert_gltf(
+    gltf: &Gltf,
+    asset_server: &AssetServer,
+    scenes: &mut Assets<Scene>,
+    standard_materials: &Assets<StandardMaterial>,
+    rust_gpu_materials: &mut Assets<RustGpu<StandardMaterial>>,
+    converted: &mut RustGpuGltfMaterials,
+) -> bool {
+    let mut complete = true;
+
+    for scene_handle in gltf.scenes.iter() {
+        let Some(scene) = scenes.get_mut(scene_handle) else {
+            complete = false;
+            continue;
+        };
+
+        let entities = scene
+            .world
+            .query::<(Entity, &Handle<StandardMaterial>)>()
+            .iter(&scene.world)
+            .map(|(entity, handle)| (entity, handle.clone()))
+            .collect::<Vec<_>>();
+
+        for (entity, handle) in entities {
+            // Materials outside `Gltf::materials`, such as the default material, may still be loading
+            let Some(material) = standard_materials.get(&handle) else {
+                complete = false;
+                continue;
+            };
+
+            let shader = converted.shader;
+            let rust_gpu = converted
+                .materials
+                .entry(handle)
+                .or_insert_with(|| {
+                    let shader = asset_server.load::<RustGpuBuilderOutput, _>(shader);
+                    rust_gpu_materials.add(RustGpu {
+                        base: material.clone(),
+                        vertex_shader: Some(shader.clone()),
+                        fragment_shader: Some(shader),
+                        ..default()
+                    })
+                })
+                .clone();
+
+            scene
+                .world
+                .entity_mut(entity)
+                .remove::<Handle<StandardMaterial>>()
+                .insert(rust_gpu);
+        }
+    }
+
+    complete
+}
+
+/// Carry changes to converted glTF materials, such as hot-reloaded textures,
+/// over to their `RustGpu<StandardMaterial>` copies
+fn sync_gltf_materials(
+    mut events: EventReader<AssetEvent<StandardMaterial>>,
+    converted: Res<RustGpuGltfMaterials>,
+    standard_materials: Res<Assets<StandardMaterial>>,
+    mut rust_gpu_materials: ResMut<Assets<RustGpu<StandardMaterial>>>,
+) {
+    for event in events.iter() {
+        let AssetEvent::Modified { handle } = event else {
+            continue;
+        };
+
+        let (Some(material), Some(rust_gpu)) =
+            (standard_materials.get(handle), converted.get(handle))
+        else {
+            continue;
+        };
+
+        if let Some(paired) = rust_gpu_materials.get_mut(rust_gpu) {
+            paired.base = material.clone();
+        }
+    }
+}
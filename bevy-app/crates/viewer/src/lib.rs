@@ -0,0 +1,15 @@
+//! CPU-side support code shared by the viewer examples.
+
+pub mod debug_draw;
+pub mod deformed_bounds;
+pub mod extended_pbr;
+pub mod gltf;
+pub mod material_swap;
+pub mod msdf;
+pub mod pbr_vertex;
+pub mod prepass;
+pub mod sdf_2d;
+pub mod sdf_bake;
+pub mod sky;
+pub mod specialize;
+pub mod vertex_inputs;
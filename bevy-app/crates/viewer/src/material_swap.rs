@@ -0,0 +1,159 @@
+//! Runtime A/B comparison between Bevy's WGSL `StandardMaterial` and its `bevy-pbr-rust` port.
+//!
+//! On a key press, [`MaterialSwapPlugin`] swaps every entity's `Handle<StandardMaterial>`
+//! for an equivalent `Handle<RustGpu<StandardMaterial>>`, or back again.
+//! Each handle is paired with its counterpart the first time it is swapped,
+//! so repeated toggles reuse the same assets and shared materials stay shared.
+
+use bevy::{
+    prelude::{
+        default, info, App, AssetServer, Assets, Commands, Entity, Handle, Input, KeyCode, Plugin,
+        Query, Res, ResMut, Resource, StandardMaterial,
+    },
+    utils::HashMap,
+};
+use bevy_rust_gpu::{
+    prelude::{RustGpu, RustGpuMaterialPlugin},
+    RustGpuBuilderOutput,
+};
+
+/// Which implementation of `StandardMaterial` the scene is drawn with
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum MaterialBackend {
+    #[default]
+    Wgsl,
+    RustGpu,
+}
+
+/// Current backend, and the pairing between handles of both material types
+#[derive(Debug, Resource)]
+pub struct MaterialSwap {
+    pub backend: MaterialBackend,
+    /// Key toggling between backends
+    pub key: KeyCode,
+    /// Shader asset path for newly created `RustGpu<StandardMaterial>` assets
+    pub shader: &'static str,
+    wgsl_to_rust_gpu: HashMap<Handle<StandardMaterial>, Handle<RustGpu<StandardMaterial>>>,
+    rust_gpu_to_wgsl: HashMap<Handle<RustGpu<StandardMaterial>>, Handle<StandardMaterial>>,
+}
+
+/// Sets up [`MaterialSwap`], toggling its backend when `key` is pressed
+/// and building `RustGpu<StandardMaterial>` assets from the shader asset at `shader`
+pub struct MaterialSwapPlugin {
+    pub shader: &'static str,
+    pub key: KeyCode,
+}
+
+impl Plugin for MaterialSwapPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<RustGpuMaterialPlugin<StandardMaterial>>() {
+            app.add_plugin(RustGpuMaterialPlugin::<StandardMaterial>::default());
+        }
+
+        app.insert_resource(MaterialSwap {
+            backend: MaterialBackend::default(),
+            key: self.key,
+            shader: self.shader,
+            wgsl_to_rust_gpu: default(),
+            rust_gpu_to_wgsl: default(),
+        })
+        .add_system(swap_materials);
+    }
+}
+
+/// Flip the backend on key press, and move every entity onto it.
+///
+/// While on [`MaterialBackend::RustGpu`], entities spawned with a `Handle<StandardMaterial>`,
+/// such as late-loading scenes, are swapped on the frame they appear.
+#[allow(clippy::too_many_arguments)]
+fn swap_materials(
+    keys: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    mut swap: ResMut<MaterialSwap>,
+    mut commands: Commands,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+    mut rust_gpu_materials: ResMut<Assets<RustGpu<StandardMaterial>>>,
+    wgsl_entities: Query<(Entity, &Handle<StandardMaterial>)>,
+    rust_gpu_entities: Query<(Entity, &Handle<RustGpu<StandardMaterial>>)>,
+) {
+    let toggled = keys.just_pressed(swap.key);
+
+    if toggled {
+        swap.backend = match swap.backend {
+            MaterialBackend::Wgsl => MaterialBackend::RustGpu,
+            MaterialBackend::RustGpu => MaterialBackend::Wgsl,
+        };
+        info!("Drawing StandardMaterial with {:?}", swap.backend);
+    } else if swap.backend == MaterialBackend::Wgsl {
+        // Leave deliberate side-by-side setups alone until asked to swap
+        return;
+    }
+
+    let swap = &mut *swap;
+
+    match swap.backend {
+        MaterialBackend::RustGpu => {
+            for (entity, handle) in wgsl_entities.iter() {
+                let Some(material) = standard_materials.get(handle) else {
+                    continue;
+                };
+
+                let rust_gpu = match swap.wgsl_to_rust_gpu.get(handle) {
+                    Some(rust_gpu) => {
+                        // Pick up any edits made to the WGSL material since it was paired
+                        if let Some(paired) = rust_gpu_materials.get_mut(rust_gpu) {
+                            paired.base = material.clone();
+                        }
+                        rust_gpu.clone()
+                    }
+                    None => {
+                        let shader = asset_server.load::<RustGpuBuilderOutput, _>(swap.shader);
+                        let rust_gpu = rust_gpu_materials.add(RustGpu {
+                            base: material.clone(),
+                            vertex_shader: Some(shader.clone()),
+                            fragment_shader: Some(shader),
+                            ..default()
+                        });
+                        swap.wgsl_to_rust_gpu
+                            .insert(handle.clone(), rust_gpu.clone());
+                        swap.rust_gpu_to_wgsl
+                            .insert(rust_gpu.clone(), handle.clone());
+                        rust_gpu
+                    }
+                };
+
+                commands
+                    .entity(entity)
+                    .remove::<Handle<StandardMaterial>>()
+                    .insert(rust_gpu);
+            }
+        }
+        MaterialBackend::Wgsl => {
+            for (entity, handle) in rust_gpu_entities.iter() {
+                let Some(material) = rust_gpu_materials.get(handle) else {
+                    continue;
+                };
+
+                let wgsl = match swap.rust_gpu_to_wgsl.get(handle) {
+                    Some(wgsl) => {
+                        if let Some(paired) = standard_materials.get_mut(wgsl) {
+                            *paired = material.base.clone();
+                        }
+                        wgsl.clone()
+                    }
+                    None => {
+                        let wgsl = standard_materials.add(material.base.clone());
+                        swap.rust_gpu_to_wgsl.insert(handle.clone(), wgsl.clone());
+                        swap.wgsl_to_rust_gpu.insert(wgsl.clone(), handle.clone());
+                        wgsl
+                    }
+                };
+
+                commands
+                    .entity(entity)
+                    .remove::<Handle<RustGpu<StandardMaterial>>>()
+                    .insert(wgsl);
+            }
+        }
+    }
+}
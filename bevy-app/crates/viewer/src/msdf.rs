@@ -0,0 +1,786 @@
+//! Multi-channel signed distance field font atlas generation.
+//!
+//! Rasterizes TTF glyph outlines into an RGBA atlas for `fragment_msdf_text`:
+//! RGB holds a multi-channel SDF whose median reconstructs sharp corners,
+//! and A holds a true SDF used for outlines and drop shadows.
+
+use std::fmt::{Display, Formatter};
+
+use bevy::{
+    math::Vec2,
+    render::{
+        mesh::{Indices, Mesh},
+        render_resource::{Extent3d, PrimitiveTopology, TextureDimension, TextureFormat},
+        texture::Image,
+    },
+    utils::HashMap,
+};
+use ttf_parser::{Face, FaceParsingError, OutlineBuilder};
+
+const RED: u8 = 1;
+const GREEN: u8 = 2;
+const BLUE: u8 = 4;
+const CYAN: u8 = GREEN | BLUE;
+const MAGENTA: u8 = RED | BLUE;
+const YELLOW: u8 = RED | GREEN;
+const WHITE: u8 = RED | GREEN | BLUE;
+
+/// Number of line segments each curve is flattened into
+const CURVE_STEPS: usize = 8;
+
+/// Sine of the minimum angle between two segments for their join to count as a corner
+const CORNER_SIN: f32 = 0.14112;
+
+/// Parameters for [`MsdfAtlas::from_ttf`]
+#[derive(Debug, Clone)]
+pub struct MsdfAtlasSettings {
+    /// Size of one em in atlas texels
+    pub em_size: f32,
+    /// Width of the encoded distance range in atlas texels
+    pub distance_range: f32,
+    /// Atlas width in texels, height grows to fit
+    pub width: u32,
+    /// Characters to bake into the atlas
+    pub charset: String,
+}
+
+impl Default for MsdfAtlasSettings {
+    fn default() -> Self {
+        MsdfAtlasSettings {
+            em_size: 48.0,
+            distance_range: 6.0,
+            width: 512,
+            charset: (' '..='~').collect(),
+        }
+    }
+}
+
+/// Failure to bake an [`MsdfAtlas`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MsdfAtlasError {
+    /// The font data couldn't be parsed
+    Parse(FaceParsingError),
+    /// A glyph's cell, including distance range padding, is wider than [`MsdfAtlasSettings::width`]
+    GlyphTooWide(char),
+}
+
+impl Display for MsdfAtlasError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MsdfAtlasError::Parse(e) => write!(f, "Failed to parse font: {e}"),
+            MsdfAtlasError::GlyphTooWide(c) => write!(f, "Glyph {c:?} is wider than the atlas"),
+        }
+    }
+}
+
+impl std::error::Error for MsdfAtlasError {}
+
+impl From<FaceParsingError> for MsdfAtlasError {
+    fn from(e: FaceParsingError) -> Self {
+        MsdfAtlasError::Parse(e)
+    }
+}
+
+/// Placement of a single baked glyph
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct MsdfQuad {
+    /// Top-left atlas UV
+    pub uv_min: Vec2,
+    /// Bottom-right atlas UV
+    pub uv_max: Vec2,
+    /// Bottom-left corner relative to the pen position, in ems
+    pub plane_min: Vec2,
+    /// Top-right corner relative to the pen position, in ems
+    pub plane_max: Vec2,
+}
+
+/// Metrics for a single character
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct MsdfGlyph {
+    /// Horizontal advance in ems
+    pub advance: f32,
+    /// Atlas placement, or `None` for glyphs without an outline
+    pub quad: Option<MsdfQuad>,
+}
+
+/// Baked MSDF atlas image and the glyph metrics needed to lay out text with it
+#[derive(Debug, Clone)]
+pub struct MsdfAtlas {
+    pub image: Image,
+    pub glyphs: HashMap<char, MsdfGlyph>,
+    /// Atlas dimensions in texels
+    pub size: Vec2,
+    /// Width of the encoded distance range in atlas texels
+    pub distance_range: f32,
+    /// Distance between baselines in ems
+    pub line_height: f32,
+}
+
+impl MsdfAtlas {
+    /// Parse a TTF / OTF font and bake every character of `settings.charset` into an atlas
+    pub fn from_ttf(data: &[u8], settings: &MsdfAtlasSettings) -> Result<Self, MsdfAtlasError> {
+        let face = Face::parse(data, 0)?;
+        let units_per_em = face.units_per_em() as f32;
+        let scale = settings.em_size / units_per_em;
+        let padding = settings.distance_range.ceil() as u32;
+
+        // Extract and color outlines, then shelf-pack their cells
+        let mut glyphs = HashMap::default();
+        let mut cells = vec![];
+        let (mut cursor_x, mut cursor_y, mut row_height) = (0, 0, 0);
+
+        for c in settings.charset.chars() {
+            let Some(id) = face.glyph_index(c) else {
+                continue;
+            };
+
+            let advance = face.glyph_hor_advance(id).unwrap_or_default() as f32 / units_per_em;
+
+            let mut builder = ShapeBuilder::default();
+            let shape = face
+                .outline_glyph(id, &mut builder)
+                .map(|rect| (builder.finish(), rect));
+
+            let Some((contours, rect)) = shape else {
+                glyphs.insert(
+                    c,
+                    MsdfGlyph {
+                        advance,
+                        quad: None,
+                    },
+                );
+                continue;
+            };
+
+            let width = ((rect.x_max - rect.x_min) as f32 * scale).ceil() as u32 + padding * 2;
+            let height = ((rect.y_max - rect.y_min) as f32 * scale).ceil() as u32 + padding * 2;
+            if width > settings.width {
+                return Err(MsdfAtlasError::GlyphTooWide(c));
+            }
+
+            if cursor_x + width > settings.width {
+                cursor_x = 0;
+                cursor_y += row_height;
+                row_height = 0;
+            }
+
+            // Font-space origin of the cell's top-left corner
+            let origin = Vec2::new(
+                rect.x_min as f32 - padding as f32 / scale,
+                rect.y_max as f32 + padding as f32 / scale,
+            );
+
+            cells.push(Cell {
+                c,
+                advance,
+                contours,
+                origin,
+                x: cursor_x,
+                y: cursor_y,
+                width,
+                height,
+            });
+
+            cursor_x += width;
+            row_height = row_height.max(height);
+        }
+
+        let atlas_width = settings.width;
+        let atlas_height = (cursor_y + row_height).max(1);
+        let size = Vec2::new(atlas_width as f32, atlas_height as f32);
+
+        // Rasterize distances
+        let mut data = vec![0; (atlas_width * atlas_height * 4) as usize];
+        for cell in cells {
+            let sign = if signed_area(&cell.contours) < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+
+            for y in 0..cell.height {
+                for x in 0..cell.width {
+                    let p = cell.origin + Vec2::new(x as f32 + 0.5, -(y as f32 + 0.5)) / scale;
+
+                    let texel = sample_msdf(&cell.contours, p, sign)
+                        .map(|d| d * scale / settings.distance_range + 0.5)
+                        .map(|d| (d.clamp(0.0, 1.0) * 255.0).round() as u8);
+
+                    let i = (((cell.y + y) * atlas_width + cell.x + x) * 4) as usize;
+                    data[i..i + 4].copy_from_slice(&texel);
+                }
+            }
+
+            let cell_min = Vec2::new(cell.x as f32, cell.y as f32);
+            let cell_size = Vec2::new(cell.width as f32, cell.height as f32);
+            let plane_size = cell_size / settings.em_size;
+            let plane_top_left = cell.origin / units_per_em;
+
+            glyphs.insert(
+                cell.c,
+                MsdfGlyph {
+                    advance: cell.advance,
+                    quad: Some(MsdfQuad {
+                        uv_min: cell_min / size,
+                        uv_max: (cell_min + cell_size) / size,
+                        plane_min: plane_top_left - Vec2::Y * plane_size.y,
+                        plane_max: plane_top_left + Vec2::X * plane_size.x,
+                    }),
+                },
+            );
+        }
+
+        let image = Image::new(
+            Extent3d {
+                width: atlas_width,
+                height: atlas_height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8Unorm,
+        );
+
+        Ok(MsdfAtlas {
+            image,
+            glyphs,
+            size,
+            distance_range: settings.distance_range,
+            line_height: (face.ascender() as f32 - face.descender() as f32
+                + face.line_gap() as f32)
+                / units_per_em,
+        })
+    }
+
+    /// Build a mesh of glyph quads for `text` at `font_size` world units per em.
+    ///
+    /// The first baseline starts at the origin, and each newline moves down by one line height.
+    pub fn text_mesh(&self, text: &str, font_size: f32) -> Mesh {
+        let mut positions = vec![];
+        let mut normals = vec![];
+        let mut uvs = vec![];
+        let mut indices = vec![];
+
+        let mut pen = Vec2::ZERO;
+        for c in text.chars() {
+            if c == '\n' {
+                pen = Vec2::new(0.0, pen.y - self.line_height);
+                continue;
+            }
+
+            let Some(glyph) = self.glyphs.get(&c) else {
+                continue;
+            };
+
+            if let Some(quad) = glyph.quad {
+                let min = (pen + quad.plane_min) * font_size;
+                let max = (pen + quad.plane_max) * font_size;
+
+                let base = positions.len() as u32;
+                positions.extend([
+                    [min.x, min.y, 0.0],
+                    [max.x, min.y, 0.0],
+                    [max.x, max.y, 0.0],
+                    [min.x, max.y, 0.0],
+                ]);
+                normals.extend([[0.0, 0.0, 1.0]; 4]);
+                uvs.extend([
+                    [quad.uv_min.x, quad.uv_max.y],
+                    [quad.uv_max.x, quad.uv_max.y],
+                    [quad.uv_max.x, quad.uv_min.y],
+                    [quad.uv_min.x, quad.uv_min.y],
+                ]);
+                indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+            }
+
+            pen.x += glyph.advance;
+        }
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.set_indices(Some(Indices::U32(indices)));
+        mesh
+    }
+}
+
+/// Glyph awaiting rasterization
+struct Cell {
+    c: char,
+    advance: f32,
+    contours: Vec<Vec<Edge>>,
+    origin: Vec2,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// Straight edge of a flattened glyph contour
+#[derive(Debug, Copy, Clone)]
+struct Edge {
+    a: Vec2,
+    b: Vec2,
+    color: u8,
+    /// Whether this edge begins a new outline segment, and thus may form a corner
+    segment_start: bool,
+}
+
+/// Distance from a point to an edge, ordered by magnitude with orthogonality as a tiebreak
+#[derive(Debug, Copy, Clone)]
+struct EdgeDistance {
+    dist: f32,
+    dot: f32,
+    t: f32,
+}
+
+impl EdgeDistance {
+    const MAX: Self = EdgeDistance {
+        dist: f32::MAX,
+        dot: 1.0,
+        t: 0.0,
+    };
+
+    fn closer_than(&self, rhs: &Self) -> bool {
+        let (l, r) = (self.dist.abs(), rhs.dist.abs());
+        l < r || (l == r && self.dot < rhs.dot)
+    }
+}
+
+impl Edge {
+    fn direction(&self) -> Vec2 {
+        (self.b - self.a).normalize_or_zero()
+    }
+
+    fn distance(&self, p: Vec2) -> EdgeDistance {
+        let ab = self.b - self.a;
+        let aq = p - self.a;
+        let t = aq.dot(ab) / ab.length_squared();
+
+        let eq = if t > 0.5 { self.b - p } else { self.a - p };
+        let endpoint_dist = eq.length();
+
+        if t > 0.0 && t < 1.0 {
+            let ortho = ab.normalize().perp_dot(aq);
+            if ortho.abs() < endpoint_dist {
+                return EdgeDistance {
+                    dist: ortho,
+                    dot: 0.0,
+                    t,
+                };
+            }
+        }
+
+        let sign = if ab.perp_dot(aq) < 0.0 { -1.0 } else { 1.0 };
+        EdgeDistance {
+            dist: sign * endpoint_dist,
+            dot: ab.normalize().dot(eq.normalize_or_zero()).abs(),
+            t,
+        }
+    }
+
+    /// Extend the edge past its endpoints, so adjacent same-colored edges meet in a sharp corner
+    fn pseudo_distance(&self, p: Vec2, distance: EdgeDistance) -> f32 {
+        let dir = self.direction();
+        let (origin, side) = if distance.t < 0.0 {
+            (self.a, -1.0)
+        } else if distance.t > 1.0 {
+            (self.b, 1.0)
+        } else {
+            return distance.dist;
+        };
+
+        let q = p - origin;
+        if q.dot(dir) * side > 0.0 {
+            let pseudo = dir.perp_dot(q);
+            if pseudo.abs() <= distance.dist.abs() {
+                return pseudo;
+            }
+        }
+
+        distance.dist
+    }
+}
+
+/// Collects a glyph outline as flattened contours
+#[derive(Default)]
+struct ShapeBuilder {
+    contours: Vec<Vec<Edge>>,
+    current: Vec<Edge>,
+    start: Vec2,
+    last: Vec2,
+    segment_start: bool,
+}
+
+impl ShapeBuilder {
+    fn push(&mut self, to: Vec2) {
+        if to.distance_squared(self.last) > f32::EPSILON {
+            self.current.push(Edge {
+                a: self.last,
+                b: to,
+                color: WHITE,
+                segment_start: self.segment_start,
+            });
+            self.segment_start = false;
+        }
+        self.last = to;
+    }
+
+    fn finish_contour(&mut self) {
+        let mut contour = std::mem::take(&mut self.current);
+        if !contour.is_empty() {
+            color_edges(&mut contour);
+            self.contours.push(contour);
+        }
+    }
+
+    fn finish(mut self) -> Vec<Vec<Edge>> {
+        self.finish_contour();
+        self.contours
+    }
+}
+
+impl OutlineBuilder for ShapeBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.finish_contour();
+        self.start = Vec2::new(x, y);
+        self.last = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.segment_start = true;
+        self.push(Vec2::new(x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (p0, p1, p2) = (self.last, Vec2::new(x1, y1), Vec2::new(x, y));
+        self.segment_start = true;
+        for i in 1..=CURVE_STEPS {
+            let t = i as f32 / CURVE_STEPS as f32;
+            let s = 1.0 - t;
+            self.push(p0 * s * s + p1 * 2.0 * s * t + p2 * t * t);
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (p0, p1, p2, p3) = (
+            self.last,
+            Vec2::new(x1, y1),
+            Vec2::new(x2, y2),
+            Vec2::new(x, y),
+        );
+        self.segment_start = true;
+        for i in 1..=CURVE_STEPS {
+            let t = i as f32 / CURVE_STEPS as f32;
+            let s = 1.0 - t;
+            self.push(
+                p0 * s * s * s + p1 * 3.0 * s * s * t + p2 * 3.0 * s * t * t + p3 * t * t * t,
+            );
+        }
+    }
+
+    fn close(&mut self) {
+        self.segment_start = true;
+        self.push(self.start);
+        self.finish_contour();
+    }
+}
+
+/// Assign channel colors so that every corner sits between two differently-colored edges
+fn color_edges(contour: &mut [Edge]) {
+    let n = contour.len();
+    let corners = (0..n)
+        .filter(|&i| {
+            let prev = contour[(i + n - 1) % n].direction();
+            let next = contour[i].direction();
+            contour[i].segment_start
+                && (prev.dot(next) <= 0.0 || prev.perp_dot(next).abs() > CORNER_SIN)
+        })
+        .collect::<Vec<_>>();
+
+    match corners.len() {
+        0 => contour.iter_mut().for_each(|edge| edge.color = WHITE),
+        1 => {
+            // Teardrop: split the contour into three runs around its single corner
+            let colors = [MAGENTA, WHITE, YELLOW];
+            for k in 0..n {
+                contour[(corners[0] + k) % n].color = colors[(k * 3 / n).min(2)];
+            }
+        }
+        count => {
+            let colors = [CYAN, MAGENTA, YELLOW];
+            let mut spline = 0;
+            for k in 0..n {
+                let i = (corners[0] + k) % n;
+                if spline + 1 < count && corners[spline + 1] == i {
+                    spline += 1;
+                }
+
+                // Avoid matching the first spline's color when wrapping around
+                let color = if spline == count - 1 && spline % 3 == 0 {
+                    1
+                } else {
+                    spline % 3
+                };
+                contour[i].color = colors[color];
+            }
+        }
+    }
+}
+
+/// Shoelace area of a shape, used to detect its winding convention
+fn signed_area(contours: &[Vec<Edge>]) -> f32 {
+    contours
+        .iter()
+        .flatten()
+        .map(|edge| edge.a.perp_dot(edge.b))
+        .sum::<f32>()
+        * 0.5
+}
+
+/// Non-zero winding test
+fn inside(contours: &[Vec<Edge>], p: Vec2) -> bool {
+    let mut winding = 0;
+    for edge in contours.iter().flatten() {
+        let side = (edge.b - edge.a).perp_dot(p - edge.a);
+        if edge.a.y <= p.y {
+            if edge.b.y > p.y && side > 0.0 {
+                winding += 1;
+            }
+        } else if edge.b.y <= p.y && side < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding != 0
+}
+
+/// Evaluate RGB pseudo-distances and the A true distance at `p`, in font units, positive inside
+fn sample_msdf(contours: &[Vec<Edge>], p: Vec2, sign: f32) -> [f32; 4] {
+    let mut closest = [(EdgeDistance::MAX, None::<&Edge>); 3];
+    let mut closest_true = EdgeDistance::MAX;
+
+    for edge in contours.iter().flatten() {
+        let distance = edge.distance(p);
+
+        if distance.closer_than(&closest_true) {
+            closest_true = distance;
+        }
+
+        for (channel, (closest, closest_edge)) in closest.iter_mut().enumerate() {
+            if edge.color & (1 << channel) != 0 && distance.closer_than(closest) {
+                *closest = distance;
+                *closest_edge = Some(edge);
+            }
+        }
+    }
+
+    let true_dist = if inside(contours, p) {
+        closest_true.dist.abs()
+    } else {
+        -closest_true.dist.abs()
+    };
+
+    let [r, g, b] = closest.map(|(distance, edge)| match edge {
+        Some(edge) => edge.pseudo_distance(p, distance) * sign,
+        None => true_dist,
+    });
+
+    // Fall back to the true distance where the channels disagree on which side of the edge we are
+    let median = r.max(g).min(r.min(g).max(b));
+    if (median > 0.0) != (true_dist > 0.0) {
+        [true_dist; 4]
+    } else {
+        [r, g, b, true_dist]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{math::Vec3, render::mesh::VertexAttributeValues};
+
+    use super::*;
+
+    /// Contours traced through `points` with straight edges
+    fn polygon(points: &[Vec2]) -> Vec<Vec<Edge>> {
+        let mut builder = ShapeBuilder::default();
+        builder.move_to(points[0].x, points[0].y);
+        for point in &points[1..] {
+            builder.line_to(point.x, point.y);
+        }
+        builder.close();
+        builder.finish()
+    }
+
+    /// Counter-clockwise square spanning `min` to `max`
+    fn square(min: Vec2, max: Vec2) -> Vec<Vec<Edge>> {
+        polygon(&[min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)])
+    }
+
+    fn sign(contours: &[Vec<Edge>]) -> f32 {
+        if signed_area(contours) < 0.0 {
+            -1.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Median of the RGB channels, as reconstructed by `fragment_msdf_text`
+    fn median([r, g, b, _]: [f32; 4]) -> f32 {
+        r.max(g).min(r.min(g).max(b))
+    }
+
+    #[test]
+    fn corners_separate_channels() {
+        for corners in 3..=8 {
+            let points = (0..corners)
+                .map(|i| {
+                    let angle = i as f32 / corners as f32 * std::f32::consts::TAU;
+                    Vec2::new(angle.cos(), angle.sin()) * 100.0
+                })
+                .collect::<Vec<_>>();
+
+            let contour = &polygon(&points)[0];
+            assert_eq!(contour.len(), corners);
+
+            for (i, edge) in contour.iter().enumerate() {
+                let next = &contour[(i + 1) % contour.len()];
+                assert_ne!(edge.color, next.color, "{corners} corners, edge {i}");
+                assert_eq!(edge.color.count_ones(), 2, "{corners} corners, edge {i}");
+            }
+        }
+    }
+
+    #[test]
+    fn smooth_contour_is_white() {
+        let mut builder = ShapeBuilder::default();
+        builder.move_to(100.0, 0.0);
+        builder.quad_to(100.0, 100.0, 0.0, 100.0);
+        builder.quad_to(-100.0, 100.0, -100.0, 0.0);
+        builder.quad_to(-100.0, -100.0, 0.0, -100.0);
+        builder.quad_to(100.0, -100.0, 100.0, 0.0);
+        builder.close();
+
+        let contours = builder.finish();
+        assert!(contours[0].iter().all(|edge| edge.color == WHITE));
+    }
+
+    #[test]
+    fn sample_sign_inside_and_outside() {
+        let ccw = square(Vec2::ZERO, Vec2::splat(100.0));
+        let mut cw = ccw.clone();
+        for contour in &mut cw {
+            contour.reverse();
+            for edge in contour.iter_mut() {
+                std::mem::swap(&mut edge.a, &mut edge.b);
+            }
+        }
+
+        for contours in [ccw, cw] {
+            let sign = sign(&contours);
+
+            let center = sample_msdf(&contours, Vec2::splat(50.0), sign);
+            assert!(median(center) > 0.0, "{center:?}");
+            assert!((center[3] - 50.0).abs() < 1e-3);
+
+            let near_edge = sample_msdf(&contours, Vec2::new(50.0, 10.0), sign);
+            assert!((near_edge[3] - 10.0).abs() < 1e-3);
+
+            // Single channels may extend past a corner, but their median stays outside
+            for p in [Vec2::new(150.0, 50.0), Vec2::new(-20.0, -20.0)] {
+                let outside = sample_msdf(&contours, p, sign);
+                assert!(
+                    median(outside) < 0.0 && outside[3] < 0.0,
+                    "{p}: {outside:?}"
+                );
+            }
+
+            let beside = sample_msdf(&contours, Vec2::new(150.0, 50.0), sign);
+            assert!((beside[3] + 50.0).abs() < 1e-3);
+        }
+    }
+
+    fn test_atlas() -> MsdfAtlas {
+        let quad = MsdfQuad {
+            uv_min: Vec2::ZERO,
+            uv_max: Vec2::ONE,
+            plane_min: Vec2::new(0.1, -0.2),
+            plane_max: Vec2::new(0.5, 0.7),
+        };
+
+        let mut glyphs = HashMap::default();
+        glyphs.insert(
+            'a',
+            MsdfGlyph {
+                advance: 0.6,
+                quad: Some(quad),
+            },
+        );
+        glyphs.insert(
+            ' ',
+            MsdfGlyph {
+                advance: 0.25,
+                quad: None,
+            },
+        );
+
+        MsdfAtlas {
+            image: Image::default(),
+            glyphs,
+            size: Vec2::ONE,
+            distance_range: 4.0,
+            line_height: 1.2,
+        }
+    }
+
+    #[test]
+    fn text_mesh_quads_and_advances() {
+        let mesh = test_atlas().text_mesh("a a\naz", 10.0);
+
+        // Spaces and newlines advance without a quad, and unknown characters are skipped
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("Missing positions");
+        };
+        assert_eq!(positions.len(), 3 * 4);
+
+        let Some(Indices::U32(indices)) = mesh.indices() else {
+            panic!("Missing indices");
+        };
+        assert_eq!(indices.len(), 3 * 6);
+        assert_eq!(&indices[6..12], &[4, 5, 6, 4, 6, 7]);
+
+        let min_corners = positions.iter().step_by(4).collect::<Vec<_>>();
+        let expected = [
+            [1.0, -2.0, 0.0],
+            [(0.6 + 0.25 + 0.1) * 10.0, -2.0, 0.0],
+            [1.0, (-1.2 - 0.2) * 10.0, 0.0],
+        ];
+        for (corner, expected) in min_corners.into_iter().zip(expected) {
+            assert!(Vec3::from(*corner).abs_diff_eq(Vec3::from(expected), 1e-5));
+        }
+
+        assert!(Vec3::from(positions[2]).abs_diff_eq(Vec3::new(5.0, 7.0, 0.0), 1e-5));
+    }
+
+    #[test]
+    fn bakes_committed_font() {
+        let font = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/assets/fonts/FiraSans-Medium.ttf"
+        ));
+        let settings = MsdfAtlasSettings {
+            em_size: 16.0,
+            charset: "Ag ".into(),
+            ..Default::default()
+        };
+
+        let atlas = MsdfAtlas::from_ttf(font, &settings).unwrap();
+        assert!(atlas.glyphs[&'A'].quad.is_some());
+        assert!(atlas.glyphs[&'g'].quad.is_some());
+        assert!(atlas.glyphs[&' '].quad.is_none());
+        assert!(atlas.glyphs[&' '].advance > 0.0);
+        assert!(atlas.line_height > 1.0);
+    }
+}
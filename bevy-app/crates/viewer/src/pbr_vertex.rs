@@ -0,0 +1,190 @@
+//! `StandardMaterial` drawn with a custom vertex entry point.
+//!
+//! [`StandardMaterialWithVertex<V>`] binds exactly what `StandardMaterial` binds,
+//! and pairs vertex entry point `V` with the PBR fragment used by `RustGpu<StandardMaterial>`.
+//! `V` must write world position as a `Vec4`, world normal and UV, in that order.
+//! It passes no tangents or vertex colors through, so normal-mapped materials and meshes with vertex colors
+//! fail specialization rather than reading fragment inputs `V` never writes.
+
+use std::marker::PhantomData;
+
+use bevy::{
+    pbr::{MaterialPipeline, MaterialPipelineKey},
+    prelude::{error, AlphaMode, Image, Material, Mesh, StandardMaterial, Vec3},
+    reflect::TypeUuid,
+    render::{
+        mesh::MeshVertexBufferLayout,
+        render_asset::RenderAssets,
+        render_resource::{
+            AsBindGroup, AsBindGroupError, BindGroupLayout, Face, PreparedBindGroup,
+            RenderPipelineDescriptor, ShaderDefVal, SpecializedMeshPipelineError,
+        },
+        renderer::RenderDevice,
+        texture::FallbackImage,
+    },
+    utils::Uuid,
+};
+use bevy_rust_gpu::{EntryPoint, RustGpuMaterial};
+use shader_shared::warp::PBR_WARP;
+
+use crate::{
+    deformed_bounds::VertexDeformation,
+    specialize::{specialize_rust_gpu, EntryPointOverrides},
+    vertex_inputs::unsupported_input_error,
+};
+
+/// Shader defs selecting fragment inputs that `V` doesn't write
+const UNSUPPORTED_FRAGMENT_DEFS: [&str; 2] = ["VERTEX_TANGENTS", "VERTEX_COLORS"];
+
+/// A `StandardMaterial` whose vertex stage is replaced by entry point `V`
+#[derive(Debug, Clone)]
+pub struct StandardMaterialWithVertex<V> {
+    pub base: StandardMaterial,
+    pub _phantom: PhantomData<V>,
+}
+
+impl<V> Default for StandardMaterialWithVertex<V> {
+    fn default() -> Self {
+        StandardMaterial::default().into()
+    }
+}
+
+impl<V> From<StandardMaterial> for StandardMaterialWithVertex<V> {
+    fn from(base: StandardMaterial) -> Self {
+        StandardMaterialWithVertex {
+            base,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Pipeline key for [`StandardMaterialWithVertex`],
+/// carrying the parts of `StandardMaterial`'s own key its specialization reads
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StandardMaterialWithVertexKey {
+    normal_map: bool,
+    cull_mode: Option<Face>,
+    depth_bias: i32,
+}
+
+impl<V> AsBindGroup for StandardMaterialWithVertex<V>
+where
+    V: Send + Sync + 'static,
+{
+    type Data = StandardMaterialWithVertexKey;
+
+    fn as_bind_group(
+        &self,
+        layout: &BindGroupLayout,
+        render_device: &RenderDevice,
+        images: &RenderAssets<Image>,
+        fallback_image: &FallbackImage,
+    ) -> Result<PreparedBindGroup<Self>, AsBindGroupError> {
+        let prepared = self
+            .base
+            .as_bind_group(layout, render_device, images, fallback_image)?;
+
+        Ok(PreparedBindGroup {
+            bindings: prepared.bindings,
+            bind_group: prepared.bind_group,
+            data: StandardMaterialWithVertexKey {
+                normal_map: self.base.normal_map_texture.is_some(),
+                cull_mode: self.base.cull_mode,
+                depth_bias: self.base.depth_bias as i32,
+            },
+        })
+    }
+
+    fn bind_group_layout(render_device: &RenderDevice) -> BindGroupLayout {
+        StandardMaterial::bind_group_layout(render_device)
+    }
+}
+
+impl<V> Material for StandardMaterialWithVertex<V>
+where
+    V: EntryPoint,
+    Self: TypeUuid,
+{
+    fn alpha_mode(&self) -> AlphaMode {
+        self.base.alpha_mode
+    }
+
+    fn depth_bias(&self) -> f32 {
+        self.base.depth_bias
+    }
+
+    // Matches `StandardMaterial::specialize`, whose key fields are private,
+    // except for the fragment inputs `V` can't provide
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        if key.bind_group_data.normal_map {
+            error!(
+                "{} doesn't output tangents, so it can't draw normal maps",
+                V::NAME
+            );
+            return Err(unsupported_input_error(
+                layout,
+                "Vertex_Tangent output for a normal map, which StandardMaterialWithVertex doesn't support",
+            ));
+        }
+
+        if layout.contains(Mesh::ATTRIBUTE_COLOR) {
+            error!(
+                "{} doesn't output vertex colors, so it can't draw meshes with them",
+                V::NAME
+            );
+            return Err(unsupported_input_error(
+                layout,
+                "Vertex_Color output, which StandardMaterialWithVertex doesn't support",
+            ));
+        }
+
+        // Bevy's mesh pipeline adds these from the mesh layout, but `V` doesn't write the inputs they enable
+        if let Some(fragment) = descriptor.fragment.as_mut() {
+            fragment.shader_defs.retain(|def| {
+                let (ShaderDefVal::Bool(name, _)
+                | ShaderDefVal::Int(name, _)
+                | ShaderDefVal::UInt(name, _)) = def;
+                !UNSUPPORTED_FRAGMENT_DEFS.contains(&name.as_str())
+            });
+        }
+
+        descriptor.primitive.cull_mode = key.bind_group_data.cull_mode;
+
+        if let Some(depth_stencil) = descriptor.depth_stencil.as_mut() {
+            depth_stencil.bias.constant = key.bind_group_data.depth_bias;
+        }
+
+        specialize_rust_gpu::<Self>(descriptor, layout, &EntryPointOverrides::default())
+    }
+}
+
+impl<V> RustGpuMaterial for StandardMaterialWithVertex<V>
+where
+    V: EntryPoint,
+    Self: TypeUuid,
+{
+    type Vertex = V;
+    type Fragment = <StandardMaterial as RustGpuMaterial>::Fragment;
+}
+
+/// Marker type describing the `vertex_warp_pbr` entrypoint from the shader crate
+pub enum VertexWarpPbr {}
+
+impl EntryPoint for VertexWarpPbr {
+    const NAME: &'static str = "vertex_warp_pbr";
+}
+
+impl TypeUuid for StandardMaterialWithVertex<VertexWarpPbr> {
+    const TYPE_UUID: Uuid = Uuid::from_u128(0x27d5_b9c1_6e3a_4f08_8c42_a1e9_5d7b_f301);
+}
+
+impl VertexDeformation for StandardMaterialWithVertex<VertexWarpPbr> {
+    fn deform_position(&self, position: Vec3, time: f32) -> Vec3 {
+        PBR_WARP.position(position, time)
+    }
+}
@@ -0,0 +1,30 @@
+//! Depth and normal prepass entry points for Rust-GPU materials.
+//!
+//! Bevy 0.10 only draws built-in materials into the prepass.
+//! [`RustGpuPrepassMaterial`] lets a [`RustGpuMaterial`] opt in by naming the entry points
+//! that reproduce its geometry there: a vertex entry point binding the prepass view layout,
+//! and a fragment entry point writing the remapped normal, and depth for raymarched materials.
+//!
+//! Materials install them by calling `specialize::specialize_rust_gpu_prepass` from `Material::specialize`.
+
+use bevy_rust_gpu::{EntryPoint, RustGpuMaterial};
+
+/// A [`RustGpuMaterial`] that can be drawn into the depth and normal prepass
+pub trait RustGpuPrepassMaterial: RustGpuMaterial {
+    type PrepassVertex: EntryPoint;
+    type PrepassFragment: EntryPoint;
+}
+
+/// Marker type describing the `vertex_warp_prepass` entrypoint from the shader crate
+pub enum VertexWarpPrepass {}
+
+impl EntryPoint for VertexWarpPrepass {
+    const NAME: &'static str = "vertex_warp_prepass";
+}
+
+/// Marker type describing the `fragment_normal_prepass` entrypoint from the shader crate
+pub enum FragmentNormalPrepass {}
+
+impl EntryPoint for FragmentNormalPrepass {
+    const NAME: &'static str = "fragment_normal_prepass";
+}
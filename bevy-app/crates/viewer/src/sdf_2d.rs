@@ -0,0 +1,267 @@
+//! CPU-side queries against the same 2D fields the shader crate draws.
+//!
+//! Attach an [`Sdf2d`] component to an entity, add [`Sdf2dPlugin`],
+//! and use the [`Sdf2dQuery`] system param to evaluate the union of all SDF entities in world space.
+
+use bevy::{
+    ecs::system::SystemParam,
+    math::{Affine3A, Vec2},
+    prelude::{
+        Added, App, Changed, Component, CoreSet, Entity, GlobalTransform, IntoSystemConfig, Or,
+        Plugin, Query,
+    },
+    transform::TransformSystem,
+};
+use rust_gpu_sdf::prelude::{AttrDistance, Field, FieldAttribute};
+
+/// Offset used to estimate gradients by central differences
+const GRADIENT_EPSILON: f32 = 0.001;
+
+/// Number of steps taken by [`Sdf2dQuery::ray_cast`] before giving up
+const MAX_RAY_STEPS: usize = 256;
+
+/// Distance at which a ray is considered to have hit a surface
+const RAY_HIT_EPSILON: f32 = 0.0001;
+
+/// Type-erased 2D signed distance field attached to an entity.
+///
+/// The field is evaluated in the entity's local space.
+#[derive(Component)]
+pub struct Sdf2d {
+    field: Box<dyn Fn(Vec2) -> f32 + Send + Sync>,
+    world_to_local: Affine3A,
+    scale: f32,
+}
+
+impl Sdf2d {
+    pub fn new<T>(sdf: T) -> Self
+    where
+        T: Field<AttrDistance<Vec2>> + Send + Sync + 'static,
+    {
+        Sdf2d {
+            field: Box::new(move |p| *sdf.field_attribute::<AttrDistance<Vec2>>(&p.into())),
+            world_to_local: Affine3A::IDENTITY,
+            scale: 1.0,
+        }
+    }
+
+    /// Signed distance from world-space point `p` to this field's surface
+    pub fn distance(&self, p: Vec2) -> f32 {
+        let local = self
+            .world_to_local
+            .transform_point3(p.extend(0.0))
+            .truncate();
+        (self.field)(local) * self.scale
+    }
+
+    /// World-space gradient at `p`, which is the outward surface normal for a true distance field
+    pub fn gradient(&self, p: Vec2) -> Vec2 {
+        let dx = Vec2::X * GRADIENT_EPSILON;
+        let dy = Vec2::Y * GRADIENT_EPSILON;
+        Vec2::new(
+            self.distance(p + dx) - self.distance(p - dx),
+            self.distance(p + dy) - self.distance(p - dy),
+        )
+        .normalize_or_zero()
+    }
+}
+
+/// Keeps each [`Sdf2d`]'s cached world-to-local transform in sync with its [`GlobalTransform`]
+pub struct Sdf2dPlugin;
+
+impl Plugin for Sdf2dPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            update_sdf_2d_transforms
+                .in_base_set(CoreSet::PostUpdate)
+                .after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+fn update_sdf_2d_transforms(
+    mut query: Query<(&mut Sdf2d, &GlobalTransform), Or<(Added<Sdf2d>, Changed<GlobalTransform>)>>,
+) {
+    for (mut sdf, transform) in query.iter_mut() {
+        let (scale, _, _) = transform.to_scale_rotation_translation();
+        sdf.world_to_local = transform.affine().inverse();
+        // Smallest axis keeps the scaled field a conservative bound under non-uniform scale
+        sdf.scale = scale.x.abs().min(scale.y.abs());
+    }
+}
+
+/// Distance from a point to the nearest SDF entity
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Sdf2dDistance {
+    pub entity: Entity,
+    pub distance: f32,
+}
+
+/// Intersection of a ray with an SDF entity
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Sdf2dRayHit {
+    pub entity: Entity,
+    /// Distance along the ray
+    pub t: f32,
+    pub point: Vec2,
+    pub normal: Vec2,
+}
+
+/// System param for querying the union of all [`Sdf2d`] entities in world space
+#[derive(SystemParam)]
+pub struct Sdf2dQuery<'w, 's> {
+    query: Query<'w, 's, (Entity, &'static Sdf2d)>,
+}
+
+impl<'w, 's> Sdf2dQuery<'w, 's> {
+    /// Signed distance from `p` to the nearest SDF entity, or `None` if there are none
+    pub fn distance(&self, p: Vec2) -> Option<Sdf2dDistance> {
+        self.query
+            .iter()
+            .map(|(entity, sdf)| Sdf2dDistance {
+                entity,
+                distance: sdf.distance(p),
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /// Signed distance from `p` to a specific SDF entity
+    pub fn distance_to(&self, entity: Entity, p: Vec2) -> Option<f32> {
+        self.query.get(entity).ok().map(|(_, sdf)| sdf.distance(p))
+    }
+
+    /// Closest surface point to `p` on the nearest SDF entity
+    pub fn closest_point(&self, p: Vec2) -> Option<(Entity, Vec2)> {
+        let Sdf2dDistance { entity, distance } = self.distance(p)?;
+        let (_, sdf) = self.query.get(entity).ok()?;
+        Some((entity, p - sdf.gradient(p) * distance))
+    }
+
+    /// The entity containing `p`, if any
+    pub fn inside(&self, p: Vec2) -> Option<Entity> {
+        self.distance(p)
+            .filter(|hit| hit.distance <= 0.0)
+            .map(|hit| hit.entity)
+    }
+
+    /// Sphere-trace a ray from `origin` along `direction` for up to `max_t` world units
+    pub fn ray_cast(&self, origin: Vec2, direction: Vec2, max_t: f32) -> Option<Sdf2dRayHit> {
+        let direction = direction.normalize_or_zero();
+        if direction == Vec2::ZERO {
+            return None;
+        }
+
+        let mut t = 0.0;
+        for _ in 0..MAX_RAY_STEPS {
+            let point = origin + direction * t;
+            let Sdf2dDistance { entity, distance } = self.distance(point)?;
+
+            if distance.abs() < RAY_HIT_EPSILON {
+                let (_, sdf) = self.query.get(entity).ok()?;
+                return Some(Sdf2dRayHit {
+                    entity,
+                    t,
+                    point,
+                    normal: sdf.gradient(point),
+                });
+            }
+
+            // Rays starting inside a field march out toward its surface
+            t += distance.abs();
+            if t > max_t {
+                break;
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::system::SystemState,
+        prelude::{GlobalTransform, Schedule, Transform, World},
+    };
+    use rust_gpu_sdf::prelude::Circle;
+
+    use super::*;
+
+    /// World with unit circles centered at `centers`, transforms already synced
+    fn world_with_circles(centers: &[Vec2]) -> (World, Vec<Entity>) {
+        let mut world = World::new();
+        let entities = centers
+            .iter()
+            .map(|center| {
+                world
+                    .spawn((
+                        Sdf2d::new(Circle::default()),
+                        GlobalTransform::from(Transform::from_translation(center.extend(0.0))),
+                    ))
+                    .id()
+            })
+            .collect();
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(update_sdf_2d_transforms);
+        schedule.run(&mut world);
+
+        (world, entities)
+    }
+
+    #[test]
+    fn inside_matches_circle() {
+        let (mut world, entities) = world_with_circles(&[Vec2::ZERO, Vec2::new(4.0, 0.0)]);
+        let mut state = SystemState::<Sdf2dQuery>::new(&mut world);
+        let query = state.get(&world);
+
+        assert_eq!(query.inside(Vec2::new(0.5, 0.5)), Some(entities[0]));
+        assert_eq!(query.inside(Vec2::new(4.0, -0.9)), Some(entities[1]));
+        assert_eq!(query.inside(Vec2::new(2.0, 0.0)), None);
+        assert_eq!(query.inside(Vec2::new(0.8, 0.8)), None);
+    }
+
+    #[test]
+    fn distance_and_closest_point() {
+        let (mut world, entities) = world_with_circles(&[Vec2::new(4.0, 0.0)]);
+        let mut state = SystemState::<Sdf2dQuery>::new(&mut world);
+        let query = state.get(&world);
+
+        let nearest = query.distance(Vec2::ZERO).unwrap();
+        assert_eq!(nearest.entity, entities[0]);
+        assert!((nearest.distance - 3.0).abs() < 1e-4);
+
+        let (entity, point) = query.closest_point(Vec2::ZERO).unwrap();
+        assert_eq!(entity, entities[0]);
+        assert!(point.abs_diff_eq(Vec2::new(3.0, 0.0), 1e-3));
+    }
+
+    #[test]
+    fn ray_cast_hits_nearest_surface() {
+        let (mut world, entities) = world_with_circles(&[Vec2::new(4.0, 0.0), Vec2::new(8.0, 0.0)]);
+        let mut state = SystemState::<Sdf2dQuery>::new(&mut world);
+        let query = state.get(&world);
+
+        let hit = query.ray_cast(Vec2::ZERO, Vec2::X, 100.0).unwrap();
+        assert_eq!(hit.entity, entities[0]);
+        assert!((hit.t - 3.0).abs() < 1e-3);
+        assert!(hit.point.abs_diff_eq(Vec2::new(3.0, 0.0), 1e-3));
+        assert!(hit.normal.abs_diff_eq(-Vec2::X, 1e-2));
+    }
+
+    #[test]
+    fn ray_cast_misses() {
+        let (mut world, _) = world_with_circles(&[Vec2::new(4.0, 0.0)]);
+        let mut state = SystemState::<Sdf2dQuery>::new(&mut world);
+        let query = state.get(&world);
+
+        // Passes above the circle
+        assert_eq!(query.ray_cast(Vec2::new(0.0, 2.0), Vec2::X, 100.0), None);
+        // Stops short of it
+        assert_eq!(query.ray_cast(Vec2::ZERO, Vec2::X, 2.0), None);
+        // Points away from it
+        assert_eq!(query.ray_cast(Vec2::ZERO, -Vec2::X, 100.0), None);
+        // Has no direction
+        assert_eq!(query.ray_cast(Vec2::ZERO, Vec2::ZERO, 100.0), None);
+    }
+}
@@ -0,0 +1,342 @@
+//! Signed distance texture baking for raster sprites.
+//!
+//! Converts a sprite's alpha channel into a signed distance field using an exact
+//! Euclidean distance transform (Felzenszwalb & Huttenlocher), for use with `fragment_sdf_sprite`.
+//!
+//! The baked image keeps the sprite's format. Alpha holds the encoded distance,
+//! where 0.5 lies on the sprite's edge and values above it are inside,
+//! and RGB is dilated outward from the nearest opaque texel so filtering never bleeds in transparent color.
+
+use bevy::render::{
+    render_resource::{Extent3d, TextureDimension, TextureFormat},
+    texture::Image,
+};
+
+/// Parameters for [`bake_sdf`]
+#[derive(Debug, Copy, Clone)]
+pub struct SdfBakeSettings {
+    /// Alpha value at or above which a texel counts as inside the sprite
+    pub threshold: f32,
+    /// Width of the encoded distance range in texels
+    pub spread: f32,
+    /// Transparent border added around the sprite to make room for outlines and glow, in texels
+    pub padding: u32,
+}
+
+impl Default for SdfBakeSettings {
+    fn default() -> Self {
+        SdfBakeSettings {
+            threshold: 0.5,
+            spread: 32.0,
+            padding: 16,
+        }
+    }
+}
+
+/// Bake the alpha channel of `image` into a signed distance texture.
+///
+/// Returns `None` if `image` is not an 8-bit RGBA texture.
+pub fn bake_sdf(image: &Image, settings: &SdfBakeSettings) -> Option<Image> {
+    let format = image.texture_descriptor.format;
+    if !matches!(
+        format,
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb
+    ) {
+        return None;
+    }
+
+    let size = image.texture_descriptor.size;
+    let padding = settings.padding as usize;
+    let (src_width, src_height) = (size.width as usize, size.height as usize);
+    let (width, height) = (src_width + padding * 2, src_height + padding * 2);
+
+    // Pad source texels into the output grid
+    let mut texels = vec![[0u8; 4]; width * height];
+    for y in 0..src_height {
+        for x in 0..src_width {
+            let i = (y * src_width + x) * 4;
+            texels[(y + padding) * width + x + padding].copy_from_slice(&image.data[i..i + 4]);
+        }
+    }
+
+    let threshold = (settings.threshold * 255.0) as u8;
+    let inside = texels
+        .iter()
+        .map(|texel| texel[3] >= threshold)
+        .collect::<Vec<_>>();
+
+    let to_inside = distance_transform(&inside, width, height);
+    let to_outside = distance_transform(
+        &inside.iter().map(|i| !i).collect::<Vec<_>>(),
+        width,
+        height,
+    );
+
+    let mut data = Vec::with_capacity(width * height * 4);
+    for i in 0..width * height {
+        // Distances are measured between texel centers, so the edge lies half a texel away
+        let (dist, color) = if inside[i] {
+            (to_outside[i].0.sqrt() - 0.5, texels[i])
+        } else {
+            let (dist, nearest) = to_inside[i];
+            (0.5 - dist.sqrt(), texels[nearest])
+        };
+
+        let encoded = (dist as f32 / settings.spread + 0.5).clamp(0.0, 1.0);
+        data.extend_from_slice(&color[..3]);
+        data.push((encoded * 255.0).round() as u8);
+    }
+
+    Some(Image::new(
+        Extent3d {
+            width: width as u32,
+            height: height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+    ))
+}
+
+/// Large finite stand-in for infinity, so parabola intersections stay well-defined
+const FAR: f64 = 1e20;
+
+/// Exact squared Euclidean distance from each texel to the nearest `site` texel,
+/// paired with the index of that texel
+fn distance_transform(sites: &[bool], width: usize, height: usize) -> Vec<(f64, usize)> {
+    let n = width.max(height);
+    let mut f = vec![0.0; n];
+    let mut d = vec![0.0; n];
+    let mut arg = vec![0; n];
+
+    // Columns
+    let mut column_dist = vec![0.0; width * height];
+    let mut column_arg = vec![0; width * height];
+    for x in 0..width {
+        for y in 0..height {
+            f[y] = if sites[y * width + x] { 0.0 } else { FAR };
+        }
+
+        distance_transform_1d(&f[..height], &mut d[..height], &mut arg[..height]);
+
+        for y in 0..height {
+            column_dist[y * width + x] = d[y];
+            column_arg[y * width + x] = arg[y];
+        }
+    }
+
+    // Rows
+    let mut out = vec![(0.0, 0); width * height];
+    for y in 0..height {
+        f[..width].copy_from_slice(&column_dist[y * width..(y + 1) * width]);
+
+        distance_transform_1d(&f[..width], &mut d[..width], &mut arg[..width]);
+
+        for x in 0..width {
+            let nearest_x = arg[x];
+            let nearest_y = column_arg[y * width + nearest_x];
+            out[y * width + x] = (d[x], nearest_y * width + nearest_x);
+        }
+    }
+
+    out
+}
+
+/// One-dimensional squared distance transform of sampled function `f`,
+/// computed as the lower envelope of parabolas rooted at each sample
+fn distance_transform_1d(f: &[f64], d: &mut [f64], arg: &mut [usize]) {
+    let n = f.len();
+    if n == 0 {
+        return;
+    }
+
+    let mut v = vec![0; n];
+    let mut z = vec![0.0; n + 1];
+    let mut k = 0;
+
+    z[0] = -f64::INFINITY;
+    z[1] = f64::INFINITY;
+
+    let intersect = |q: usize, p: usize| {
+        let (qf, pf) = (q as f64, p as f64);
+        ((f[q] + qf * qf) - (f[p] + pf * pf)) / (2.0 * qf - 2.0 * pf)
+    };
+
+    for q in 1..n {
+        let mut s = intersect(q, v[k]);
+        while s <= z[k] {
+            k -= 1;
+            s = intersect(q, v[k]);
+        }
+
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = f64::INFINITY;
+    }
+
+    k = 0;
+    for q in 0..n {
+        while z[k + 1] < q as f64 {
+            k += 1;
+        }
+
+        let delta = q as f64 - v[k] as f64;
+        d[q] = delta * delta + f[v[k]];
+        arg[q] = v[k];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Squared distance from every texel to its nearest site, by exhaustive search
+    fn brute_force(sites: &[bool], width: usize) -> Vec<f64> {
+        let positions = sites
+            .iter()
+            .enumerate()
+            .filter(|(_, site)| **site)
+            .map(|(i, _)| ((i % width) as f64, (i / width) as f64))
+            .collect::<Vec<_>>();
+
+        (0..sites.len())
+            .map(|i| {
+                let (x, y) = ((i % width) as f64, (i / width) as f64);
+                positions
+                    .iter()
+                    .map(|(sx, sy)| (x - sx).powi(2) + (y - sy).powi(2))
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn single_site_is_exact() {
+        let (width, height) = (13, 9);
+        let site = 6 * width + 4;
+        let mut sites = vec![false; width * height];
+        sites[site] = true;
+
+        let transform = distance_transform(&sites, width, height);
+        for (i, (dist, nearest)) in transform.into_iter().enumerate() {
+            let dx = (i % width) as f64 - 4.0;
+            let dy = (i / width) as f64 - 6.0;
+            assert_eq!(dist, dx * dx + dy * dy, "texel {i}");
+            assert_eq!(nearest, site);
+        }
+    }
+
+    #[test]
+    fn scattered_sites_match_brute_force() {
+        let (width, height) = (23, 17);
+
+        // Deterministic pseudo-random mask
+        let mut state = 0x2545_f491_u32;
+        let sites = (0..width * height)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state % 11 == 5
+            })
+            .collect::<Vec<_>>();
+
+        let transform = distance_transform(&sites, width, height);
+        let expected = brute_force(&sites, width);
+        for (i, ((dist, nearest), expected)) in transform.into_iter().zip(expected).enumerate() {
+            assert_eq!(dist, expected, "texel {i}");
+            assert!(sites[nearest]);
+
+            let (dx, dy) = (
+                (i % width) as f64 - (nearest % width) as f64,
+                (i / width) as f64 - (nearest / width) as f64,
+            );
+            assert_eq!(dx * dx + dy * dy, dist, "texel {i}");
+        }
+    }
+
+    #[test]
+    fn empty_and_full_masks() {
+        let (width, height) = (7, 5);
+
+        let empty = distance_transform(&vec![false; width * height], width, height);
+        assert!(empty.iter().all(|(dist, _)| *dist >= FAR));
+
+        let full = distance_transform(&vec![true; width * height], width, height);
+        for (i, (dist, nearest)) in full.into_iter().enumerate() {
+            assert_eq!(dist, 0.0);
+            assert_eq!(nearest, i);
+        }
+    }
+
+    /// 8x8 sprite with an opaque 4x4 red square in its center
+    fn square_sprite(format: TextureFormat) -> Image {
+        let mut data = vec![0; 8 * 8 * 4];
+        for y in 2..6 {
+            for x in 2..6 {
+                let i = (y * 8 + x) * 4;
+                data[i..i + 4].copy_from_slice(&[255, 0, 0, 255]);
+            }
+        }
+
+        Image::new(
+            Extent3d {
+                width: 8,
+                height: 8,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            format,
+        )
+    }
+
+    #[test]
+    fn bake_signs_and_pads() {
+        let settings = SdfBakeSettings {
+            threshold: 0.5,
+            spread: 16.0,
+            padding: 3,
+        };
+
+        let baked = bake_sdf(&square_sprite(TextureFormat::Rgba8Unorm), &settings).unwrap();
+        let size = baked.texture_descriptor.size;
+        assert_eq!((size.width, size.height), (14, 14));
+        assert_eq!(baked.texture_descriptor.format, TextureFormat::Rgba8Unorm);
+
+        let texel = |x: usize, y: usize| {
+            let i = (y * 14 + x) * 4;
+            [
+                baked.data[i],
+                baked.data[i + 1],
+                baked.data[i + 2],
+                baked.data[i + 3],
+            ]
+        };
+
+        // The square spans texels 5..9 once padded, with its edge half a texel outside them
+        let encode = |dist: f32| ((dist / settings.spread + 0.5) * 255.0).round() as u8;
+        assert_eq!(texel(5, 7)[3], encode(0.5));
+        assert_eq!(texel(6, 7)[3], encode(1.5));
+        assert_eq!(texel(4, 7)[3], encode(-0.5));
+        assert_eq!(texel(0, 7)[3], encode(-4.5));
+        assert_eq!(texel(0, 0)[3], encode(0.5 - 50f32.sqrt()));
+
+        // Outside texels take the color of the nearest inside texel
+        assert_eq!(texel(0, 0), [255, 0, 0, encode(0.5 - 50f32.sqrt())]);
+        assert_eq!(&texel(13, 13)[..3], &[255, 0, 0]);
+    }
+
+    #[test]
+    fn bake_rejects_other_formats() {
+        let image = square_sprite(TextureFormat::Rgba8Unorm);
+        let mut other = image.clone();
+        other.texture_descriptor.format = TextureFormat::R8Unorm;
+        assert!(bake_sdf(&other, &SdfBakeSettings::default()).is_none());
+
+        let srgb = square_sprite(TextureFormat::Rgba8UnormSrgb);
+        assert!(bake_sdf(&srgb, &SdfBakeSettings::default()).is_some());
+    }
+}
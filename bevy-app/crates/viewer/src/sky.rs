@@ -0,0 +1,236 @@
+//! Procedural Rayleigh / Mie sky, drawn behind the scene.
+//!
+//! [`SkyPlugin`] spawns a camera-following sphere shaded by `sky::vertex_sky` and `sky::fragment_sky`,
+//! which take the sun direction from the first [`DirectionalLight`].
+//! It can also evaluate the same atmosphere on the CPU to drive [`AmbientLight`],
+//! which PBR materials and `fragment_sdf_3d` pick up as ambient.
+
+use bevy::{
+    pbr::{MaterialPipeline, MaterialPipelineKey},
+    prelude::{
+        default, shape::UVSphere, AmbientLight, App, AssetServer, Assets, Camera, Color, Commands,
+        Component, DirectionalLight, GlobalTransform, Handle, IntoSystemConfig, Material,
+        MaterialMeshBundle, Mesh, Plugin, Query, Res, ResMut, Transform, Vec3, With, Without,
+    },
+    reflect::TypeUuid,
+    render::{
+        mesh::MeshVertexBufferLayout,
+        render_resource::{AsBindGroup, RenderPipelineDescriptor, SpecializedMeshPipelineError},
+        view::NoFrustumCulling,
+    },
+};
+use bevy_rust_gpu::{
+    prelude::{RustGpu, RustGpuMaterialPlugin},
+    EntryPoint, RustGpuBuilderOutput, RustGpuMaterial,
+};
+use shader_shared::atmosphere::Atmosphere;
+
+use crate::specialize::{specialize_rust_gpu, RustGpuShadersPlugin};
+
+/// Marker type describing the `sky::vertex_sky` entrypoint from the shader crate
+pub enum VertexSky {}
+
+impl EntryPoint for VertexSky {
+    const NAME: &'static str = "sky::vertex_sky";
+}
+
+/// Marker type describing the `sky::fragment_sky` entrypoint from the shader crate
+pub enum FragmentSky {}
+
+impl EntryPoint for FragmentSky {
+    const NAME: &'static str = "sky::fragment_sky";
+}
+
+/// Sky material tying together [`VertexSky`] and [`FragmentSky`].
+///
+/// The leading fields mirror `shader_shared::atmosphere::Atmosphere`.
+#[derive(Debug, Copy, Clone, AsBindGroup, TypeUuid)]
+#[uuid = "6a1d8e3f-4b72-49c0-9e15-d27b3f8a05c6"]
+pub struct SkyMaterial {
+    /// Rayleigh scattering coefficients at sea level, per color channel
+    #[uniform(0)]
+    pub rayleigh_scattering: Vec3,
+    /// Altitude over which Rayleigh density falls by `1 / e`
+    #[uniform(0)]
+    pub rayleigh_scale_height: f32,
+    #[uniform(0)]
+    pub planet_radius: f32,
+    /// Radius of the outer edge of the atmosphere
+    #[uniform(0)]
+    pub atmosphere_radius: f32,
+    /// Mie scattering coefficient at sea level
+    #[uniform(0)]
+    pub mie_scattering: f32,
+    /// Altitude over which Mie density falls by `1 / e`
+    #[uniform(0)]
+    pub mie_scale_height: f32,
+    /// Mie anisotropy, with larger values concentrating light around the sun
+    #[uniform(0)]
+    pub mie_g: f32,
+    /// Radiance of the sun before attenuation
+    #[uniform(0)]
+    pub sun_intensity: f32,
+    /// Multiplier applied to sky radiance, which is output as linear HDR for the camera to tonemap
+    #[uniform(0)]
+    pub exposure: f32,
+    /// Angular radius of the sun disk in radians
+    #[uniform(0)]
+    pub sun_angular_radius: f32,
+}
+
+impl Default for SkyMaterial {
+    fn default() -> Self {
+        let atmosphere = Atmosphere::default();
+        SkyMaterial {
+            rayleigh_scattering: atmosphere.rayleigh_scattering,
+            rayleigh_scale_height: atmosphere.rayleigh_scale_height,
+            planet_radius: atmosphere.planet_radius,
+            atmosphere_radius: atmosphere.atmosphere_radius,
+            mie_scattering: atmosphere.mie_scattering,
+            mie_scale_height: atmosphere.mie_scale_height,
+            mie_g: atmosphere.mie_g,
+            sun_intensity: atmosphere.sun_intensity,
+            exposure: 2.0,
+            sun_angular_radius: 0.01,
+        }
+    }
+}
+
+impl From<SkyMaterial> for Atmosphere {
+    fn from(material: SkyMaterial) -> Self {
+        Atmosphere {
+            rayleigh_scattering: material.rayleigh_scattering,
+            rayleigh_scale_height: material.rayleigh_scale_height,
+            planet_radius: material.planet_radius,
+            atmosphere_radius: material.atmosphere_radius,
+            mie_scattering: material.mie_scattering,
+            mie_scale_height: material.mie_scale_height,
+            mie_g: material.mie_g,
+            sun_intensity: material.sun_intensity,
+        }
+    }
+}
+
+impl Material for SkyMaterial {
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        // The camera sits inside the sphere
+        descriptor.primitive.cull_mode = None;
+        specialize_rust_gpu::<Self>(descriptor, layout, &default())
+    }
+}
+
+impl RustGpuMaterial for SkyMaterial {
+    type Vertex = VertexSky;
+    type Fragment = FragmentSky;
+}
+
+/// Marker for the sphere the sky is drawn on
+#[derive(Debug, Default, Copy, Clone, Component)]
+pub struct SkyDome;
+
+/// Spawns a [`SkyDome`] using the shader asset at `shader`
+pub struct SkyPlugin {
+    pub shader: &'static str,
+    /// If set, drive [`AmbientLight`] from the sky each frame, scaled by this factor
+    pub ambient_strength: Option<f32>,
+}
+
+impl Plugin for SkyPlugin {
+    fn build(&self, app: &mut App) {
+        let shader = self.shader;
+
+        if !app.is_plugin_added::<RustGpuShadersPlugin>() {
+            app.add_plugin(RustGpuShadersPlugin);
+        }
+
+        app.add_plugin(RustGpuMaterialPlugin::<SkyMaterial>::default())
+            .add_startup_system(
+                move |mut commands: Commands,
+                      asset_server: Res<AssetServer>,
+                      mut meshes: ResMut<Assets<Mesh>>,
+                      mut materials: ResMut<Assets<RustGpu<SkyMaterial>>>| {
+                    let shader = asset_server.load::<RustGpuBuilderOutput, _>(shader);
+
+                    commands.spawn((
+                        MaterialMeshBundle {
+                            mesh: meshes.add(
+                                UVSphere {
+                                    radius: 1.0,
+                                    ..default()
+                                }
+                                .into(),
+                            ),
+                            material: materials.add(RustGpu {
+                                vertex_shader: Some(shader.clone()),
+                                fragment_shader: Some(shader),
+                                ..default()
+                            }),
+                            ..default()
+                        },
+                        SkyDome,
+                        // Drawn at the far plane regardless of where the sphere is
+                        NoFrustumCulling,
+                    ));
+                },
+            )
+            .add_system(follow_camera);
+
+        if let Some(strength) = self.ambient_strength {
+            app.add_system(
+                move |sky: Query<&Handle<RustGpu<SkyMaterial>>, With<SkyDome>>,
+                      materials: Res<Assets<RustGpu<SkyMaterial>>>,
+                      lights: Query<&GlobalTransform, With<DirectionalLight>>,
+                      mut ambient: ResMut<AmbientLight>| {
+                    let Some(material) = sky.iter().next().and_then(|handle| materials.get(handle))
+                    else {
+                        return;
+                    };
+
+                    let sun = lights
+                        .iter()
+                        .next()
+                        .map(|transform| transform.back())
+                        .unwrap_or(Vec3::NEG_Y);
+
+                    let color = sky_ambient(&material.base.into(), sun) * material.base.exposure;
+                    ambient.color = Color::rgb_linear(color.x, color.y, color.z);
+                    ambient.brightness = strength;
+                },
+            );
+        }
+    }
+}
+
+/// Keep the sphere centered on the camera, so its view directions stay undistorted
+fn follow_camera(
+    cameras: Query<&GlobalTransform, With<Camera>>,
+    mut skies: Query<&mut Transform, (With<SkyDome>, Without<Camera>)>,
+) {
+    let Some(camera) = cameras.iter().next() else {
+        return;
+    };
+
+    for mut transform in skies.iter_mut() {
+        transform.translation = camera.translation();
+    }
+}
+
+/// Average sky radiance over the upper hemisphere, for a sun in unit direction `sun`
+pub fn sky_ambient(atmosphere: &Atmosphere, sun: Vec3) -> Vec3 {
+    const RING: usize = 8;
+
+    // Zenith, plus a ring of samples between the zenith and the horizon
+    let mut sum = atmosphere.sky(Vec3::Y, sun);
+    for i in 0..RING {
+        let azimuth = i as f32 / RING as f32 * std::f32::consts::TAU;
+        let direction = Vec3::new(azimuth.cos(), 0.5, azimuth.sin()).normalize();
+        sum += atmosphere.sky(direction, sun);
+    }
+
+    sum / (RING + 1) as f32
+}
@@ -0,0 +1,394 @@
+//! Pipeline specialization shared by the viewer's Rust-GPU materials.
+//!
+//! `RustGpu<M>` from `bevy-rust-gpu` v0.5 picks entry points from `M`'s associated types alone,
+//! and leaves its prepass to Bevy's WGSL shaders.
+//! [`RustGpuShadersPlugin`] registers the modules of each loaded `RustGpuBuilderOutput` as `Shader` assets
+//! and reads their vertex inputs, so that every material's `specialize` can call [`specialize_rust_gpu`]
+//! to install per-instance [`EntryPointOverrides`] carried in its pipeline key and check its mesh attributes,
+//! and [`specialize_rust_gpu_prepass`] to install the entry points of a [`RustGpuPrepassMaterial`] in its prepass.
+//!
+//! Leave a stage's shader handle on `RustGpu` as `None` when overriding it,
+//! otherwise `RustGpu<M>` installs `M`'s own entry point over the override.
+
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    hash::{Hash, Hasher},
+    sync::RwLock,
+};
+
+use bevy::{
+    asset::HandleId,
+    prelude::{
+        warn, App, AssetEvent, Assets, CoreSet, EventReader, Handle, HandleUntyped,
+        IntoSystemConfig, Plugin, Res, ResMut, Shader,
+    },
+    reflect::TypeUuid,
+    render::{
+        mesh::MeshVertexBufferLayout,
+        render_resource::{FragmentState, RenderPipelineDescriptor, SpecializedMeshPipelineError},
+    },
+};
+use bevy_rust_gpu::{EntryPoint, RustGpuBuilderModules, RustGpuBuilderOutput, RustGpuMaterial};
+
+use crate::{
+    prepass::RustGpuPrepassMaterial,
+    vertex_inputs::{entry_point_vertex_layout, read_vertex_inputs, validate_vertex_inputs},
+};
+
+/// Separator between an entry point's name and its permutation values in the compiled module,
+/// as joined by `EntryPoint::build`
+const PERMUTATION_SEPARATOR: &str = "__";
+
+/// Label Bevy 0.10 gives the pipelines of its depth and normal prepass
+const PREPASS_PIPELINE_LABEL: &str = "prepass_pipeline";
+
+/// Shader for each compiled entry point of every loaded builder output
+static ENTRY_POINT_SHADERS: RwLock<BTreeMap<HandleId, BTreeMap<String, Handle<Shader>>>> =
+    RwLock::new(BTreeMap::new());
+
+/// Registers the modules of loaded `RustGpuBuilderOutput` assets as `Shader` assets and reads their vertex inputs,
+/// and again whenever they're reloaded.
+///
+/// Runs in [`CoreSet::PreUpdate`], ahead of `bevy-rust-gpu`'s own material reload on the same asset event,
+/// so pipelines specialized again for a newly loaded output see its modules and vertex inputs.
+pub struct RustGpuShadersPlugin;
+
+impl Plugin for RustGpuShadersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(register_entry_point_shaders.in_base_set(CoreSet::PreUpdate));
+    }
+}
+
+fn register_entry_point_shaders(
+    mut events: EventReader<AssetEvent<RustGpuBuilderOutput>>,
+    outputs: Res<Assets<RustGpuBuilderOutput>>,
+    mut shaders: ResMut<Assets<Shader>>,
+) {
+    for event in events.iter() {
+        let (AssetEvent::Created { handle } | AssetEvent::Modified { handle }) = event else {
+            continue;
+        };
+
+        let Some(output) = outputs.get(handle) else {
+            continue;
+        };
+
+        read_vertex_inputs(handle.id(), &output.modules);
+
+        let entry_points = match &output.modules {
+            RustGpuBuilderModules::Single(module) => {
+                let shader = module_shader(handle.id(), None);
+                shaders.set_untracked(shader.clone_weak(), Shader::from_spirv(module.clone()));
+                output
+                    .entry_points
+                    .iter()
+                    .map(|entry_point| (entry_point.clone(), shader.clone_weak()))
+                    .collect()
+            }
+            RustGpuBuilderModules::Multi(modules) => modules
+                .iter()
+                .map(|(entry_point, module)| {
+                    let shader = module_shader(handle.id(), Some(entry_point.as_str()));
+                    shaders.set_untracked(shader.clone_weak(), Shader::from_spirv(module.clone()));
+                    (entry_point.clone(), shader)
+                })
+                .collect(),
+        };
+
+        ENTRY_POINT_SHADERS
+            .write()
+            .unwrap()
+            .insert(handle.id(), entry_points);
+    }
+}
+
+/// Shader holding `entry_point` in any loaded builder output
+fn loaded_entry_point_shader(entry_point: &str) -> Option<Handle<Shader>> {
+    ENTRY_POINT_SHADERS
+        .read()
+        .unwrap()
+        .values()
+        .find_map(|shaders| shaders.get(entry_point))
+        .map(Handle::clone_weak)
+}
+
+/// Stable handle for the module of `output` holding `entry_point`, or its only module if `None`
+fn module_shader(output: HandleId, entry_point: Option<&str>) -> Handle<Shader> {
+    let mut hasher = DefaultHasher::new();
+    (output, entry_point).hash(&mut hasher);
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, hasher.finish()).typed()
+}
+
+/// Entry point selected at runtime in place of the one named by a material's type
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct EntryPointOverride {
+    /// Builder output containing the entry point
+    pub shader: Handle<RustGpuBuilderOutput>,
+    /// Entry point name, such as `"fragment_normal"`
+    pub name: String,
+    /// Permutation values, in the order the entry point declares its parameters and types.
+    ///
+    /// The permutation must already be compiled into `shader`, such as by listing it in `entry_points.json`.
+    pub permutation: Vec<String>,
+}
+
+impl EntryPointOverride {
+    pub fn new(shader: Handle<RustGpuBuilderOutput>, name: impl Into<String>) -> Self {
+        EntryPointOverride {
+            shader,
+            name: name.into(),
+            permutation: Vec::new(),
+        }
+    }
+
+    pub fn with_permutation<I, S>(mut self, permutation: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.permutation = permutation.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Name of the compiled entry point, with permutation values appended
+    pub fn entry_point(&self) -> String {
+        permutation_name(&self.name, self.permutation.iter().map(String::as_str))
+    }
+
+    /// Whether `shader` has loaded, and [`EntryPointOverride::resolve`] can find the entry point
+    pub fn is_loaded(&self) -> bool {
+        ENTRY_POINT_SHADERS
+            .read()
+            .unwrap()
+            .contains_key(&self.shader.id())
+    }
+
+    /// The shader and entry point to install,
+    /// or `None` if `shader` hasn't loaded yet or its module lacks the entry point
+    fn resolve(&self) -> Option<(Handle<Shader>, String)> {
+        let entry_point = self.entry_point();
+
+        let entry_point_shaders = ENTRY_POINT_SHADERS.read().unwrap();
+        let shaders = entry_point_shaders.get(&self.shader.id())?;
+
+        match shaders.get(&entry_point) {
+            Some(shader) => Some((shader.clone_weak(), entry_point)),
+            None => {
+                warn!("Entry point override {entry_point} is not in the loaded module, keeping the material's own");
+                None
+            }
+        }
+    }
+}
+
+/// Per-instance overrides for the entry points of a material's vertex and fragment stages.
+///
+/// Carry these in a material's `AsBindGroup::Data` along with [`EntryPointOverrides::is_loaded`],
+/// so pipelines are specialized per distinct override, and again once its builder outputs load.
+/// Until then, the material's own entry points stay in place.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct EntryPointOverrides {
+    pub vertex: Option<EntryPointOverride>,
+    pub fragment: Option<EntryPointOverride>,
+}
+
+impl EntryPointOverrides {
+    /// Whether the builder outputs of every override have loaded
+    pub fn is_loaded(&self) -> bool {
+        [&self.vertex, &self.fragment]
+            .into_iter()
+            .flatten()
+            .all(EntryPointOverride::is_loaded)
+    }
+}
+
+fn permutation_name<'a>(name: &'a str, permutation: impl Iterator<Item = &'a str>) -> String {
+    std::iter::once(name)
+        .chain(permutation)
+        .collect::<Vec<_>>()
+        .join(PERMUTATION_SEPARATOR)
+}
+
+/// Whether `descriptor` is for the depth and normal prepass rather than the main pass
+pub fn is_prepass(descriptor: &RenderPipelineDescriptor) -> bool {
+    descriptor.label.as_deref() == Some(PREPASS_PIPELINE_LABEL)
+}
+
+/// Install the entry points in `overrides` over those of `M`,
+/// and check that `layout` provides every mesh attribute the resulting vertex entry point reads.
+///
+/// Call this last from the `specialize` of every `RustGpuMaterial`, once any shader defs are pushed,
+/// so a mesh missing an attribute fails specialization by name rather than as a wgpu pipeline error.
+/// Overrides replace main pass entry points, so prepass pipelines are left alone.
+pub fn specialize_rust_gpu<M: RustGpuMaterial>(
+    descriptor: &mut RenderPipelineDescriptor,
+    layout: &MeshVertexBufferLayout,
+    overrides: &EntryPointOverrides,
+) -> Result<(), SpecializedMeshPipelineError> {
+    if is_prepass(descriptor) {
+        return Ok(());
+    }
+
+    let vertex = match overrides
+        .vertex
+        .as_ref()
+        .and_then(EntryPointOverride::resolve)
+    {
+        Some((shader, entry_point)) => {
+            descriptor.vertex.shader = shader;
+            descriptor.vertex.entry_point = entry_point.clone().into();
+            entry_point
+        }
+        None => M::Vertex::build(&descriptor.vertex.shader_defs),
+    };
+
+    if let Some(fragment) = descriptor.fragment.as_mut() {
+        if let Some((shader, entry_point)) = overrides
+            .fragment
+            .as_ref()
+            .and_then(EntryPointOverride::resolve)
+        {
+            fragment.shader = shader;
+            fragment.entry_point = entry_point.into();
+        }
+    }
+
+    validate_vertex_inputs(&vertex, layout)
+}
+
+/// Install the prepass entry points of `M` if `descriptor` is a prepass pipeline,
+/// feeding the prepass vertex entry point the mesh attributes it reads.
+///
+/// Depth-only prepasses have no fragment stage, so one is added for materials that write depth from it.
+/// Bevy's prepass shaders stay in place, with a warning, until a module holding both entry points has loaded.
+/// [`RustGpuShadersPlugin`] registers modules before `bevy-rust-gpu` reloads materials for a loaded output,
+/// so the prepass pipeline is specialized again with them once it loads.
+pub fn specialize_rust_gpu_prepass<M: RustGpuPrepassMaterial>(
+    descriptor: &mut RenderPipelineDescriptor,
+    layout: &MeshVertexBufferLayout,
+) -> Result<(), SpecializedMeshPipelineError> {
+    if !is_prepass(descriptor) {
+        return Ok(());
+    }
+
+    let no_shader_defs = Vec::new();
+    let vertex = M::PrepassVertex::build(&descriptor.vertex.shader_defs);
+    let fragment = M::PrepassFragment::build(
+        descriptor
+            .fragment
+            .as_ref()
+            .map_or(&no_shader_defs, |fragment| &fragment.shader_defs),
+    );
+
+    let (Some(vertex_shader), Some(fragment_shader)) = (
+        loaded_entry_point_shader(&vertex),
+        loaded_entry_point_shader(&fragment),
+    ) else {
+        warn!("Prepass entry points {vertex} and {fragment} aren't loaded, keeping Bevy's prepass shaders");
+        return Ok(());
+    };
+
+    // Bevy's prepass binds attributes at its own locations, which needn't match the entry point's
+    let Some(vertex_layout) = entry_point_vertex_layout(&vertex, layout) else {
+        warn!("Vertex inputs of {vertex} are unknown, keeping Bevy's prepass shaders");
+        return Ok(());
+    };
+
+    descriptor.vertex.shader = vertex_shader;
+    descriptor.vertex.entry_point = vertex.into();
+    descriptor.vertex.buffers = vec![vertex_layout?];
+
+    match descriptor.fragment.as_mut() {
+        Some(fragment_state) => {
+            fragment_state.shader = fragment_shader;
+            fragment_state.entry_point = fragment.into();
+        }
+        None => {
+            descriptor.fragment = Some(FragmentState {
+                shader: fragment_shader,
+                shader_defs: Vec::new(),
+                entry_point: fragment.into(),
+                targets: Vec::new(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::render::render_resource::ShaderDefVal;
+    use bevy_rust_gpu::{EntryPointParameters, EntryPointTypes};
+
+    use super::*;
+
+    /// Entry point with two parameters and a type, like `extended_pbr::fragment_extended_pbr`
+    enum Permutated {}
+
+    impl EntryPoint for Permutated {
+        const NAME: &'static str = "module::permutated";
+        const PARAMETERS: EntryPointParameters = &[
+            (&[("FIRST_A", "a"), ("FIRST_B", "b")], "none"),
+            (&[("SECOND", "some")], "none"),
+        ];
+
+        fn types() -> EntryPointTypes {
+            vec![("Pattern".to_string(), "Marble".to_string())]
+        }
+    }
+
+    #[test]
+    fn override_names_match_entry_point_builder() {
+        for (shader_defs, permutation, name) in [
+            (
+                vec![],
+                ["none", "none", "Marble"],
+                "module::permutated__none__none__Marble",
+            ),
+            (
+                vec![ShaderDefVal::from("FIRST_B")],
+                ["b", "none", "Marble"],
+                "module::permutated__b__none__Marble",
+            ),
+            (
+                vec![ShaderDefVal::from("SECOND"), ShaderDefVal::from("FIRST_A")],
+                ["a", "some", "Marble"],
+                "module::permutated__a__some__Marble",
+            ),
+        ] {
+            let entry_point_override = EntryPointOverride::new(Handle::default(), Permutated::NAME)
+                .with_permutation(permutation);
+
+            assert_eq!(Permutated::build(&shader_defs), name);
+            assert_eq!(entry_point_override.entry_point(), name);
+        }
+    }
+
+    #[test]
+    fn unpermutated_override_name_is_bare() {
+        let entry_point_override = EntryPointOverride::new(Handle::default(), "vertex_warp");
+        assert_eq!(entry_point_override.entry_point(), "vertex_warp");
+    }
+
+    #[test]
+    fn unloaded_override_keeps_material_entry_point() {
+        let overrides = EntryPointOverrides {
+            fragment: Some(EntryPointOverride::new(
+                Handle::default(),
+                "fragment_normal",
+            )),
+            ..Default::default()
+        };
+
+        assert!(!overrides.is_loaded());
+        assert_eq!(
+            overrides
+                .fragment
+                .as_ref()
+                .and_then(EntryPointOverride::resolve),
+            None
+        );
+        assert!(EntryPointOverrides::default().is_loaded());
+    }
+}
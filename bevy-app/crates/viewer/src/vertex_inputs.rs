@@ -0,0 +1,370 @@
+//! Checks vertex entry point inputs against the mesh they're drawn with.
+//!
+//! A mesh missing an attribute that a vertex entry point reads surfaces as an opaque wgpu error
+//! at pipeline creation. `specialize::RustGpuShadersPlugin` reads the input locations of each vertex entry point
+//! from the modules of every loaded `RustGpuBuilderOutput`, and [`validate_vertex_inputs`] compares them with a
+//! [`MeshVertexBufferLayout`] from `Material::specialize`, naming the missing attribute instead.
+//!
+//! `specialize::specialize_rust_gpu` runs this check for the vertex entry point a material's pipeline ends up with.
+
+use std::{collections::BTreeMap, sync::RwLock};
+
+use bevy::{
+    asset::HandleId,
+    prelude::{error, info, warn, Mesh},
+    render::{
+        mesh::{MeshVertexAttribute, MeshVertexBufferLayout, MissingVertexAttributeError},
+        render_resource::{SpecializedMeshPipelineError, VertexBufferLayout, VertexFormat},
+    },
+};
+use bevy_rust_gpu::RustGpuBuilderModules;
+
+const SPIRV_MAGIC: u32 = 0x0723_0203;
+const SPIRV_HEADER_WORDS: usize = 5;
+
+const OP_ENTRY_POINT: u32 = 15;
+const OP_VARIABLE: u32 = 59;
+const OP_DECORATE: u32 = 71;
+
+const EXECUTION_MODEL_VERTEX: u32 = 0;
+const STORAGE_CLASS_INPUT: u32 = 1;
+const DECORATION_BUILT_IN: u32 = 11;
+const DECORATION_LOCATION: u32 = 30;
+
+/// Attribute ID no mesh uses, for reporting inputs no mesh attribute can provide
+const UNSUPPORTED_ATTRIBUTE_ID: usize = 0x6e1d_04c7;
+
+/// Input locations of each vertex entry point of every loaded builder output
+static VERTEX_INPUTS: RwLock<BTreeMap<HandleId, BTreeMap<String, Vec<u32>>>> =
+    RwLock::new(BTreeMap::new());
+
+/// Read the input locations of each vertex entry point in `modules`, replacing those read for `output`
+pub(crate) fn read_vertex_inputs(output: HandleId, modules: &RustGpuBuilderModules) {
+    let modules = match modules {
+        RustGpuBuilderModules::Single(module) => vec![module],
+        RustGpuBuilderModules::Multi(modules) => modules.values().collect(),
+    };
+
+    let mut inputs = BTreeMap::new();
+    for module in modules {
+        match vertex_input_locations(module) {
+            Some(locations) => inputs.extend(locations),
+            None => warn!("Skipping vertex inputs of a builder output module that is not SPIR-V"),
+        }
+    }
+
+    info!("Read inputs of {} vertex entry points", inputs.len());
+    VERTEX_INPUTS.write().unwrap().insert(output, inputs);
+}
+
+/// Input locations of `entry_point` in any loaded builder output
+fn loaded_vertex_inputs(entry_point: &str) -> Option<Vec<u32>> {
+    VERTEX_INPUTS
+        .read()
+        .unwrap()
+        .values()
+        .find_map(|inputs| inputs.get(entry_point))
+        .cloned()
+}
+
+/// Input locations read by each vertex entry point in a SPIR-V `module`, keyed by entry point name.
+///
+/// Returns `None` if `module` isn't valid SPIR-V.
+pub fn vertex_input_locations(module: &[u8]) -> Option<BTreeMap<String, Vec<u32>>> {
+    if module.len() % 4 != 0 {
+        return None;
+    }
+
+    let words = module
+        .chunks_exact(4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect::<Vec<_>>();
+
+    if words.len() < SPIRV_HEADER_WORDS || words[0] != SPIRV_MAGIC {
+        return None;
+    }
+
+    let mut entry_points = Vec::new();
+    let mut inputs = Vec::new();
+    let mut locations = BTreeMap::new();
+    let mut built_ins = Vec::new();
+
+    let mut i = SPIRV_HEADER_WORDS;
+    while i < words.len() {
+        let word_count = (words[i] >> 16) as usize;
+        let opcode = words[i] & 0xffff;
+        if word_count == 0 || i + word_count > words.len() {
+            return None;
+        }
+        let operands = &words[i + 1..i + word_count];
+
+        match opcode {
+            OP_ENTRY_POINT if operands.len() >= 3 && operands[0] == EXECUTION_MODEL_VERTEX => {
+                let (name, name_words) = literal_string(&operands[2..]);
+                entry_points.push((name, operands[2 + name_words..].to_vec()));
+            }
+            OP_VARIABLE if operands.len() >= 3 && operands[2] == STORAGE_CLASS_INPUT => {
+                inputs.push(operands[1]);
+            }
+            OP_DECORATE if operands.len() >= 3 && operands[1] == DECORATION_LOCATION => {
+                locations.insert(operands[0], operands[2]);
+            }
+            OP_DECORATE if operands.len() >= 2 && operands[1] == DECORATION_BUILT_IN => {
+                built_ins.push(operands[0]);
+            }
+            _ => (),
+        }
+
+        i += word_count;
+    }
+
+    Some(
+        entry_points
+            .into_iter()
+            .map(|(name, interface)| {
+                // Built-ins such as the vertex index have no location to fill from the mesh
+                let mut entry_locations = interface
+                    .iter()
+                    .filter(|id| inputs.contains(id) && !built_ins.contains(id))
+                    .filter_map(|id| locations.get(id).copied())
+                    .collect::<Vec<_>>();
+                entry_locations.sort_unstable();
+                (name, entry_locations)
+            })
+            .collect(),
+    )
+}
+
+/// Decode a nul-terminated SPIR-V literal string, returning it and the number of words it occupies
+fn literal_string(words: &[u32]) -> (String, usize) {
+    let mut bytes = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        for byte in word.to_le_bytes() {
+            if byte == 0 {
+                return (String::from_utf8_lossy(&bytes).into_owned(), i + 1);
+            }
+            bytes.push(byte);
+        }
+    }
+    (String::from_utf8_lossy(&bytes).into_owned(), words.len())
+}
+
+/// The mesh attribute Bevy's mesh pipeline binds to shader input `location`
+pub fn mesh_attribute_at(location: u32) -> Option<MeshVertexAttribute> {
+    match location {
+        0 => Some(Mesh::ATTRIBUTE_POSITION),
+        1 => Some(Mesh::ATTRIBUTE_NORMAL),
+        2 => Some(Mesh::ATTRIBUTE_UV_0),
+        3 => Some(Mesh::ATTRIBUTE_TANGENT),
+        4 => Some(Mesh::ATTRIBUTE_COLOR),
+        5 => Some(Mesh::ATTRIBUTE_JOINT_INDEX),
+        6 => Some(Mesh::ATTRIBUTE_JOINT_WEIGHT),
+        _ => None,
+    }
+}
+
+/// A [`SpecializedMeshPipelineError`] for a pipeline input that `layout` can't provide, described by `description`.
+///
+/// Bevy 0.10's only specialization error is a missing mesh attribute,
+/// so this reports `description` as the name of an attribute no mesh has.
+pub fn unsupported_input_error(
+    layout: &MeshVertexBufferLayout,
+    description: &'static str,
+) -> SpecializedMeshPipelineError {
+    let attribute =
+        MeshVertexAttribute::new(description, UNSUPPORTED_ATTRIBUTE_ID, VertexFormat::Float32);
+
+    layout
+        .get_layout(&[attribute.at_shader_location(0)])
+        .expect_err("no mesh has the unsupported input attribute")
+        .into()
+}
+
+/// Vertex buffer layout feeding `entry_point` each mesh attribute it reads, at the location it reads it from.
+///
+/// Returns `None` if no loaded builder output has `entry_point`.
+pub fn entry_point_vertex_layout(
+    entry_point: &str,
+    layout: &MeshVertexBufferLayout,
+) -> Option<Result<VertexBufferLayout, MissingVertexAttributeError>> {
+    let locations = loaded_vertex_inputs(entry_point)?;
+
+    let attributes = locations
+        .into_iter()
+        .filter_map(|location| {
+            mesh_attribute_at(location).map(|attribute| attribute.at_shader_location(location))
+        })
+        .collect::<Vec<_>>();
+
+    Some(layout.get_layout(&attributes))
+}
+
+/// Check that `layout` provides every mesh attribute the compiled vertex `entry_point` reads.
+///
+/// Warns and passes if no loaded builder output has `entry_point`.
+/// `specialize::RustGpuShadersPlugin` reads inputs before `bevy-rust-gpu` reloads materials for a loaded output,
+/// so the pipeline is specialized and checked again once it loads.
+pub fn validate_vertex_inputs(
+    entry_point: &str,
+    layout: &MeshVertexBufferLayout,
+) -> Result<(), SpecializedMeshPipelineError> {
+    let Some(locations) = loaded_vertex_inputs(entry_point) else {
+        warn!("Inputs of vertex entry point {entry_point} aren't loaded, skipping validation");
+        return Ok(());
+    };
+
+    let mut attributes = Vec::with_capacity(locations.len());
+    for location in locations {
+        let Some(attribute) = mesh_attribute_at(location) else {
+            error!(
+                "Vertex entry point {entry_point} reads input location {location}, which has no mesh attribute"
+            );
+            return Err(unsupported_input_error(
+                layout,
+                "vertex input at a location Bevy's mesh pipeline binds no attribute to",
+            ));
+        };
+
+        if !layout.contains(attribute.id) {
+            error!(
+                "Vertex entry point {entry_point} reads {} at location {location}, which the mesh doesn't have",
+                attribute.name
+            );
+        }
+
+        attributes.push(attribute.at_shader_location(location));
+    }
+
+    layout.get_layout(&attributes)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXECUTION_MODEL_FRAGMENT: u32 = 4;
+    const STORAGE_CLASS_OUTPUT: u32 = 3;
+    const BUILT_IN_VERTEX_INDEX: u32 = 42;
+
+    /// A SPIR-V instruction, with its word count and opcode packed into the first word
+    fn instruction(opcode: u32, operands: &[u32]) -> Vec<u32> {
+        let mut words = vec![((operands.len() as u32 + 1) << 16) | opcode];
+        words.extend_from_slice(operands);
+        words
+    }
+
+    /// A nul-terminated literal string, padded to a whole word
+    fn string(s: &str) -> Vec<u32> {
+        let mut bytes = s.as_bytes().to_vec();
+        bytes.push(0);
+        bytes.resize((bytes.len() + 3) & !3, 0);
+        bytes
+            .chunks_exact(4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect()
+    }
+
+    fn entry_point(execution_model: u32, function: u32, name: &str, interface: &[u32]) -> Vec<u32> {
+        let mut operands = vec![execution_model, function];
+        operands.extend(string(name));
+        operands.extend_from_slice(interface);
+        instruction(OP_ENTRY_POINT, &operands)
+    }
+
+    fn module(instructions: &[Vec<u32>]) -> Vec<u8> {
+        [SPIRV_MAGIC, 0x0001_0300, 0, 100, 0]
+            .into_iter()
+            .chain(instructions.iter().flatten().copied())
+            .flat_map(u32::to_le_bytes)
+            .collect()
+    }
+
+    /// Two vertex entry points sharing an input, a fragment entry point,
+    /// and a built-in input and an output that have no mesh attribute
+    fn test_module() -> Vec<u8> {
+        module(&[
+            entry_point(EXECUTION_MODEL_VERTEX, 1, "vs", &[10, 11, 12, 13]),
+            entry_point(EXECUTION_MODEL_VERTEX, 2, "vertex_b", &[10, 14]),
+            entry_point(EXECUTION_MODEL_FRAGMENT, 3, "fs", &[15]),
+            instruction(OP_DECORATE, &[10, DECORATION_LOCATION, 0]),
+            instruction(OP_DECORATE, &[11, DECORATION_LOCATION, 2]),
+            instruction(
+                OP_DECORATE,
+                &[12, DECORATION_BUILT_IN, BUILT_IN_VERTEX_INDEX],
+            ),
+            instruction(OP_DECORATE, &[13, DECORATION_LOCATION, 0]),
+            instruction(OP_DECORATE, &[14, DECORATION_LOCATION, 1]),
+            instruction(OP_DECORATE, &[15, DECORATION_LOCATION, 3]),
+            instruction(OP_VARIABLE, &[20, 11, STORAGE_CLASS_INPUT]),
+            instruction(OP_VARIABLE, &[20, 10, STORAGE_CLASS_INPUT]),
+            instruction(OP_VARIABLE, &[21, 12, STORAGE_CLASS_INPUT]),
+            instruction(OP_VARIABLE, &[20, 13, STORAGE_CLASS_OUTPUT]),
+            instruction(OP_VARIABLE, &[20, 14, STORAGE_CLASS_INPUT]),
+            instruction(OP_VARIABLE, &[20, 15, STORAGE_CLASS_INPUT]),
+        ])
+    }
+
+    #[test]
+    fn literal_string_words() {
+        assert_eq!(literal_string(&string("vs")), ("vs".to_string(), 1));
+        assert_eq!(literal_string(&string("abc")), ("abc".to_string(), 1));
+
+        // A multiple of four bytes leaves the nul in a word of its own
+        let words = string("vertex_b");
+        assert_eq!(words.len(), 3);
+        assert_eq!(literal_string(&words), ("vertex_b".to_string(), 3));
+
+        // Following operands are left alone
+        let mut words = string("main");
+        words.extend([7, 8]);
+        assert_eq!(literal_string(&words), ("main".to_string(), 2));
+
+        // Unterminated strings consume every word
+        let words = &string("abcd")[..1];
+        assert_eq!(literal_string(words), ("abcd".to_string(), 1));
+    }
+
+    #[test]
+    fn vertex_entry_point_locations() {
+        let locations = vertex_input_locations(&test_module()).unwrap();
+
+        assert_eq!(
+            locations,
+            BTreeMap::from([
+                ("vs".to_string(), vec![0, 2]),
+                ("vertex_b".to_string(), vec![0, 1]),
+            ])
+        );
+    }
+
+    #[test]
+    fn invalid_modules() {
+        let module = test_module();
+
+        // Not a whole number of words
+        assert_eq!(vertex_input_locations(&module[..module.len() - 1]), None);
+
+        // Last instruction cut short
+        assert_eq!(vertex_input_locations(&module[..module.len() - 4]), None);
+
+        // Shorter than the header
+        assert_eq!(vertex_input_locations(&module[..12]), None);
+
+        // Wrong magic number
+        let mut bad_magic = module.clone();
+        bad_magic[0] ^= 0xff;
+        assert_eq!(vertex_input_locations(&bad_magic), None);
+
+        // Zero word count, which would never advance
+        let mut zero_count = module[..SPIRV_HEADER_WORDS * 4].to_vec();
+        zero_count.extend(OP_DECORATE.to_le_bytes());
+        assert_eq!(vertex_input_locations(&zero_count), None);
+
+        // A header alone has no entry points
+        assert_eq!(
+            vertex_input_locations(&module[..SPIRV_HEADER_WORDS * 4]),
+            Some(BTreeMap::new())
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Single-scattering sky model with Rayleigh and Mie terms.
+//!
+//! [`Atmosphere::sky`] marches a view ray from just above the ground through the atmosphere shell,
+//! and at each sample integrates the optical depth towards the sun to attenuate its light.
+//! Distances are in meters and scattering coefficients per meter.
+
+use core::f32::consts::PI;
+
+use crate::glam::Vec3;
+
+#[cfg(target_arch = "spirv")]
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+/// Samples along the view ray
+const VIEW_STEPS: u32 = 16;
+
+/// Samples along each ray towards the sun
+const LIGHT_STEPS: u32 = 8;
+
+/// Planet and atmosphere parameters, laid out to match the `SkyMaterial` uniform in the viewer.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(C)]
+pub struct Atmosphere {
+    /// Rayleigh scattering coefficients at sea level, per color channel
+    pub rayleigh_scattering: Vec3,
+    /// Altitude over which Rayleigh density falls by `1 / e`
+    pub rayleigh_scale_height: f32,
+    pub planet_radius: f32,
+    /// Radius of the outer edge of the atmosphere
+    pub atmosphere_radius: f32,
+    /// Mie scattering coefficient at sea level
+    pub mie_scattering: f32,
+    /// Altitude over which Mie density falls by `1 / e`
+    pub mie_scale_height: f32,
+    /// Mie anisotropy, with larger values concentrating light around the sun
+    pub mie_g: f32,
+    /// Radiance of the sun before attenuation
+    pub sun_intensity: f32,
+}
+
+impl Default for Atmosphere {
+    /// Earth-like atmosphere
+    fn default() -> Self {
+        Atmosphere {
+            rayleigh_scattering: Vec3::new(5.8e-6, 13.5e-6, 33.1e-6),
+            rayleigh_scale_height: 8.0e3,
+            planet_radius: 6360e3,
+            atmosphere_radius: 6420e3,
+            mie_scattering: 21e-6,
+            mie_scale_height: 1.2e3,
+            mie_g: 0.76,
+            sun_intensity: 22.0,
+        }
+    }
+}
+
+/// Distances to the near and far intersections of a ray from `origin` along unit `direction`
+/// with a sphere of `radius` at the origin, with near greater than far on a miss
+fn intersect_sphere(origin: Vec3, direction: Vec3, radius: f32) -> (f32, f32) {
+    let b = origin.dot(direction);
+    let c = origin.dot(origin) - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return (f32::MAX, -f32::MAX);
+    }
+    let root = discriminant.sqrt();
+    (-b - root, -b + root)
+}
+
+impl Atmosphere {
+    /// Rayleigh and Mie densities relative to sea level at `position`
+    fn density(&self, position: Vec3) -> (f32, f32) {
+        let altitude = position.length() - self.planet_radius;
+        (
+            (-altitude / self.rayleigh_scale_height).exp(),
+            (-altitude / self.mie_scale_height).exp(),
+        )
+    }
+
+    /// Extinction over optical depths of Rayleigh and Mie particles
+    fn extinction(&self, rayleigh_depth: f32, mie_depth: f32) -> Vec3 {
+        let tau = self.rayleigh_scattering * rayleigh_depth
+            + Vec3::splat(self.mie_scattering * 1.1 * mie_depth);
+        Vec3::new((-tau.x).exp(), (-tau.y).exp(), (-tau.z).exp())
+    }
+
+    /// Whether the planet leaves `position` in the shadow of a sun in direction `sun`
+    fn in_shadow(&self, position: Vec3, sun: Vec3) -> bool {
+        let (near, far) = intersect_sphere(position, sun, self.planet_radius);
+        near <= far && near > 0.0
+    }
+
+    /// Optical depths of Rayleigh and Mie particles from `position` to the top of the atmosphere
+    /// towards `sun`
+    fn light_depth(&self, position: Vec3, sun: Vec3) -> (f32, f32) {
+        let (_, far) = intersect_sphere(position, sun, self.atmosphere_radius);
+        let step = far.max(0.0) / LIGHT_STEPS as f32;
+
+        let mut rayleigh = 0.0;
+        let mut mie = 0.0;
+        let mut i = 0;
+        while i < LIGHT_STEPS {
+            let (r, m) = self.density(position + sun * (step * (i as f32 + 0.5)));
+            rayleigh += r * step;
+            mie += m * step;
+            i += 1;
+        }
+
+        (rayleigh, mie)
+    }
+
+    /// Fraction of sunlight reaching a viewer on the ground from unit direction `sun`
+    pub fn transmittance(&self, sun: Vec3) -> Vec3 {
+        let origin = Vec3::Y * (self.planet_radius + 1.0);
+        if self.in_shadow(origin, sun) {
+            return Vec3::ZERO;
+        }
+        let (rayleigh, mie) = self.light_depth(origin, sun);
+        self.extinction(rayleigh, mie)
+    }
+
+    /// Scattered sky radiance seen from the ground along unit `direction`,
+    /// lit by a sun in unit direction `sun`
+    pub fn sky(&self, direction: Vec3, sun: Vec3) -> Vec3 {
+        let origin = Vec3::Y * (self.planet_radius + 1.0);
+
+        // The viewer is inside the atmosphere, so the ray always leaves through its far side
+        let (_, mut far) = intersect_sphere(origin, direction, self.atmosphere_radius);
+
+        // Stop at the ground for rays looking down
+        let (ground_near, ground_far) = intersect_sphere(origin, direction, self.planet_radius);
+        if ground_near <= ground_far && ground_near > 0.0 {
+            far = ground_near;
+        }
+
+        let step = far / VIEW_STEPS as f32;
+
+        let mut rayleigh_sum = Vec3::ZERO;
+        let mut mie_sum = Vec3::ZERO;
+        let mut rayleigh_depth = 0.0;
+        let mut mie_depth = 0.0;
+
+        let mut i = 0;
+        while i < VIEW_STEPS {
+            let position = origin + direction * (step * (i as f32 + 0.5));
+            let (r, m) = self.density(position);
+            rayleigh_depth += r * step;
+            mie_depth += m * step;
+
+            if !self.in_shadow(position, sun) {
+                let (light_rayleigh, light_mie) = self.light_depth(position, sun);
+                let attenuation =
+                    self.extinction(rayleigh_depth + light_rayleigh, mie_depth + light_mie);
+                rayleigh_sum += attenuation * r * step;
+                mie_sum += attenuation * m * step;
+            }
+            i += 1;
+        }
+
+        // Phase functions, with Cornette-Shanks for Mie
+        let mu = direction.dot(sun);
+        let rayleigh_phase = 3.0 / (16.0 * PI) * (1.0 + mu * mu);
+        let g = self.mie_g;
+        let mie_phase = 3.0 / (8.0 * PI) * ((1.0 - g * g) * (1.0 + mu * mu))
+            / ((2.0 + g * g) * (1.0 + g * g - 2.0 * g * mu).powf(1.5));
+
+        (rayleigh_sum * self.rayleigh_scattering * rayleigh_phase
+            + mie_sum * self.mie_scattering * mie_phase)
+            * self.sun_intensity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sun_at_elevation(degrees: f32) -> Vec3 {
+        let angle = degrees.to_radians();
+        Vec3::new(angle.cos(), angle.sin(), 0.0)
+    }
+
+    #[test]
+    fn noon_sky_is_blue() {
+        let atmosphere = Atmosphere::default();
+        let zenith = atmosphere.sky(Vec3::Y, sun_at_elevation(60.0));
+        assert!(zenith.z > zenith.y && zenith.y > zenith.x, "{zenith}");
+        assert!(zenith.min_element() > 0.0);
+    }
+
+    #[test]
+    fn sunset_is_red() {
+        let atmosphere = Atmosphere::default();
+        let sun = sun_at_elevation(1.0);
+        let horizon = atmosphere.sky(Vec3::new(1.0, 0.05, 0.0).normalize(), sun);
+        assert!(horizon.x > horizon.z, "{horizon}");
+
+        let transmittance = atmosphere.transmittance(sun);
+        assert!(transmittance.x > transmittance.y && transmittance.y > transmittance.z);
+        assert!(atmosphere.transmittance(Vec3::Y).min_element() > 0.5);
+    }
+
+    #[test]
+    fn night_is_dark() {
+        let atmosphere = Atmosphere::default();
+        let sun = sun_at_elevation(-30.0);
+        assert!(atmosphere.sky(Vec3::Y, sun).max_element() < 1e-3);
+        assert_eq!(atmosphere.transmittance(sun), Vec3::ZERO);
+    }
+
+    #[test]
+    fn brighter_towards_the_sun() {
+        let atmosphere = Atmosphere::default();
+        let sun = sun_at_elevation(20.0);
+        let towards = atmosphere.sky(sun, sun);
+        let away = atmosphere.sky(Vec3::new(-sun.x, sun.y, 0.0), sun);
+        assert!(towards.x > away.x * 2.0, "{towards} vs {away}");
+    }
+}
@@ -0,0 +1,365 @@
+//! Color-space conversion and tonemapping.
+//!
+//! Colors are `Vec3` RGB triples, linear unless a function says otherwise.
+//! Without an HDR camera, `StandardMaterial` tonemaps in its fragment shader,
+//! so custom entry points writing linear color can apply [`tonemap_aces`] or [`tonemap_reinhard`] to match it.
+
+use crate::glam::{Mat3, Vec3};
+
+#[cfg(target_arch = "spirv")]
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+/// Rec. 709 luminance weights for linear RGB
+pub const LUMINANCE: Vec3 = Vec3::new(0.2126, 0.7152, 0.0722);
+
+/// Relative luminance of a linear color
+pub fn luminance(color: Vec3) -> f32 {
+    color.dot(LUMINANCE)
+}
+
+/// Decode a single sRGB channel to linear
+pub fn srgb_to_linear_channel(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode a single linear channel to sRGB
+pub fn linear_to_srgb_channel(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Decode an sRGB color to linear
+pub fn srgb_to_linear(color: Vec3) -> Vec3 {
+    Vec3::new(
+        srgb_to_linear_channel(color.x),
+        srgb_to_linear_channel(color.y),
+        srgb_to_linear_channel(color.z),
+    )
+}
+
+/// Encode a linear color to sRGB
+pub fn linear_to_srgb(color: Vec3) -> Vec3 {
+    Vec3::new(
+        linear_to_srgb_channel(color.x),
+        linear_to_srgb_channel(color.y),
+        linear_to_srgb_channel(color.z),
+    )
+}
+
+/// Convert RGB to hue, saturation and value, each in `[0, 1]`
+///
+/// Operates on whichever encoding it is given; HSV of sRGB values matches color pickers.
+pub fn rgb_to_hsv(color: Vec3) -> Vec3 {
+    let max = color.max_element();
+    let min = color.min_element();
+    let delta = max - min;
+
+    let hue = if delta <= 0.0 {
+        0.0
+    } else if max == color.x {
+        let h = (color.y - color.z) / delta;
+        if h < 0.0 {
+            h + 6.0
+        } else {
+            h
+        }
+    } else if max == color.y {
+        (color.z - color.x) / delta + 2.0
+    } else {
+        (color.x - color.y) / delta + 4.0
+    };
+
+    let saturation = if max <= 0.0 { 0.0 } else { delta / max };
+
+    Vec3::new(hue / 6.0, saturation, max)
+}
+
+/// Convert hue, saturation and value in `[0, 1]` to RGB
+pub fn hsv_to_rgb(hsv: Vec3) -> Vec3 {
+    let h = (hsv.x - hsv.x.floor()) * 6.0;
+    let k = Vec3::new(5.0, 3.0, 1.0) + Vec3::splat(h);
+    let k = Vec3::new(k.x % 6.0, k.y % 6.0, k.z % 6.0);
+    let f = (Vec3::splat(4.0) - k).min(k).clamp(Vec3::ZERO, Vec3::ONE);
+    Vec3::splat(hsv.z) - f * (hsv.z * hsv.y)
+}
+
+/// Linear sRGB to LMS cone response, from Björn Ottosson's OKLab reference
+const RGB_TO_LMS: Mat3 = Mat3::from_cols_array(&[
+    0.412_221_46,
+    0.211_903_5,
+    0.088_302_46,
+    0.536_332_55,
+    0.680_699_5,
+    0.281_718_85,
+    0.051_445_995,
+    0.107_396_96,
+    0.629_978_7,
+]);
+
+const LMS_TO_OKLAB: Mat3 = Mat3::from_cols_array(&[
+    0.210_454_26,
+    1.977_998_5,
+    0.025_904_037,
+    0.793_617_8,
+    -2.428_592_2,
+    0.782_771_77,
+    -0.004_072_047,
+    0.450_593_7,
+    -0.808_675_77,
+]);
+
+const OKLAB_TO_LMS: Mat3 = Mat3::from_cols_array(&[
+    1.0,
+    1.0,
+    1.0,
+    0.396_337_78,
+    -0.105_561_346,
+    -0.089_484_18,
+    0.215_803_76,
+    -0.063_854_17,
+    -1.291_485_5,
+]);
+
+const LMS_TO_RGB: Mat3 = Mat3::from_cols_array(&[
+    4.076_741_7,
+    -1.268_438,
+    -0.004_196_086_3,
+    -3.307_711_6,
+    2.609_757_4,
+    -0.703_418_6,
+    0.230_969_94,
+    -0.341_319_4,
+    1.707_614_7,
+]);
+
+fn cbrt(x: f32) -> f32 {
+    x.signum() * x.abs().powf(1.0 / 3.0)
+}
+
+/// Convert linear sRGB to OKLab lightness and opponent axes
+pub fn linear_to_oklab(color: Vec3) -> Vec3 {
+    let lms = RGB_TO_LMS * color;
+    LMS_TO_OKLAB * Vec3::new(cbrt(lms.x), cbrt(lms.y), cbrt(lms.z))
+}
+
+/// Convert OKLab to linear sRGB
+pub fn oklab_to_linear(lab: Vec3) -> Vec3 {
+    let lms = OKLAB_TO_LMS * lab;
+    LMS_TO_RGB * (lms * lms * lms)
+}
+
+/// Blend two linear colors through OKLab, avoiding the muddy midpoints of an RGB blend
+pub fn mix_oklab(a: Vec3, b: Vec3, t: f32) -> Vec3 {
+    oklab_to_linear(linear_to_oklab(a).lerp(linear_to_oklab(b), t))
+}
+
+/// Cosine gradient `offset + amplitude * cos(TAU * (frequency * t + phase))`,
+/// as described by Inigo Quilez
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(C)]
+pub struct Palette {
+    pub offset: Vec3,
+    pub amplitude: Vec3,
+    pub frequency: Vec3,
+    pub phase: Vec3,
+}
+
+impl Default for Palette {
+    /// Full rainbow
+    fn default() -> Self {
+        Palette {
+            offset: Vec3::splat(0.5),
+            amplitude: Vec3::splat(0.5),
+            frequency: Vec3::ONE,
+            phase: Vec3::new(0.0, 0.33, 0.67),
+        }
+    }
+}
+
+impl Palette {
+    /// Color at `t`, repeating with period `1 / frequency` per channel
+    pub fn sample(&self, t: f32) -> Vec3 {
+        let angle = (self.frequency * t + self.phase) * core::f32::consts::TAU;
+        self.offset + self.amplitude * Vec3::new(angle.x.cos(), angle.y.cos(), angle.z.cos())
+    }
+
+    /// `i`th of `count` colors spread evenly over one period
+    pub fn swatch(&self, i: u32, count: u32) -> Vec3 {
+        self.sample(i as f32 / count.max(1) as f32)
+    }
+}
+
+/// Per-channel Reinhard operator `c / (1 + c)`
+pub fn tonemap_reinhard(color: Vec3) -> Vec3 {
+    color / (Vec3::ONE + color)
+}
+
+/// Reinhard on luminance, preserving hue, with `white` mapping to 1
+pub fn tonemap_reinhard_luminance(color: Vec3, white: f32) -> Vec3 {
+    let l = luminance(color);
+    if l <= 0.0 {
+        return Vec3::ZERO;
+    }
+    let mapped = l * (1.0 + l / (white * white)) / (1.0 + l);
+    color * (mapped / l)
+}
+
+/// sRGB to ACES AP1 with the RRT saturation adjustment, from Stephen Hill's fit
+const ACES_INPUT: Mat3 = Mat3::from_cols_array(&[
+    0.59719, 0.07600, 0.02840, 0.35458, 0.90834, 0.13383, 0.04823, 0.01566, 0.83777,
+]);
+
+const ACES_OUTPUT: Mat3 = Mat3::from_cols_array(&[
+    1.60475, -0.10208, -0.00327, -0.53108, 1.10813, -0.07276, -0.07367, -0.00605, 1.07602,
+]);
+
+/// ACES filmic curve, using Stephen Hill's fit of the RRT and ODT
+pub fn tonemap_aces(color: Vec3) -> Vec3 {
+    let v = ACES_INPUT * color;
+    let a = v * (v + Vec3::splat(0.024_578_6)) - Vec3::splat(0.000_090_537);
+    let b = v * (v * 0.983_729 + Vec3::splat(0.432_951)) + Vec3::splat(0.238_081);
+    (ACES_OUTPUT * (a / b)).clamp(Vec3::ZERO, Vec3::ONE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Vec3, expected: Vec3, tolerance: f32) {
+        assert!(
+            (actual - expected).abs().max_element() <= tolerance,
+            "{actual} != {expected}"
+        );
+    }
+
+    /// Grid of colors across the unit cube
+    fn colors() -> impl Iterator<Item = Vec3> {
+        (0..8 * 8 * 8).map(|i| Vec3::new((i % 8) as f32, (i / 8 % 8) as f32, (i / 64) as f32) / 7.0)
+    }
+
+    #[test]
+    fn srgb_values() {
+        assert_eq!(srgb_to_linear_channel(0.0), 0.0);
+        assert_eq!(srgb_to_linear_channel(1.0), 1.0);
+        assert!((srgb_to_linear_channel(0.5) - 0.214_041_14).abs() < 1e-6);
+        assert!((linear_to_srgb_channel(0.5) - 0.735_356_9).abs() < 1e-6);
+        assert!((linear_to_srgb_channel(0.002) - 0.025_84).abs() < 1e-6);
+
+        for color in colors() {
+            assert_close(linear_to_srgb(srgb_to_linear(color)), color, 1e-5);
+        }
+    }
+
+    #[test]
+    fn hsv_values() {
+        assert_close(
+            rgb_to_hsv(Vec3::new(1.0, 0.0, 0.0)),
+            Vec3::new(0.0, 1.0, 1.0),
+            1e-6,
+        );
+        assert_close(
+            rgb_to_hsv(Vec3::new(0.0, 1.0, 0.0)),
+            Vec3::new(1.0 / 3.0, 1.0, 1.0),
+            1e-6,
+        );
+        assert_close(
+            rgb_to_hsv(Vec3::new(0.0, 0.0, 0.5)),
+            Vec3::new(2.0 / 3.0, 1.0, 0.5),
+            1e-6,
+        );
+        assert_close(
+            rgb_to_hsv(Vec3::splat(0.25)),
+            Vec3::new(0.0, 0.0, 0.25),
+            1e-6,
+        );
+        assert_close(
+            hsv_to_rgb(Vec3::new(1.0 / 6.0, 1.0, 1.0)),
+            Vec3::new(1.0, 1.0, 0.0),
+            1e-6,
+        );
+        assert_close(
+            hsv_to_rgb(Vec3::new(0.5, 0.5, 0.8)),
+            Vec3::new(0.4, 0.8, 0.8),
+            1e-6,
+        );
+
+        for color in colors() {
+            assert_close(hsv_to_rgb(rgb_to_hsv(color)), color, 1e-5);
+        }
+    }
+
+    #[test]
+    fn oklab_values() {
+        // Reference values from Björn Ottosson's OKLab post
+        assert_close(linear_to_oklab(Vec3::ONE), Vec3::new(1.0, 0.0, 0.0), 1e-4);
+        assert_close(
+            linear_to_oklab(Vec3::new(1.0, 0.0, 0.0)),
+            Vec3::new(0.627_955, 0.224_863, 0.125_846),
+            1e-4,
+        );
+        assert_close(
+            linear_to_oklab(Vec3::new(0.0, 0.0, 1.0)),
+            Vec3::new(0.452_014, -0.032_457, -0.311_528),
+            1e-4,
+        );
+
+        for color in colors() {
+            assert_close(oklab_to_linear(linear_to_oklab(color)), color, 1e-4);
+        }
+
+        // Perceptual midpoint of black and white is brighter than the linear one
+        let mid = mix_oklab(Vec3::ZERO, Vec3::ONE, 0.5);
+        assert_close(mid, Vec3::splat(0.125), 1e-4);
+    }
+
+    #[test]
+    fn palette_values() {
+        let palette = Palette::default();
+        assert_close(
+            palette.sample(0.0),
+            Vec3::new(1.0, 0.259_123, 0.259_123),
+            1e-5,
+        );
+        assert_close(palette.sample(1.0), palette.sample(0.0), 1e-5);
+        assert_close(palette.swatch(2, 4), palette.sample(0.5), 1e-6);
+
+        for i in 0..16 {
+            let color = palette.swatch(i, 16);
+            assert!(color.min_element() >= 0.0 && color.max_element() <= 1.0);
+        }
+    }
+
+    #[test]
+    fn tonemap_values() {
+        assert_close(tonemap_reinhard(Vec3::ONE), Vec3::splat(0.5), 1e-6);
+        assert_close(
+            tonemap_reinhard_luminance(Vec3::splat(4.0), 4.0),
+            Vec3::ONE,
+            1e-6,
+        );
+        assert_eq!(tonemap_reinhard_luminance(Vec3::ZERO, 1.0), Vec3::ZERO);
+
+        assert_eq!(tonemap_aces(Vec3::ZERO), Vec3::ZERO);
+        assert_close(tonemap_aces(Vec3::splat(0.18)), Vec3::splat(0.1056), 1e-3);
+        assert_close(tonemap_aces(Vec3::ONE), Vec3::splat(0.6191), 1e-3);
+        assert_close(tonemap_aces(Vec3::splat(100.0)), Vec3::ONE, 1e-6);
+
+        // Both operators are monotonic and stay in range
+        let mut previous = (Vec3::ZERO, Vec3::ZERO);
+        for i in 1..200 {
+            let c = Vec3::splat(i as f32 * 0.1);
+            let current = (tonemap_reinhard(c), tonemap_aces(c));
+            assert!(current.0.x >= previous.0.x && current.1.x >= previous.1.x);
+            assert!(current.0.x <= 1.0 && current.1.x <= 1.0);
+            previous = current;
+        }
+    }
+}
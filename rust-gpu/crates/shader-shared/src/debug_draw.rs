@@ -0,0 +1,193 @@
+//! Debug shape layout and SDFs, shared by `debug_draw::fragment_debug_shapes` and the viewer's `DebugShapes`.
+
+use crate::glam::{Vec2, Vec4};
+
+#[cfg(target_arch = "spirv")]
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+pub const KIND_NONE: u32 = 0;
+pub const KIND_LINE: u32 = 1;
+pub const KIND_CIRCLE: u32 = 2;
+pub const KIND_RECT: u32 = 3;
+pub const KIND_ARROW: u32 = 4;
+
+/// A single debug shape, as laid out in the storage buffer
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "encase", derive(encase::ShaderType))]
+#[repr(C)]
+pub struct DebugShape {
+    /// Linear RGBA color
+    pub color: Vec4,
+    /// Line / arrow start, or circle / rect center
+    pub a: Vec2,
+    /// Line / arrow end, or rect half-extents
+    pub b: Vec2,
+    pub kind: u32,
+    /// Stroke width in world units, or zero for filled circles and rects
+    pub stroke: f32,
+    /// Circle radius, or arrow head length
+    pub size: f32,
+    pub _padding: f32,
+}
+
+fn distance_segment(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let pa = p - a;
+    let ba = b - a;
+    let h = (pa.dot(ba) / ba.dot(ba).max(f32::EPSILON)).clamp(0.0, 1.0);
+    (pa - ba * h).length()
+}
+
+fn distance_box(p: Vec2, extent: Vec2) -> f32 {
+    let d = p.abs() - extent;
+    d.max(Vec2::ZERO).length() + d.x.max(d.y).min(0.0)
+}
+
+impl DebugShape {
+    /// Oriented bounding box as `(center, x axis, half extents)`, excluding antialiasing margin
+    pub fn bounds(&self) -> (Vec2, Vec2, Vec2) {
+        let half_stroke = self.stroke * 0.5;
+        match self.kind {
+            KIND_LINE | KIND_ARROW => {
+                let delta = self.b - self.a;
+                let axis = delta.normalize_or_zero();
+                let axis = if axis == Vec2::ZERO { Vec2::X } else { axis };
+                let head = if self.kind == KIND_ARROW {
+                    self.size * 0.5
+                } else {
+                    0.0
+                };
+                (
+                    (self.a + self.b) * 0.5,
+                    axis,
+                    Vec2::new(delta.length() * 0.5 + half_stroke, head + half_stroke),
+                )
+            }
+            KIND_CIRCLE => (self.a, Vec2::X, Vec2::splat(self.size + half_stroke)),
+            KIND_RECT => (self.a, Vec2::X, self.b + half_stroke),
+            _ => (self.a, Vec2::X, Vec2::ZERO),
+        }
+    }
+
+    /// Signed distance from world-space point `p` to the shape's painted area
+    pub fn distance(&self, p: Vec2) -> f32 {
+        let half_stroke = self.stroke * 0.5;
+        match self.kind {
+            KIND_LINE => distance_segment(p, self.a, self.b) - half_stroke,
+            KIND_ARROW => {
+                let dir = (self.b - self.a).normalize_or_zero();
+                let perp = dir.perp();
+                let back = self.b - dir * self.size;
+                let head_l = back + perp * self.size * 0.5;
+                let head_r = back - perp * self.size * 0.5;
+                distance_segment(p, self.a, self.b)
+                    .min(distance_segment(p, self.b, head_l))
+                    .min(distance_segment(p, self.b, head_r))
+                    - half_stroke
+            }
+            KIND_CIRCLE => {
+                let d = (p - self.a).length() - self.size;
+                if self.stroke > 0.0 {
+                    d.abs() - half_stroke
+                } else {
+                    d
+                }
+            }
+            KIND_RECT => {
+                let d = distance_box(p - self.a, self.b);
+                if self.stroke > 0.0 {
+                    d.abs() - half_stroke
+                } else {
+                    d
+                }
+            }
+            _ => f32::MAX,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(kind: u32, a: Vec2, b: Vec2, stroke: f32, size: f32) -> DebugShape {
+        DebugShape {
+            kind,
+            a,
+            b,
+            stroke,
+            size,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn filled_and_stroked_distances() {
+        let disc = shape(KIND_CIRCLE, Vec2::ZERO, Vec2::ZERO, 0.0, 2.0);
+        assert_eq!(disc.distance(Vec2::ZERO), -2.0);
+        assert_eq!(disc.distance(Vec2::new(3.0, 0.0)), 1.0);
+
+        let ring = DebugShape {
+            stroke: 0.5,
+            ..disc
+        };
+        assert_eq!(ring.distance(Vec2::ZERO), 1.75);
+        assert_eq!(ring.distance(Vec2::new(0.0, 2.0)), -0.25);
+
+        let rect = shape(KIND_RECT, Vec2::ONE, Vec2::new(2.0, 1.0), 0.0, 0.0);
+        assert_eq!(rect.distance(Vec2::ONE), -1.0);
+        assert_eq!(rect.distance(Vec2::new(1.0, 4.0)), 2.0);
+
+        let line = shape(KIND_LINE, Vec2::ZERO, Vec2::X * 4.0, 1.0, 0.0);
+        assert_eq!(line.distance(Vec2::new(2.0, 3.0)), 2.5);
+        assert_eq!(line.distance(Vec2::new(6.0, 0.0)), 1.5);
+
+        let none = shape(KIND_NONE, Vec2::ZERO, Vec2::ZERO, 0.0, 0.0);
+        assert_eq!(none.distance(Vec2::ZERO), f32::MAX);
+    }
+
+    #[test]
+    fn bounds_contain_painted_area() {
+        let shapes = [
+            shape(
+                KIND_LINE,
+                Vec2::new(-1.0, 2.0),
+                Vec2::new(3.0, -1.0),
+                0.5,
+                0.0,
+            ),
+            shape(KIND_ARROW, Vec2::ZERO, Vec2::new(2.0, 2.0), 0.25, 1.0),
+            shape(KIND_CIRCLE, Vec2::new(1.0, 1.0), Vec2::ZERO, 0.5, 1.5),
+            shape(
+                KIND_RECT,
+                Vec2::new(-2.0, 0.0),
+                Vec2::new(1.0, 0.5),
+                0.0,
+                0.0,
+            ),
+        ];
+
+        for shape in shapes {
+            let (center, axis, extent) = shape.bounds();
+            let perp = axis.perp();
+
+            // Sample a grid around the shape, and check every painted point lies in its bounds
+            for y in -40..=40 {
+                for x in -40..=40 {
+                    let p = Vec2::new(x as f32, y as f32) * 0.125;
+                    if shape.distance(p) > 0.0 {
+                        continue;
+                    }
+
+                    let local = p - center;
+                    assert!(
+                        local.dot(axis).abs() <= extent.x + 1e-4
+                            && local.dot(perp).abs() <= extent.y + 1e-4,
+                        "{p} outside bounds of kind {}",
+                        shape.kind
+                    );
+                }
+            }
+        }
+    }
+}
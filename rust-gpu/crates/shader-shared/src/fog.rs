@@ -0,0 +1,172 @@
+//! Distance fog matching Bevy's `FogSettings`.
+//!
+//! [`Fog`] mirrors the fog uniform Bevy binds at `descriptor_set = 0, binding = 10`,
+//! and [`Fog::apply`] reproduces its linear, exponential, exponential-squared and atmospheric falloffs.
+
+use crate::glam::{Vec3, Vec4, Vec4Swizzles};
+
+#[cfg(target_arch = "spirv")]
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+pub const FOG_MODE_OFF: u32 = 0;
+pub const FOG_MODE_LINEAR: u32 = 1;
+pub const FOG_MODE_EXPONENTIAL: u32 = 2;
+pub const FOG_MODE_EXPONENTIAL_SQUARED: u32 = 3;
+pub const FOG_MODE_ATMOSPHERIC: u32 = 4;
+
+/// Mirror of Bevy's `GpuFog` uniform
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(C)]
+pub struct Fog {
+    /// Fog color, with alpha scaling its strength
+    pub base_color: Vec4,
+    /// Color added looking towards directional lights, with alpha scaling it
+    pub directional_light_color: Vec4,
+    /// Start and end distances for linear fog, density in `x` for exponential fog,
+    /// or per-channel extinction for atmospheric fog
+    pub be: Vec3,
+    /// Sharpness of the directional light glow
+    pub directional_light_exponent: f32,
+    /// Per-channel inscattering for atmospheric fog
+    pub bi: Vec3,
+    pub mode: u32,
+}
+
+impl Fog {
+    /// Glow contributed to the fog by a directional light of `light_color` in unit direction `to_light`,
+    /// seen along unit `view_direction`.
+    ///
+    /// Sum this over the scene's directional lights to get the `scattering` passed to [`Fog::apply`].
+    pub fn scattering(&self, view_direction: Vec3, to_light: Vec3, light_color: Vec3) -> Vec3 {
+        view_direction
+            .dot(to_light)
+            .max(0.0)
+            .powf(self.directional_light_exponent)
+            * light_color
+    }
+
+    /// Fog color with directional light `scattering` added
+    fn color(&self, scattering: Vec3) -> Vec4 {
+        if self.directional_light_color.w > 0.0 {
+            (self.base_color.xyz()
+                + scattering * self.directional_light_color.xyz() * self.directional_light_color.w)
+                .extend(self.base_color.w)
+        } else {
+            self.base_color
+        }
+    }
+
+    /// Fog `color` seen from `distance` away, keeping its alpha
+    pub fn apply(&self, color: Vec4, distance: f32, scattering: Vec3) -> Vec4 {
+        let fog_color = self.color(scattering);
+
+        let amount = if self.mode == FOG_MODE_LINEAR {
+            let (start, end) = (self.be.x, self.be.y);
+            1.0 - ((end - distance) / (end - start)).clamp(0.0, 1.0)
+        } else if self.mode == FOG_MODE_EXPONENTIAL {
+            1.0 - 1.0 / (distance * self.be.x).exp()
+        } else if self.mode == FOG_MODE_EXPONENTIAL_SQUARED {
+            1.0 - 1.0 / (distance * self.be.x).powf(2.0).exp()
+        } else if self.mode == FOG_MODE_ATMOSPHERIC {
+            // Extinction and inscattering vary per channel, so don't reduce to a single blend factor
+            let extinction = Vec3::ONE - exp3(-distance * self.be);
+            let inscattering = Vec3::ONE - exp3(-distance * self.bi);
+            let rgb = color.xyz() * (Vec3::ONE - extinction * fog_color.w)
+                + fog_color.xyz() * inscattering * fog_color.w;
+            return rgb.extend(color.w);
+        } else {
+            return color;
+        };
+
+        color
+            .xyz()
+            .lerp(fog_color.xyz(), amount * fog_color.w)
+            .extend(color.w)
+    }
+}
+
+fn exp3(v: Vec3) -> Vec3 {
+    Vec3::new(v.x.exp(), v.y.exp(), v.z.exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fog(mode: u32, be: Vec3, bi: Vec3) -> Fog {
+        Fog {
+            base_color: Vec4::new(0.5, 0.6, 0.7, 1.0),
+            directional_light_color: Vec4::ZERO,
+            be,
+            directional_light_exponent: 8.0,
+            bi,
+            mode,
+        }
+    }
+
+    const RED: Vec4 = Vec4::new(1.0, 0.0, 0.0, 0.25);
+
+    #[test]
+    fn off_leaves_color() {
+        let fog = fog(FOG_MODE_OFF, Vec3::ONE, Vec3::ONE);
+        assert_eq!(fog.apply(RED, 100.0, Vec3::ZERO), RED);
+    }
+
+    #[test]
+    fn linear_spans_start_to_end() {
+        let fog = fog(FOG_MODE_LINEAR, Vec3::new(10.0, 20.0, 0.0), Vec3::ZERO);
+        assert_eq!(fog.apply(RED, 5.0, Vec3::ZERO), RED);
+        assert!((fog.apply(RED, 15.0, Vec3::ZERO).x - 0.75).abs() < 1e-6);
+        assert_eq!(
+            fog.apply(RED, 30.0, Vec3::ZERO),
+            fog.base_color.xyz().extend(RED.w)
+        );
+    }
+
+    #[test]
+    fn exponential_falloffs() {
+        let density = 0.1;
+        let distance = 10.0;
+
+        let exponential = fog(FOG_MODE_EXPONENTIAL, Vec3::X * density, Vec3::ZERO);
+        let amount = 1.0 - (-1.0f32).exp();
+        let expected = RED.xyz().lerp(exponential.base_color.xyz(), amount);
+        assert!(exponential
+            .apply(RED, distance, Vec3::ZERO)
+            .xyz()
+            .abs_diff_eq(expected, 1e-6));
+
+        // Squared falloff stays clearer up close and thickens faster past `1 / density`
+        let squared = fog(FOG_MODE_EXPONENTIAL_SQUARED, Vec3::X * density, Vec3::ZERO);
+        assert!(squared.apply(RED, 5.0, Vec3::ZERO).x > exponential.apply(RED, 5.0, Vec3::ZERO).x);
+        assert!(
+            squared.apply(RED, 20.0, Vec3::ZERO).x < exponential.apply(RED, 20.0, Vec3::ZERO).x
+        );
+    }
+
+    #[test]
+    fn atmospheric_extinguishes_and_inscatters() {
+        let fog = fog(FOG_MODE_ATMOSPHERIC, Vec3::splat(0.1), Vec3::splat(0.05));
+        let near = fog.apply(RED, 0.0, Vec3::ZERO);
+        assert!(near.abs_diff_eq(RED, 1e-6));
+
+        let far = fog.apply(RED, 1000.0, Vec3::ZERO);
+        assert!(far.xyz().abs_diff_eq(fog.base_color.xyz(), 1e-4));
+        assert_eq!(far.w, RED.w);
+    }
+
+    #[test]
+    fn scattering_tints_towards_lights() {
+        let mut fog = fog(FOG_MODE_EXPONENTIAL, Vec3::X, Vec3::ZERO);
+        fog.directional_light_color = Vec4::new(1.0, 0.5, 0.0, 1.0);
+
+        let towards = fog.scattering(Vec3::Z, Vec3::Z, Vec3::ONE);
+        let away = fog.scattering(Vec3::Z, -Vec3::Z, Vec3::ONE);
+        assert_eq!(towards, Vec3::ONE);
+        assert_eq!(away, Vec3::ZERO);
+
+        let lit = fog.apply(RED, 100.0, towards);
+        assert!(lit.x > fog.base_color.x && lit.z == fog.base_color.z);
+    }
+}
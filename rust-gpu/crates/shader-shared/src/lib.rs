@@ -0,0 +1,21 @@
+//! Math shared between the `shader` crate and CPU code in the viewer.
+//!
+//! Builds against `spirv_std::glam` on the SPIR-V target, and against `glam` elsewhere
+//! so that host code can use the same types as Bevy.
+
+#![cfg_attr(target_arch = "spirv", no_std)]
+
+#[cfg(target_arch = "spirv")]
+pub use spirv_std::glam;
+
+#[cfg(not(target_arch = "spirv"))]
+pub use glam;
+
+pub mod atmosphere;
+pub mod color;
+pub mod debug_draw;
+pub mod fog;
+pub mod noise;
+pub mod procedural;
+pub mod warp;
+pub mod water;
@@ -0,0 +1,809 @@
+//! Procedural noise over `Vec2` and `Vec3`.
+//!
+//! Every function is a pure function of its input, hashed from integer lattice coordinates,
+//! so the same point gives the same result on the GPU and the host.
+//! Value, Perlin and simplex noise return a [`Noise2`] or [`Noise3`] in `[-1, 1]` with its analytic gradient.
+//! Worley noise returns distances to the nearest feature points,
+//! and [`Fractal`] layers any basis into fBm or ridged noise.
+
+use core::f32::consts::FRAC_1_SQRT_2;
+
+use crate::glam::{IVec2, IVec3, Vec2, Vec3};
+
+#[cfg(target_arch = "spirv")]
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+/// Noise value and its gradient over 2D input
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Noise2 {
+    pub value: f32,
+    pub gradient: Vec2,
+}
+
+/// Noise value and its gradient over 3D input
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Noise3 {
+    pub value: f32,
+    pub gradient: Vec3,
+}
+
+/// Distances to the nearest and second-nearest feature points of 2D Worley noise
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Worley2 {
+    pub f1: f32,
+    pub f2: f32,
+    /// Gradient of `f1`
+    pub gradient: Vec2,
+}
+
+/// Distances to the nearest and second-nearest feature points of 3D Worley noise
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Worley3 {
+    pub f1: f32,
+    pub f2: f32,
+    /// Gradient of `f1`
+    pub gradient: Vec3,
+}
+
+/// PCG-based integer hash
+fn pcg(v: u32) -> u32 {
+    let state = v.wrapping_mul(747796405).wrapping_add(2891336453);
+    let word = ((state >> ((state >> 28) + 4)) ^ state).wrapping_mul(277803737);
+    (word >> 22) ^ word
+}
+
+fn hash_2d(c: IVec2) -> u32 {
+    pcg((c.x as u32).wrapping_add(pcg(c.y as u32)))
+}
+
+fn hash_3d(c: IVec3) -> u32 {
+    pcg((c.x as u32).wrapping_add(pcg((c.y as u32).wrapping_add(pcg(c.z as u32)))))
+}
+
+/// Map a hash to `[0, 1)`
+fn unit(h: u32) -> f32 {
+    (h >> 8) as f32 * (1.0 / 16777216.0)
+}
+
+/// Map a hash to `[-1, 1)`
+fn signed(h: u32) -> f32 {
+    unit(h) * 2.0 - 1.0
+}
+
+/// Quintic fade curve, with zero first and second derivatives at 0 and 1
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn fade_derivative(t: f32) -> f32 {
+    30.0 * t * t * (t * (t - 2.0) + 1.0)
+}
+
+const GRADIENTS_2D: [Vec2; 8] = [
+    Vec2::new(1.0, 0.0),
+    Vec2::new(-1.0, 0.0),
+    Vec2::new(0.0, 1.0),
+    Vec2::new(0.0, -1.0),
+    Vec2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    Vec2::new(-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    Vec2::new(FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+    Vec2::new(-FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+];
+
+const GRADIENTS_3D: [Vec3; 12] = [
+    Vec3::new(1.0, 1.0, 0.0),
+    Vec3::new(-1.0, 1.0, 0.0),
+    Vec3::new(1.0, -1.0, 0.0),
+    Vec3::new(-1.0, -1.0, 0.0),
+    Vec3::new(1.0, 0.0, 1.0),
+    Vec3::new(-1.0, 0.0, 1.0),
+    Vec3::new(1.0, 0.0, -1.0),
+    Vec3::new(-1.0, 0.0, -1.0),
+    Vec3::new(0.0, 1.0, 1.0),
+    Vec3::new(0.0, -1.0, 1.0),
+    Vec3::new(0.0, 1.0, -1.0),
+    Vec3::new(0.0, -1.0, -1.0),
+];
+
+/// Unit-length lattice gradient for a 2D cell corner
+fn gradient_2d(c: IVec2) -> Vec2 {
+    GRADIENTS_2D[(hash_2d(c) & 7) as usize]
+}
+
+/// Unit-length lattice gradient for a 3D cell corner
+fn gradient_3d(c: IVec3) -> Vec3 {
+    GRADIENTS_3D[(hash_3d(c) % 12) as usize] * FRAC_1_SQRT_2
+}
+
+/// Bilinear interpolation of `[a, b, c, d]` at the corners of a unit square,
+/// where `f` is the position within it and `grads` are the corners' own gradients (zero for value noise)
+fn interpolate_2d(values: [f32; 4], grads: [Vec2; 4], f: Vec2) -> Noise2 {
+    let [a, b, c, d] = values;
+    let [ga, gb, gc, gd] = grads;
+    let u = Vec2::new(fade(f.x), fade(f.y));
+    let du = Vec2::new(fade_derivative(f.x), fade_derivative(f.y));
+    let k = a - b - c + d;
+
+    Noise2 {
+        value: a + u.x * (b - a) + u.y * (c - a) + u.x * u.y * k,
+        gradient: ga
+            + u.x * (gb - ga)
+            + u.y * (gc - ga)
+            + u.x * u.y * (ga - gb - gc + gd)
+            + du * Vec2::new(b - a + u.y * k, c - a + u.x * k),
+    }
+}
+
+/// Trilinear counterpart of [`interpolate_2d`], with corners ordered by `x`, then `y`, then `z`
+fn interpolate_3d(values: [f32; 8], grads: [Vec3; 8], f: Vec3) -> Noise3 {
+    let [a, b, c, d, e, g, h, i] = values;
+    let [ga, gb, gc, gd, ge, gg, gh, gi] = grads;
+    let u = Vec3::new(fade(f.x), fade(f.y), fade(f.z));
+    let du = Vec3::new(
+        fade_derivative(f.x),
+        fade_derivative(f.y),
+        fade_derivative(f.z),
+    );
+
+    let k1 = b - a;
+    let k2 = c - a;
+    let k3 = e - a;
+    let k4 = a - b - c + d;
+    let k5 = a - c - e + h;
+    let k6 = a - b - e + g;
+    let k7 = -a + b + c - d + e - g - h + i;
+
+    let gk1 = gb - ga;
+    let gk2 = gc - ga;
+    let gk3 = ge - ga;
+    let gk4 = ga - gb - gc + gd;
+    let gk5 = ga - gc - ge + gh;
+    let gk6 = ga - gb - ge + gg;
+    let gk7 = -ga + gb + gc - gd + ge - gg - gh + gi;
+
+    Noise3 {
+        value: a
+            + u.x * k1
+            + u.y * k2
+            + u.z * k3
+            + u.x * u.y * k4
+            + u.y * u.z * k5
+            + u.z * u.x * k6
+            + u.x * u.y * u.z * k7,
+        gradient: ga
+            + u.x * gk1
+            + u.y * gk2
+            + u.z * gk3
+            + u.x * u.y * gk4
+            + u.y * u.z * gk5
+            + u.z * u.x * gk6
+            + u.x * u.y * u.z * gk7
+            + du * Vec3::new(
+                k1 + k4 * u.y + k6 * u.z + k7 * u.y * u.z,
+                k2 + k5 * u.z + k4 * u.x + k7 * u.z * u.x,
+                k3 + k6 * u.x + k5 * u.y + k7 * u.x * u.y,
+            ),
+    }
+}
+
+const CORNERS_2D: [IVec2; 4] = [
+    IVec2::new(0, 0),
+    IVec2::new(1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(1, 1),
+];
+
+const CORNERS_3D: [IVec3; 8] = [
+    IVec3::new(0, 0, 0),
+    IVec3::new(1, 0, 0),
+    IVec3::new(0, 1, 0),
+    IVec3::new(1, 1, 0),
+    IVec3::new(0, 0, 1),
+    IVec3::new(1, 0, 1),
+    IVec3::new(0, 1, 1),
+    IVec3::new(1, 1, 1),
+];
+
+/// Value noise: smoothly interpolated random values at lattice points
+pub fn value_2d(p: Vec2) -> Noise2 {
+    let i = p.floor();
+    let cell = i.as_ivec2();
+    let mut values = [0.0; 4];
+    let mut k = 0;
+    while k < 4 {
+        values[k] = signed(hash_2d(cell + CORNERS_2D[k]));
+        k += 1;
+    }
+    interpolate_2d(values, [Vec2::ZERO; 4], p - i)
+}
+
+/// Value noise: smoothly interpolated random values at lattice points
+pub fn value_3d(p: Vec3) -> Noise3 {
+    let i = p.floor();
+    let cell = i.as_ivec3();
+    let mut values = [0.0; 8];
+    let mut k = 0;
+    while k < 8 {
+        values[k] = signed(hash_3d(cell + CORNERS_3D[k]));
+        k += 1;
+    }
+    interpolate_3d(values, [Vec3::ZERO; 8], p - i)
+}
+
+/// Perlin gradient noise
+pub fn perlin_2d(p: Vec2) -> Noise2 {
+    // Largest possible magnitude with unit gradients is sqrt(2) / 2
+    const SCALE: f32 = core::f32::consts::SQRT_2;
+
+    let i = p.floor();
+    let f = p - i;
+    let cell = i.as_ivec2();
+    let mut values = [0.0; 4];
+    let mut grads = [Vec2::ZERO; 4];
+    let mut k = 0;
+    while k < 4 {
+        let corner = CORNERS_2D[k];
+        grads[k] = gradient_2d(cell + corner);
+        values[k] = grads[k].dot(f - corner.as_vec2());
+        k += 1;
+    }
+
+    let noise = interpolate_2d(values, grads, f);
+    Noise2 {
+        value: noise.value * SCALE,
+        gradient: noise.gradient * SCALE,
+    }
+}
+
+/// Perlin gradient noise
+pub fn perlin_3d(p: Vec3) -> Noise3 {
+    // Largest possible magnitude with unit gradients is sqrt(3) / 2
+    const SCALE: f32 = 2.0 / 1.7320508;
+
+    let i = p.floor();
+    let f = p - i;
+    let cell = i.as_ivec3();
+    let mut values = [0.0; 8];
+    let mut grads = [Vec3::ZERO; 8];
+    let mut k = 0;
+    while k < 8 {
+        let corner = CORNERS_3D[k];
+        grads[k] = gradient_3d(cell + corner);
+        values[k] = grads[k].dot(f - corner.as_vec3());
+        k += 1;
+    }
+
+    let noise = interpolate_3d(values, grads, f);
+    Noise3 {
+        value: noise.value * SCALE,
+        gradient: noise.gradient * SCALE,
+    }
+}
+
+/// Contribution of one simplex corner at offset `d` with gradient `g`, as `(value, gradient)`
+fn simplex_corner_2d(d: Vec2, g: Vec2) -> (f32, Vec2) {
+    let t = 0.5 - d.dot(d);
+    if t <= 0.0 {
+        return (0.0, Vec2::ZERO);
+    }
+    let t2 = t * t;
+    let t4 = t2 * t2;
+    let gd = g.dot(d);
+    (t4 * gd, t4 * g - 8.0 * t2 * t * gd * d)
+}
+
+fn simplex_corner_3d(d: Vec3, g: Vec3) -> (f32, Vec3) {
+    let t = 0.5 - d.dot(d);
+    if t <= 0.0 {
+        return (0.0, Vec3::ZERO);
+    }
+    let t2 = t * t;
+    let t4 = t2 * t2;
+    let gd = g.dot(d);
+    (t4 * gd, t4 * g - 8.0 * t2 * t * gd * d)
+}
+
+/// Simplex noise, summing radial kernels over the corners of a triangular lattice
+pub fn simplex_2d(p: Vec2) -> Noise2 {
+    const F2: f32 = 0.366_025_42;
+    const G2: f32 = 0.211_324_87;
+    // Reciprocal of the largest possible kernel sum with unit gradients
+    const SCALE: f32 = 98.0;
+
+    let skewed = (p + Vec2::splat((p.x + p.y) * F2)).floor();
+    let cell = skewed.as_ivec2();
+    let d0 = p - (skewed - Vec2::splat((skewed.x + skewed.y) * G2));
+
+    let o1 = if d0.x > d0.y { IVec2::X } else { IVec2::Y };
+    let d1 = d0 - o1.as_vec2() + Vec2::splat(G2);
+    let d2 = d0 - Vec2::ONE + Vec2::splat(2.0 * G2);
+
+    let (v0, g0) = simplex_corner_2d(d0, gradient_2d(cell));
+    let (v1, g1) = simplex_corner_2d(d1, gradient_2d(cell + o1));
+    let (v2, g2) = simplex_corner_2d(d2, gradient_2d(cell + IVec2::ONE));
+
+    Noise2 {
+        value: (v0 + v1 + v2) * SCALE,
+        gradient: (g0 + g1 + g2) * SCALE,
+    }
+}
+
+/// Simplex noise, summing radial kernels over the corners of a tetrahedral lattice
+pub fn simplex_3d(p: Vec3) -> Noise3 {
+    const F3: f32 = 1.0 / 3.0;
+    const G3: f32 = 1.0 / 6.0;
+    // Reciprocal of the largest possible kernel sum with unit gradients
+    const SCALE: f32 = 106.0;
+
+    let skewed = (p + Vec3::splat((p.x + p.y + p.z) * F3)).floor();
+    let cell = skewed.as_ivec3();
+    let d0 = p - (skewed - Vec3::splat((skewed.x + skewed.y + skewed.z) * G3));
+
+    // Traverse the simplex along the axes in order of decreasing offset
+    let (o1, o2) = if d0.x >= d0.y {
+        if d0.y >= d0.z {
+            (IVec3::X, IVec3::new(1, 1, 0))
+        } else if d0.x >= d0.z {
+            (IVec3::X, IVec3::new(1, 0, 1))
+        } else {
+            (IVec3::Z, IVec3::new(1, 0, 1))
+        }
+    } else if d0.y < d0.z {
+        (IVec3::Z, IVec3::new(0, 1, 1))
+    } else if d0.x < d0.z {
+        (IVec3::Y, IVec3::new(0, 1, 1))
+    } else {
+        (IVec3::Y, IVec3::new(1, 1, 0))
+    };
+
+    let d1 = d0 - o1.as_vec3() + Vec3::splat(G3);
+    let d2 = d0 - o2.as_vec3() + Vec3::splat(2.0 * G3);
+    let d3 = d0 - Vec3::ONE + Vec3::splat(3.0 * G3);
+
+    let (v0, g0) = simplex_corner_3d(d0, gradient_3d(cell));
+    let (v1, g1) = simplex_corner_3d(d1, gradient_3d(cell + o1));
+    let (v2, g2) = simplex_corner_3d(d2, gradient_3d(cell + o2));
+    let (v3, g3) = simplex_corner_3d(d3, gradient_3d(cell + IVec3::ONE));
+
+    Noise3 {
+        value: (v0 + v1 + v2 + v3) * SCALE,
+        gradient: (g0 + g1 + g2 + g3) * SCALE,
+    }
+}
+
+/// Worley (cellular) noise, with one randomly placed feature point per unit cell
+pub fn worley_2d(p: Vec2) -> Worley2 {
+    let i = p.floor();
+    let cell = i.as_ivec2();
+
+    let mut f1 = f32::MAX;
+    let mut f2 = f32::MAX;
+    let mut nearest = Vec2::ZERO;
+
+    let mut y = -1;
+    while y <= 1 {
+        let mut x = -1;
+        while x <= 1 {
+            let neighbour = cell + IVec2::new(x, y);
+            let h = hash_2d(neighbour);
+            let feature = neighbour.as_vec2() + Vec2::new(unit(h), unit(pcg(h)));
+            let distance = (p - feature).length();
+
+            if distance < f1 {
+                f2 = f1;
+                f1 = distance;
+                nearest = feature;
+            } else if distance < f2 {
+                f2 = distance;
+            }
+
+            x += 1;
+        }
+        y += 1;
+    }
+
+    Worley2 {
+        f1,
+        f2,
+        gradient: (p - nearest).normalize_or_zero(),
+    }
+}
+
+/// Worley (cellular) noise, with one randomly placed feature point per unit cell
+pub fn worley_3d(p: Vec3) -> Worley3 {
+    let i = p.floor();
+    let cell = i.as_ivec3();
+
+    let mut f1 = f32::MAX;
+    let mut f2 = f32::MAX;
+    let mut nearest = Vec3::ZERO;
+
+    let mut z = -1;
+    while z <= 1 {
+        let mut y = -1;
+        while y <= 1 {
+            let mut x = -1;
+            while x <= 1 {
+                let neighbour = cell + IVec3::new(x, y, z);
+                let h = hash_3d(neighbour);
+                let h2 = pcg(h);
+                let feature = neighbour.as_vec3() + Vec3::new(unit(h), unit(h2), unit(pcg(h2)));
+                let distance = (p - feature).length();
+
+                if distance < f1 {
+                    f2 = f1;
+                    f1 = distance;
+                    nearest = feature;
+                } else if distance < f2 {
+                    f2 = distance;
+                }
+
+                x += 1;
+            }
+            y += 1;
+        }
+        z += 1;
+    }
+
+    Worley3 {
+        f1,
+        f2,
+        gradient: (p - nearest).normalize_or_zero(),
+    }
+}
+
+/// Octave layering of a basis noise function
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Fractal {
+    pub octaves: u32,
+    /// Frequency multiplier between octaves
+    pub lacunarity: f32,
+    /// Amplitude multiplier between octaves
+    pub gain: f32,
+}
+
+impl Default for Fractal {
+    fn default() -> Self {
+        Fractal {
+            octaves: 5,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+impl Fractal {
+    /// Fractional Brownian motion, normalized back into `[-1, 1]`
+    pub fn fbm_2d(&self, p: Vec2, basis: impl Fn(Vec2) -> Noise2) -> Noise2 {
+        let mut sum = Noise2::default();
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut total = 0.0;
+
+        let mut i = 0;
+        while i < self.octaves {
+            let n = basis(p * frequency);
+            sum.value += n.value * amplitude;
+            sum.gradient += n.gradient * amplitude * frequency;
+            total += amplitude;
+            amplitude *= self.gain;
+            frequency *= self.lacunarity;
+            i += 1;
+        }
+
+        normalize_2d(sum, total)
+    }
+
+    /// Fractional Brownian motion, normalized back into `[-1, 1]`
+    pub fn fbm_3d(&self, p: Vec3, basis: impl Fn(Vec3) -> Noise3) -> Noise3 {
+        let mut sum = Noise3::default();
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut total = 0.0;
+
+        let mut i = 0;
+        while i < self.octaves {
+            let n = basis(p * frequency);
+            sum.value += n.value * amplitude;
+            sum.gradient += n.gradient * amplitude * frequency;
+            total += amplitude;
+            amplitude *= self.gain;
+            frequency *= self.lacunarity;
+            i += 1;
+        }
+
+        normalize_3d(sum, total)
+    }
+
+    /// Ridged multifractal, folding each octave into sharp crests, normalized into `[0, 1]`
+    pub fn ridged_2d(&self, p: Vec2, basis: impl Fn(Vec2) -> Noise2) -> Noise2 {
+        let mut sum = Noise2::default();
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut total = 0.0;
+
+        let mut i = 0;
+        while i < self.octaves {
+            let n = basis(p * frequency);
+            let ridge = 1.0 - n.value.abs();
+            sum.value += ridge * ridge * amplitude;
+            sum.gradient -= n.gradient * (2.0 * ridge * n.value.signum() * amplitude * frequency);
+            total += amplitude;
+            amplitude *= self.gain;
+            frequency *= self.lacunarity;
+            i += 1;
+        }
+
+        normalize_2d(sum, total)
+    }
+
+    /// Ridged multifractal, folding each octave into sharp crests, normalized into `[0, 1]`
+    pub fn ridged_3d(&self, p: Vec3, basis: impl Fn(Vec3) -> Noise3) -> Noise3 {
+        let mut sum = Noise3::default();
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut total = 0.0;
+
+        let mut i = 0;
+        while i < self.octaves {
+            let n = basis(p * frequency);
+            let ridge = 1.0 - n.value.abs();
+            sum.value += ridge * ridge * amplitude;
+            sum.gradient -= n.gradient * (2.0 * ridge * n.value.signum() * amplitude * frequency);
+            total += amplitude;
+            amplitude *= self.gain;
+            frequency *= self.lacunarity;
+            i += 1;
+        }
+
+        normalize_3d(sum, total)
+    }
+}
+
+fn normalize_2d(noise: Noise2, total: f32) -> Noise2 {
+    if total <= 0.0 {
+        return Noise2::default();
+    }
+    Noise2 {
+        value: noise.value / total,
+        gradient: noise.gradient / total,
+    }
+}
+
+fn normalize_3d(noise: Noise3, total: f32) -> Noise3 {
+    if total <= 0.0 {
+        return Noise3::default();
+    }
+    Noise3 {
+        value: noise.value / total,
+        gradient: noise.gradient / total,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const H: f32 = 1e-3;
+
+    /// Well-spread sample points, including negative coordinates and lattice-aligned ones
+    fn points_2d() -> impl Iterator<Item = Vec2> {
+        (0..4000).map(|i| {
+            let i = i as f32;
+            Vec2::new(
+                (i * 0.754_877_7).fract() * 40.0 - 20.0,
+                (i * 0.569_840_3).fract() * 40.0 - 20.0,
+            )
+        })
+    }
+
+    fn points_3d() -> impl Iterator<Item = Vec3> {
+        (0..4000).map(|i| {
+            let i = i as f32;
+            Vec3::new(
+                (i * 0.819_172_5).fract() * 40.0 - 20.0,
+                (i * 0.671_043_5).fract() * 40.0 - 20.0,
+                (i * 0.549_700_4).fract() * 40.0 - 20.0,
+            )
+        })
+    }
+
+    fn assert_gradient_2d(name: &str, f: impl Fn(Vec2) -> Noise2, tolerance: f32) {
+        for p in points_2d() {
+            let n = f(p);
+            let fd = Vec2::new(
+                f(p + Vec2::X * H).value - f(p - Vec2::X * H).value,
+                f(p + Vec2::Y * H).value - f(p - Vec2::Y * H).value,
+            ) / (2.0 * H);
+            assert!(
+                (fd - n.gradient).length() <= tolerance * (1.0 + n.gradient.length()),
+                "{name} at {p}: gradient {} != finite difference {fd}",
+                n.gradient
+            );
+        }
+    }
+
+    fn assert_gradient_3d(name: &str, f: impl Fn(Vec3) -> Noise3, tolerance: f32) {
+        for p in points_3d() {
+            let n = f(p);
+            let fd = Vec3::new(
+                f(p + Vec3::X * H).value - f(p - Vec3::X * H).value,
+                f(p + Vec3::Y * H).value - f(p - Vec3::Y * H).value,
+                f(p + Vec3::Z * H).value - f(p - Vec3::Z * H).value,
+            ) / (2.0 * H);
+            assert!(
+                (fd - n.gradient).length() <= tolerance * (1.0 + n.gradient.length()),
+                "{name} at {p}: gradient {} != finite difference {fd}",
+                n.gradient
+            );
+        }
+    }
+
+    fn assert_range_2d(name: &str, f: impl Fn(Vec2) -> Noise2, min: f32, max: f32) {
+        for p in points_2d() {
+            let v = f(p).value;
+            assert!((min..=max).contains(&v), "{name} at {p}: {v} out of range");
+        }
+    }
+
+    fn assert_range_3d(name: &str, f: impl Fn(Vec3) -> Noise3, min: f32, max: f32) {
+        for p in points_3d() {
+            let v = f(p).value;
+            assert!((min..=max).contains(&v), "{name} at {p}: {v} out of range");
+        }
+    }
+
+    fn fbm_perlin_2d(p: Vec2) -> Noise2 {
+        Fractal::default().fbm_2d(p, perlin_2d)
+    }
+
+    fn fbm_simplex_3d(p: Vec3) -> Noise3 {
+        Fractal::default().fbm_3d(p, simplex_3d)
+    }
+
+    fn ridged_perlin_2d(p: Vec2) -> Noise2 {
+        Fractal::default().ridged_2d(p, perlin_2d)
+    }
+
+    fn ridged_value_3d(p: Vec3) -> Noise3 {
+        Fractal::default().ridged_3d(p, value_3d)
+    }
+
+    #[test]
+    fn range() {
+        assert_range_2d("value_2d", value_2d, -1.0, 1.0);
+        assert_range_2d("perlin_2d", perlin_2d, -1.0, 1.0);
+        assert_range_2d("simplex_2d", simplex_2d, -1.0, 1.0);
+        assert_range_2d("fbm_2d", fbm_perlin_2d, -1.0, 1.0);
+        assert_range_2d("ridged_2d", ridged_perlin_2d, 0.0, 1.0);
+
+        assert_range_3d("value_3d", value_3d, -1.0, 1.0);
+        assert_range_3d("perlin_3d", perlin_3d, -1.0, 1.0);
+        assert_range_3d("simplex_3d", simplex_3d, -1.0, 1.0);
+        assert_range_3d("fbm_3d", fbm_simplex_3d, -1.0, 1.0);
+        assert_range_3d("ridged_3d", ridged_value_3d, 0.0, 1.0);
+
+        for p in points_2d() {
+            let w = worley_2d(p);
+            assert!(0.0 <= w.f1 && w.f1 <= w.f2 && w.f1 <= core::f32::consts::SQRT_2);
+        }
+        for p in points_3d() {
+            let w = worley_3d(p);
+            assert!(0.0 <= w.f1 && w.f1 <= w.f2 && w.f1 <= 1.7320508);
+        }
+    }
+
+    #[test]
+    fn range_is_used() {
+        // Guard against a scale constant collapsing the output towards zero
+        for (name, f) in [
+            ("perlin_2d", perlin_2d as fn(Vec2) -> Noise2),
+            ("simplex_2d", simplex_2d),
+        ] {
+            let max = points_2d().map(|p| f(p).value.abs()).fold(0.0, f32::max);
+            assert!(max > 0.5, "{name} peaks at {max}");
+        }
+        for (name, f) in [
+            ("perlin_3d", perlin_3d as fn(Vec3) -> Noise3),
+            ("simplex_3d", simplex_3d),
+        ] {
+            let max = points_3d().map(|p| f(p).value.abs()).fold(0.0, f32::max);
+            assert!(max > 0.5, "{name} peaks at {max}");
+        }
+    }
+
+    #[test]
+    fn gradients_match_finite_differences() {
+        assert_gradient_2d("value_2d", value_2d, 1e-2);
+        assert_gradient_2d("perlin_2d", perlin_2d, 1e-2);
+        assert_gradient_2d("simplex_2d", simplex_2d, 1e-2);
+        assert_gradient_2d("fbm_2d", fbm_perlin_2d, 5e-2);
+
+        assert_gradient_3d("value_3d", value_3d, 1e-2);
+        assert_gradient_3d("perlin_3d", perlin_3d, 1e-2);
+        assert_gradient_3d("simplex_3d", simplex_3d, 1e-2);
+        assert_gradient_3d("fbm_3d", fbm_simplex_3d, 5e-2);
+
+        for p in points_2d() {
+            let w = worley_2d(p);
+            // F1 is only differentiable away from cell boundaries
+            if w.f2 - w.f1 < 1e-2 {
+                continue;
+            }
+            let fd = Vec2::new(
+                worley_2d(p + Vec2::X * H).f1 - worley_2d(p - Vec2::X * H).f1,
+                worley_2d(p + Vec2::Y * H).f1 - worley_2d(p - Vec2::Y * H).f1,
+            ) / (2.0 * H);
+            assert!((fd - w.gradient).length() < 2e-2, "worley_2d at {p}");
+        }
+    }
+
+    #[test]
+    fn continuity() {
+        // Small steps, including across lattice lines, only move the value a little
+        const STEP: f32 = 1e-4;
+        let lipschitz = 40.0 * STEP;
+
+        for p in points_2d() {
+            for f in [
+                value_2d,
+                perlin_2d,
+                simplex_2d,
+                fbm_perlin_2d,
+                ridged_perlin_2d,
+            ] {
+                let d = (f(p + Vec2::splat(STEP)).value - f(p).value).abs();
+                assert!(d < lipschitz, "discontinuity of {d} at {p}");
+            }
+            let d = (worley_2d(p + Vec2::splat(STEP)).f1 - worley_2d(p).f1).abs();
+            assert!(d < lipschitz, "worley_2d discontinuity of {d} at {p}");
+        }
+
+        for p in points_3d() {
+            for f in [
+                value_3d,
+                perlin_3d,
+                simplex_3d,
+                fbm_simplex_3d,
+                ridged_value_3d,
+            ] {
+                let d = (f(p + Vec3::splat(STEP)).value - f(p).value).abs();
+                assert!(d < lipschitz, "discontinuity of {d} at {p}");
+            }
+            let d = (worley_3d(p + Vec3::splat(STEP)).f1 - worley_3d(p).f1).abs();
+            assert!(d < lipschitz, "worley_3d discontinuity of {d} at {p}");
+        }
+
+        // Exactly on lattice lines, where the cell lookup switches
+        for i in -5..5 {
+            for f in [value_2d, perlin_2d, simplex_2d] {
+                let p = Vec2::new(i as f32, 0.37);
+                let below = f(p - Vec2::X * STEP).value;
+                let above = f(p + Vec2::X * STEP).value;
+                assert!((above - below).abs() < lipschitz, "discontinuity at {p}");
+            }
+        }
+    }
+
+    #[test]
+    fn determinism() {
+        for p in points_2d().take(100) {
+            assert_eq!(value_2d(p), value_2d(p));
+            assert_eq!(perlin_2d(p), perlin_2d(p));
+            assert_eq!(simplex_2d(p), simplex_2d(p));
+            assert_eq!(worley_2d(p), worley_2d(p));
+        }
+        for p in points_3d().take(100) {
+            assert_eq!(value_3d(p), value_3d(p));
+            assert_eq!(perlin_3d(p), perlin_3d(p));
+            assert_eq!(simplex_3d(p), simplex_3d(p));
+            assert_eq!(worley_3d(p), worley_3d(p));
+        }
+
+        // Pinned values, so hash or constant changes that would alter existing materials are caught
+        assert_eq!(pcg(0), 129708002);
+        assert_eq!(hash_2d(IVec2::new(3, -7)), hash_2d(IVec2::new(3, -7)));
+        assert_ne!(hash_2d(IVec2::new(3, -7)), hash_2d(IVec2::new(-7, 3)));
+    }
+}
@@ -0,0 +1,325 @@
+//! Solid procedural surface patterns.
+//!
+//! Each [`Pattern`] maps an object-space position to the PBR inputs of a [`Surface`]:
+//! base color, roughness, metallic, and a height field whose analytic gradient perturbs the normal.
+//! The `shader` crate selects a pattern by type name through `fragment_procedural`'s permutations.
+
+use core::f32::consts::{PI, TAU};
+
+use crate::{
+    glam::Vec3,
+    noise::{perlin_3d, simplex_3d, value_3d, worley_3d, Fractal, Noise3},
+};
+
+#[cfg(target_arch = "spirv")]
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+/// PBR inputs produced by a [`Pattern`]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Surface {
+    /// Linear base color
+    pub base_color: Vec3,
+    pub roughness: f32,
+    pub metallic: f32,
+    /// Relief in `[0, 1]` and its object-space gradient, used for bump mapping
+    pub height: Noise3,
+}
+
+impl Surface {
+    /// Tilt `normal` against the slope of the height field, scaled by `strength`
+    pub fn perturb_normal(&self, normal: Vec3, strength: f32) -> Vec3 {
+        let gradient = self.height.gradient;
+        let surface_gradient = gradient - normal * normal.dot(gradient);
+        (normal - surface_gradient * strength).normalize()
+    }
+}
+
+/// A solid texture evaluated in object space
+pub trait Pattern {
+    /// Type name used to select this pattern's entry point permutation
+    const NAME: &'static str;
+
+    fn surface(&self, p: Vec3) -> Surface;
+}
+
+/// Cubic smoothstep of `x` between `e0` and `e1`, with its derivative with respect to `x`
+fn smooth_step(e0: f32, e1: f32, x: f32) -> (f32, f32) {
+    let t = ((x - e0) / (e1 - e0)).clamp(0.0, 1.0);
+    let dt = if t > 0.0 && t < 1.0 {
+        1.0 / (e1 - e0)
+    } else {
+        0.0
+    };
+    (t * t * (3.0 - 2.0 * t), 6.0 * t * (1.0 - t) * dt)
+}
+
+/// Polished white marble with dark turbulent veins
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Marble;
+
+impl Pattern for Marble {
+    const NAME: &'static str = "Marble";
+
+    fn surface(&self, p: Vec3) -> Surface {
+        let turbulence = Fractal::default().fbm_3d(p * 1.5, perlin_3d);
+
+        // Bands along X, bent by turbulence
+        let t = p.x * 3.0 + turbulence.value * 4.0;
+        let dt = Vec3::X * 3.0 + turbulence.gradient * 6.0;
+
+        // Thin veins where the bands cross zero
+        let band = 1.0 - t.sin().abs();
+        let dband = -t.sin().signum() * t.cos() * dt;
+        let vein = band.powi(8);
+        let dvein = 8.0 * band.powi(7) * dband;
+
+        let stone = Vec3::new(0.92, 0.9, 0.86);
+        let vein_color = Vec3::new(0.18, 0.2, 0.24);
+
+        Surface {
+            base_color: stone.lerp(vein_color, vein),
+            roughness: 0.08 + vein * 0.25,
+            metallic: 0.0,
+            // Veins are worn slightly into the polished surface
+            height: Noise3 {
+                value: 1.0 - vein,
+                gradient: -dvein,
+            },
+        }
+    }
+}
+
+/// Growth rings around the Y axis with fine grain along it
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Wood;
+
+impl Pattern for Wood {
+    const NAME: &'static str = "Wood";
+
+    fn surface(&self, p: Vec3) -> Surface {
+        let wobble = Fractal {
+            octaves: 3,
+            ..Default::default()
+        }
+        .fbm_3d(p * Vec3::new(1.0, 0.15, 1.0), perlin_3d);
+
+        // Ring coordinate, distorted so rings are not perfect circles
+        let radius = (p.x * p.x + p.z * p.z).sqrt().max(1e-4);
+        let dradius = Vec3::new(p.x, 0.0, p.z) / radius;
+        let r = radius * 6.0 + wobble.value * 0.8;
+        let dr = dradius * 6.0 + wobble.gradient * Vec3::new(0.8, 0.12, 0.8);
+
+        // Light early wood ramping into a dark band of late wood
+        let ring = r - r.floor();
+        let late = ring.powi(3);
+        let dlate = 3.0 * ring * ring * dr;
+
+        // Streaks stretched along the trunk
+        let grain = value_3d(p * Vec3::new(40.0, 1.5, 40.0));
+        let streak = grain.value * 0.5 + 0.5;
+
+        let early_color = Vec3::new(0.62, 0.4, 0.2);
+        let late_color = Vec3::new(0.3, 0.15, 0.06);
+        let base_color = early_color.lerp(late_color, late) * (0.85 + 0.15 * streak);
+
+        Surface {
+            base_color,
+            roughness: 0.55 + late * 0.2,
+            metallic: 0.0,
+            // Soft early wood wears away, leaving late wood raised
+            height: Noise3 {
+                value: late * 0.8 + streak * 0.2,
+                gradient: dlate * 0.8 + grain.gradient * Vec3::new(40.0, 1.5, 40.0) * 0.1,
+            },
+        }
+    }
+}
+
+/// Bare steel eaten by patches of flaking rust and pitting
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Rust;
+
+impl Pattern for Rust {
+    const NAME: &'static str = "Rust";
+
+    fn surface(&self, p: Vec3) -> Surface {
+        let patches = Fractal::default().fbm_3d(p * 1.2, simplex_3d);
+        let (coverage, dcoverage) = smooth_step(-0.1, 0.15, patches.value);
+        let dcoverage = patches.gradient * 1.2 * dcoverage;
+
+        // Flaky texture within the rust
+        let flakes = Fractal {
+            octaves: 3,
+            ..Default::default()
+        }
+        .fbm_3d(p * 12.0, value_3d);
+        let flake = flakes.value * 0.5 + 0.5;
+        let dflake = flakes.gradient * 6.0;
+
+        // Pits where the steel has corroded through, strongest under the rust
+        let cells = worley_3d(p * 8.0);
+        let (pit, dpit) = smooth_step(0.3, 0.0, cells.f1);
+        let dpit = cells.gradient * 8.0 * dpit;
+
+        let steel = Vec3::new(0.56, 0.57, 0.58);
+        let rust_light = Vec3::new(0.55, 0.25, 0.08);
+        let rust_dark = Vec3::new(0.22, 0.08, 0.03);
+        let rust = rust_dark.lerp(rust_light, flake);
+
+        let height = coverage * (0.6 + 0.4 * flake) - pit * coverage * 0.3;
+        let dheight = dcoverage * (0.6 + 0.4 * flake) + dflake * 0.4 * coverage
+            - (dpit * coverage + dcoverage * pit) * 0.3;
+
+        Surface {
+            base_color: steel.lerp(rust, coverage) * (1.0 - pit * 0.4),
+            roughness: 0.3 + coverage * 0.6,
+            metallic: 1.0 - coverage,
+            height: Noise3 {
+                value: height.clamp(0.0, 1.0),
+                gradient: dheight,
+            },
+        }
+    }
+}
+
+/// Layered sedimentary rock, banded by height and weathered between layers
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Strata;
+
+impl Pattern for Strata {
+    const NAME: &'static str = "Strata";
+
+    fn surface(&self, p: Vec3) -> Surface {
+        let folding = Fractal {
+            octaves: 4,
+            ..Default::default()
+        }
+        .fbm_3d(p * 0.6, perlin_3d);
+
+        // Layer coordinate, folded so layers dip and rise
+        let t = p.y * 2.5 + folding.value * 1.2;
+        let dt = Vec3::Y * 2.5 + folding.gradient * 0.72;
+
+        // Cosine palette through sandstone, ochre and shale
+        let phase = Vec3::new(0.0, 0.1, 0.2);
+        let wave = (t * 0.7 + phase) * TAU;
+        let palette = Vec3::new(0.5, 0.38, 0.27)
+            + Vec3::new(0.2, 0.15, 0.1) * Vec3::new(wave.x.cos(), wave.y.cos(), wave.z.cos());
+
+        // Hard layers stand proud, soft ones erode back
+        let ledge = (t * TAU).sin() * 0.5 + 0.5;
+        let dledge = PI * (t * TAU).cos() * dt;
+
+        Surface {
+            base_color: palette * (0.8 + 0.2 * ledge),
+            roughness: 0.95 - ledge * 0.25,
+            metallic: 0.0,
+            height: Noise3 {
+                value: ledge,
+                gradient: dledge,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const H: f32 = 1e-3;
+
+    fn points() -> impl Iterator<Item = Vec3> {
+        (0..2000).map(|i| {
+            let i = i as f32;
+            Vec3::new(
+                (i * 0.819_172_5).fract() * 4.0 - 2.0,
+                (i * 0.671_043_5).fract() * 4.0 - 2.0,
+                (i * 0.549_700_4).fract() * 4.0 - 2.0,
+            )
+        })
+    }
+
+    fn assert_surface<P: Pattern>(pattern: P) {
+        let name = P::NAME;
+        let unit = 0.0..=1.0;
+        for p in points() {
+            let s = pattern.surface(p);
+            assert!(
+                unit.contains(&s.base_color.min_element())
+                    && unit.contains(&s.base_color.max_element()),
+                "{name} base color {} out of range at {p}",
+                s.base_color
+            );
+            assert!(
+                unit.contains(&s.roughness),
+                "{name} roughness out of range at {p}"
+            );
+            assert!(
+                unit.contains(&s.metallic),
+                "{name} metallic out of range at {p}"
+            );
+            assert!(
+                unit.contains(&s.height.value),
+                "{name} height out of range at {p}"
+            );
+            assert!(
+                s.height.gradient.is_finite(),
+                "{name} gradient not finite at {p}"
+            );
+        }
+    }
+
+    /// Fraction of points where the height gradient matches finite differences
+    fn gradient_agreement<P: Pattern>(pattern: P) -> f32 {
+        let height = |p: Vec3| pattern.surface(p).height.value;
+        let matching = points()
+            .filter(|&p| {
+                let gradient = pattern.surface(p).height.gradient;
+                let fd = Vec3::new(
+                    height(p + Vec3::X * H) - height(p - Vec3::X * H),
+                    height(p + Vec3::Y * H) - height(p - Vec3::Y * H),
+                    height(p + Vec3::Z * H) - height(p - Vec3::Z * H),
+                ) / (2.0 * H);
+                (fd - gradient).length() <= 5e-2 * (1.0 + gradient.length())
+            })
+            .count();
+        matching as f32 / points().count() as f32
+    }
+
+    #[test]
+    fn surfaces_in_range() {
+        assert_surface(Marble);
+        assert_surface(Wood);
+        assert_surface(Rust);
+        assert_surface(Strata);
+    }
+
+    #[test]
+    fn names_match_types() {
+        fn short_name<P: Pattern>() -> &'static str {
+            core::any::type_name::<P>().rsplit("::").next().unwrap()
+        }
+        assert_eq!(short_name::<Marble>(), Marble::NAME);
+        assert_eq!(short_name::<Wood>(), Wood::NAME);
+        assert_eq!(short_name::<Rust>(), Rust::NAME);
+        assert_eq!(short_name::<Strata>(), Strata::NAME);
+    }
+
+    #[test]
+    fn height_gradients_match_finite_differences() {
+        // Vein centers, ring boundaries, rust edges and pit rims are creases,
+        // so only require agreement almost everywhere
+        for (name, agreement) in [
+            (Marble::NAME, gradient_agreement(Marble)),
+            (Wood::NAME, gradient_agreement(Wood)),
+            (Rust::NAME, gradient_agreement(Rust)),
+            (Strata::NAME, gradient_agreement(Strata)),
+        ] {
+            assert!(
+                agreement > 0.95,
+                "{name} gradient agrees at {agreement} of points"
+            );
+        }
+    }
+}
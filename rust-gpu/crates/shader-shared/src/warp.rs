@@ -0,0 +1,191 @@
+//! The time-varying deformation applied by `vertex_warp`.
+
+use crate::glam::{Mat3, Vec3};
+
+#[cfg(target_arch = "spirv")]
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+/// Warp parameters, laid out to match the `ExampleMaterial` uniform in the viewer.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(C)]
+pub struct Warp {
+    /// Per-axis weight of the deformation
+    pub axis: Vec3,
+    pub amplitude: f32,
+    /// Oscillation rate in radians per second
+    pub frequency: f32,
+    /// Oscillation offset in radians
+    pub phase: f32,
+}
+
+impl Default for Warp {
+    fn default() -> Self {
+        Warp {
+            axis: Vec3::ONE,
+            amplitude: 1.0,
+            frequency: 1.0,
+            phase: 0.0,
+        }
+    }
+}
+
+/// Fixed warp applied by `vertex_warp_pbr`, which only has `StandardMaterial` bindings to read from
+pub const PBR_WARP: Warp = Warp {
+    axis: Vec3::ONE,
+    amplitude: 0.3,
+    frequency: 1.5,
+    phase: 0.0,
+};
+
+impl Warp {
+    /// Per-axis `(sin, cos, sin * cos)` factors at `time`, scaled by `axis` and `amplitude`
+    fn factors(&self, time: f32) -> Vec3 {
+        let t = time * self.frequency + self.phase;
+        let (s, c) = (t.sin(), t.cos());
+        self.axis * self.amplitude * Vec3::new(s, c, s * c)
+    }
+
+    /// Deform a local-space position
+    pub fn position(&self, p: Vec3, time: f32) -> Vec3 {
+        let k = self.factors(time);
+        Vec3::new(
+            p.x + k.x * p.x * p.z,
+            p.y + k.y * p.y * p.z,
+            p.z + k.z * p.z,
+        )
+    }
+
+    /// Jacobian of [`Warp::position`] with respect to `p`
+    pub fn jacobian(&self, p: Vec3, time: f32) -> Mat3 {
+        let k = self.factors(time);
+        Mat3::from_cols(
+            Vec3::new(1.0 + k.x * p.z, 0.0, 0.0),
+            Vec3::new(0.0, 1.0 + k.y * p.z, 0.0),
+            Vec3::new(k.x * p.x, k.y * p.y, 1.0 + k.z),
+        )
+    }
+
+    /// Transform a local-space normal at `p` onto the deformed surface.
+    ///
+    /// Uses the inverse transpose of the Jacobian, so the result stays perpendicular
+    /// to tangents carried along by [`Warp::position`].
+    pub fn normal(&self, p: Vec3, n: Vec3, time: f32) -> Vec3 {
+        let jacobian = self.jacobian(p, time);
+        if jacobian.determinant().abs() < f32::EPSILON {
+            // Collapsed surface, no meaningful normal
+            return n;
+        }
+        (jacobian.inverse().transpose() * n).normalize_or_zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const H: f32 = 1e-3;
+
+    fn warps() -> [Warp; 3] {
+        [
+            Warp::default(),
+            Warp {
+                axis: Vec3::new(0.3, -0.7, 0.5),
+                amplitude: 0.8,
+                frequency: 2.5,
+                phase: 1.0,
+            },
+            Warp {
+                axis: Vec3::X,
+                amplitude: 0.4,
+                frequency: 3.0,
+                phase: -0.5,
+            },
+        ]
+    }
+
+    fn points() -> impl Iterator<Item = Vec3> {
+        (0..27).map(|i| {
+            Vec3::new(
+                (i % 3) as f32 - 1.0,
+                (i / 3 % 3) as f32 - 1.0,
+                (i / 9) as f32 - 1.0,
+            ) * 0.5
+                + Vec3::new(0.05, -0.03, 0.02)
+        })
+    }
+
+    /// Central-difference derivative of the warp along `dir`
+    fn directional_derivative(warp: &Warp, p: Vec3, dir: Vec3, time: f32) -> Vec3 {
+        (warp.position(p + dir * H, time) - warp.position(p - dir * H, time)) / (2.0 * H)
+    }
+
+    #[test]
+    fn jacobian_matches_finite_differences() {
+        for warp in warps() {
+            for time in [0.0, 0.4, 2.1] {
+                for p in points() {
+                    let jacobian = warp.jacobian(p, time);
+                    for (i, dir) in [Vec3::X, Vec3::Y, Vec3::Z].into_iter().enumerate() {
+                        let expected = directional_derivative(&warp, p, dir, time);
+                        let actual = jacobian.col(i);
+                        assert!(
+                            (expected - actual).length() < 1e-2,
+                            "{warp:?} at {p} t={time}: column {i} {actual} != {expected}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn normal_is_perpendicular_to_warped_tangents() {
+        let normals = [
+            Vec3::X,
+            -Vec3::Y,
+            Vec3::Z,
+            Vec3::new(1.0, 2.0, -0.5).normalize(),
+        ];
+
+        for warp in warps() {
+            for time in [0.0, 0.4, 2.1] {
+                for p in points() {
+                    for n in normals {
+                        let tangent = n.any_orthonormal_vector();
+                        let bitangent = n.cross(tangent);
+
+                        let warped_tangent = directional_derivative(&warp, p, tangent, time);
+                        let warped_bitangent = directional_derivative(&warp, p, bitangent, time);
+
+                        // Finite-difference normal, oriented like the input
+                        let mut expected = warped_tangent.cross(warped_bitangent).normalize();
+                        if warp.jacobian(p, time).determinant() < 0.0 {
+                            expected = -expected;
+                        }
+
+                        let actual = warp.normal(p, n, time);
+                        assert!(
+                            actual.dot(expected) > 0.999,
+                            "{warp:?} at {p} t={time}: normal {actual} != {expected}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn zero_amplitude_is_identity() {
+        let warp = Warp {
+            amplitude: 0.0,
+            ..Default::default()
+        };
+
+        for p in points() {
+            assert_eq!(warp.position(p, 1.3), p);
+            let n = p.normalize();
+            assert!((warp.normal(p, n, 1.3) - n).length() < 1e-6);
+        }
+    }
+}
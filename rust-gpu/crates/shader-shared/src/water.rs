@@ -0,0 +1,171 @@
+//! Gerstner wave sums, shared by `water::vertex_water` and CPU-side buoyancy.
+
+use crate::glam::{Vec2, Vec3};
+
+#[cfg(target_arch = "spirv")]
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+/// Size of the wave array in the water material uniform
+pub const MAX_WAVES: usize = 8;
+
+/// Gravitational acceleration used for the deep water dispersion relation
+pub const GRAVITY: f32 = 9.8;
+
+/// A single Gerstner wave.
+///
+/// Travels along `direction` on the XZ plane at the deep water phase speed for its `wavelength`.
+/// `steepness` ranges from 0.0 (flat) to 1.0 (looping crests), and should sum to at most 1.0 across all waves.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[repr(C)]
+pub struct GerstnerWave {
+    pub direction: Vec2,
+    pub steepness: f32,
+    pub wavelength: f32,
+}
+
+/// Displaced surface point, with the analytic derivatives of the displacement
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct GerstnerSample {
+    pub position: Vec3,
+    /// Derivative of `position` along X
+    pub tangent: Vec3,
+    /// Derivative of `position` along Z
+    pub binormal: Vec3,
+    /// Sum of each wave's steepness-weighted height, peaking at 1.0 where every crest lines up
+    pub crest: f32,
+}
+
+impl GerstnerSample {
+    pub fn normal(&self) -> Vec3 {
+        self.binormal.cross(self.tangent).normalize_or_zero()
+    }
+}
+
+/// Displace a point on the undisturbed XZ plane by the first `count` waves at `time`
+pub fn gerstner(
+    waves: &[GerstnerWave; MAX_WAVES],
+    count: u32,
+    position: Vec3,
+    time: f32,
+) -> GerstnerSample {
+    let mut sample = GerstnerSample {
+        position,
+        tangent: Vec3::X,
+        binormal: Vec3::Z,
+        crest: 0.0,
+    };
+
+    let count = (count as usize).min(MAX_WAVES);
+    let mut i = 0;
+    while i < count {
+        let wave = waves[i];
+        i += 1;
+
+        if wave.wavelength <= 0.0 {
+            continue;
+        }
+
+        let k = core::f32::consts::TAU / wave.wavelength;
+        let speed = (GRAVITY / k).sqrt();
+        let d = wave.direction.normalize_or_zero();
+        let f = k * (d.dot(Vec2::new(position.x, position.z)) - speed * time);
+        let s = wave.steepness;
+        let a = s / k;
+        let (sin, cos) = (f.sin(), f.cos());
+
+        sample.position += Vec3::new(d.x * a * cos, a * sin, d.y * a * cos);
+        sample.tangent += Vec3::new(-d.x * d.x * s * sin, d.x * s * cos, -d.x * d.y * s * sin);
+        sample.binormal += Vec3::new(-d.x * d.y * s * sin, d.y * s * cos, -d.y * d.y * s * sin);
+        sample.crest += s * sin;
+    }
+
+    sample
+}
+
+/// Height of the displaced surface above the point `xz`.
+///
+/// Gerstner waves also move points horizontally,
+/// so this searches for the undisplaced point that lands on `xz` before sampling its height.
+pub fn height(waves: &[GerstnerWave; MAX_WAVES], count: u32, xz: Vec2, time: f32) -> f32 {
+    let target = Vec3::new(xz.x, 0.0, xz.y);
+
+    let mut source = target;
+    let mut i = 0;
+    while i < 4 {
+        let displaced = gerstner(waves, count, source, time).position;
+        source.x -= displaced.x - target.x;
+        source.z -= displaced.z - target.z;
+        i += 1;
+    }
+
+    gerstner(waves, count, source, time).position.y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn waves() -> [GerstnerWave; MAX_WAVES] {
+        let mut waves = [GerstnerWave::default(); MAX_WAVES];
+        waves[0] = GerstnerWave {
+            direction: Vec2::new(1.0, 0.3),
+            steepness: 0.3,
+            wavelength: 12.0,
+        };
+        waves[1] = GerstnerWave {
+            direction: Vec2::new(-0.4, 1.0),
+            steepness: 0.2,
+            wavelength: 5.0,
+        };
+        waves[2] = GerstnerWave {
+            direction: Vec2::new(0.7, -0.7),
+            steepness: 0.15,
+            wavelength: 2.5,
+        };
+        waves
+    }
+
+    fn points() -> impl Iterator<Item = Vec3> {
+        (0..25).map(|i| Vec3::new((i % 5) as f32 * 1.7 - 3.0, 0.0, (i / 5) as f32 * 2.3 - 4.0))
+    }
+
+    #[test]
+    fn derivatives_match_finite_differences() {
+        const H: f32 = 1e-3;
+        let waves = waves();
+
+        for time in [0.0, 1.5, 7.25] {
+            for p in points() {
+                let sample = gerstner(&waves, 3, p, time);
+                let dx = (gerstner(&waves, 3, p + Vec3::X * H, time).position
+                    - gerstner(&waves, 3, p - Vec3::X * H, time).position)
+                    / (2.0 * H);
+                let dz = (gerstner(&waves, 3, p + Vec3::Z * H, time).position
+                    - gerstner(&waves, 3, p - Vec3::Z * H, time).position)
+                    / (2.0 * H);
+
+                assert!((sample.tangent - dx).length() < 1e-2, "{p} t={time}");
+                assert!((sample.binormal - dz).length() < 1e-2, "{p} t={time}");
+                assert!(sample.normal().y > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn height_matches_displaced_surface() {
+        let waves = waves();
+
+        for time in [0.0, 1.5, 7.25] {
+            for p in points() {
+                let displaced = gerstner(&waves, 3, p, time).position;
+                let h = height(&waves, 3, Vec2::new(displaced.x, displaced.z), time);
+                assert!(
+                    (h - displaced.y).abs() < 1e-3,
+                    "{p} t={time}: {h} != {}",
+                    displaced.y
+                );
+            }
+        }
+    }
+}
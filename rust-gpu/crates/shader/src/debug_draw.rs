@@ -0,0 +1,87 @@
+//! Immediate-mode debug shapes, drawn as analytically antialiased SDFs.
+//!
+//! Shapes are read from a storage buffer filled each frame by the viewer's `debug_draw` module,
+//! with the layout and SDFs shared through [`shader_shared::debug_draw`].
+//! Every shape owns six vertices of a single draw call,
+//! and [`vertex_debug_shapes`] expands each into an oriented bounding quad using its vertex index.
+
+use bevy_pbr_rust::prelude::View;
+use spirv_std::{
+    arch::{ddx, ddy},
+    glam::{Vec2, Vec3, Vec4},
+    spirv,
+};
+
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+use shader_shared::debug_draw::{DebugShape, KIND_NONE};
+
+use crate::coverage;
+
+/// Extra margin around each shape's quad to leave room for antialiasing, in pixels
+const AA_MARGIN: f32 = 2.0;
+
+/// Unit quad corners for the six vertices of each shape
+const CORNERS: [Vec2; 6] = [
+    Vec2::new(-1.0, -1.0),
+    Vec2::new(1.0, -1.0),
+    Vec2::new(1.0, 1.0),
+    Vec2::new(-1.0, -1.0),
+    Vec2::new(1.0, 1.0),
+    Vec2::new(-1.0, 1.0),
+];
+
+#[spirv(vertex)]
+pub fn vertex_debug_shapes(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(storage_buffer, descriptor_set = 1, binding = 0)] shapes: &[DebugShape],
+    #[spirv(vertex_index)] in_vertex_index: u32,
+    _in_position: Vec3,
+
+    #[spirv(position)] out_clip_position: &mut Vec4,
+    out_world_position: &mut Vec2,
+    #[spirv(flat)] out_shape_index: &mut u32,
+) {
+    let shape_index = in_vertex_index / 6;
+    *out_shape_index = shape_index;
+
+    if shape_index as usize >= shapes.len() || shapes[shape_index as usize].kind == KIND_NONE {
+        // Degenerate position, so unused slots rasterize nothing
+        *out_clip_position = Vec4::ZERO;
+        *out_world_position = Vec2::ZERO;
+        return;
+    }
+
+    let shape = shapes[shape_index as usize];
+
+    // World units per pixel, assuming an orthographic 2D projection
+    let pixel_size = 2.0 / (view.view_proj.x_axis.x * view.viewport.z);
+
+    let (center, axis, extent) = shape.bounds();
+    let extent = extent + pixel_size * AA_MARGIN;
+    let corner = CORNERS[(in_vertex_index % 6) as usize] * extent;
+    let world = center + axis * corner.x + axis.perp() * corner.y;
+
+    *out_clip_position = view.view_proj * world.extend(0.0).extend(1.0);
+    *out_world_position = world;
+}
+
+#[spirv(fragment)]
+pub fn fragment_debug_shapes(
+    #[spirv(storage_buffer, descriptor_set = 1, binding = 0)] shapes: &[DebugShape],
+    in_world_position: Vec2,
+    #[spirv(flat)] in_shape_index: u32,
+    out_color: &mut Vec4,
+) {
+    let shape = shapes[in_shape_index as usize];
+
+    let dist = shape.distance(in_world_position);
+
+    // One pixel's worth of world distance
+    let aa = ddx(in_world_position.x).abs() + ddy(in_world_position.y).abs();
+
+    let alpha = coverage(dist, aa.max(f32::EPSILON)) * shape.color.w;
+
+    *out_color = shape.color.truncate().extend(alpha);
+}
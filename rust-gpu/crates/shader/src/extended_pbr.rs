@@ -0,0 +1,335 @@
+//! `StandardMaterial` shading with a user hook between input preparation and lighting.
+//!
+//! [`fragment_extended_pbr`] samples the usual `StandardMaterial` bindings into `bevy-pbr-rust`'s [`PbrInput`],
+//! hands it to a [`PbrExtension`] selected by permutation along with the extension's own uniform,
+//! then lights the result with `bevy-pbr-rust`'s [`pbr`], the same lighting `StandardMaterial` gets.
+//! Output stays in linear HDR, leaving tonemapping to the camera.
+
+use bevy_pbr_rust::prelude::{
+    apply_normal_mapping, calculate_view, pbr, prepare_world_normal, ClusterLightIndexListsStorage,
+    ClusterOffsetsAndCountsStorage, Lights, Mesh, PbrInput, PointLightsStorage, StandardMaterial,
+    TextureDepth2dArray, TextureDepthCubeArray, View,
+};
+use permutate_macro::permutate;
+use shader_shared::{
+    fog::Fog,
+    noise::{simplex_3d, Fractal},
+};
+use spirv_std::{
+    arch::kill,
+    glam::{Mat3, Vec2, Vec3, Vec4, Vec4Swizzles},
+    image::Image2d,
+    spirv, Sampler,
+};
+
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+use crate::apply_fog;
+
+pub const STANDARD_MATERIAL_FLAGS_BASE_COLOR_TEXTURE: u32 = 1 << 0;
+pub const STANDARD_MATERIAL_FLAGS_EMISSIVE_TEXTURE: u32 = 1 << 1;
+pub const STANDARD_MATERIAL_FLAGS_METALLIC_ROUGHNESS_TEXTURE: u32 = 1 << 2;
+pub const STANDARD_MATERIAL_FLAGS_OCCLUSION_TEXTURE: u32 = 1 << 3;
+pub const STANDARD_MATERIAL_FLAGS_DOUBLE_SIDED: u32 = 1 << 4;
+pub const STANDARD_MATERIAL_FLAGS_UNLIT: u32 = 1 << 5;
+pub const STANDARD_MATERIAL_FLAGS_FOG_ENABLED: u32 = 1 << 8;
+pub const STANDARD_MATERIAL_FLAGS_ALPHA_MODE_RESERVED_BITS: u32 = 0b111 << 29;
+pub const STANDARD_MATERIAL_FLAGS_ALPHA_MODE_MASK: u32 = 1 << 29;
+
+/// Samples of the `StandardMaterial` textures at the current fragment
+#[derive(Debug, Copy, Clone)]
+pub struct TextureSamples {
+    pub base_color: Vec4,
+    pub emissive: Vec4,
+    pub metallic_roughness: Vec4,
+    pub occlusion: Vec4,
+}
+
+/// Combine `material` with its texture `samples` at a fragment into a [`PbrInput`],
+/// the way Bevy's `StandardMaterial` fragment does before lighting.
+///
+/// The shading normal `n` is left as the world normal, for the caller to replace when normal mapping,
+/// and mesh flags are left for the caller to fill in.
+pub fn prepare_pbr_input(
+    material: &StandardMaterial,
+    samples: &TextureSamples,
+    view: &View,
+    frag_coord: Vec4,
+    world_position: Vec4,
+    world_normal: Vec3,
+    is_front: bool,
+) -> PbrInput {
+    let flags = material.flags;
+
+    let mut input = PbrInput::default();
+    input.material = *material;
+
+    if flags & STANDARD_MATERIAL_FLAGS_BASE_COLOR_TEXTURE != 0 {
+        input.material.base_color *= samples.base_color;
+    }
+
+    if flags & STANDARD_MATERIAL_FLAGS_EMISSIVE_TEXTURE != 0 {
+        input.material.emissive =
+            (input.material.emissive.xyz() * samples.emissive.xyz()).extend(1.0);
+    }
+
+    // glTF packs roughness in green and metallic in blue
+    if flags & STANDARD_MATERIAL_FLAGS_METALLIC_ROUGHNESS_TEXTURE != 0 {
+        input.material.perceptual_roughness *= samples.metallic_roughness.y;
+        input.material.metallic *= samples.metallic_roughness.z;
+    }
+
+    if flags & STANDARD_MATERIAL_FLAGS_OCCLUSION_TEXTURE != 0 {
+        input.occlusion = samples.occlusion.x;
+    }
+
+    input.frag_coord = frag_coord;
+    input.world_position = world_position;
+    input.world_normal = prepare_world_normal(
+        world_normal,
+        flags & STANDARD_MATERIAL_FLAGS_DOUBLE_SIDED != 0,
+        is_front,
+    );
+    input.is_orthographic = view.projection.w_axis.w == 1.0;
+    input.n = input.world_normal;
+    input.v = calculate_view(world_position, input.is_orthographic, view);
+
+    input
+}
+
+/// Hook run on the prepared [`PbrInput`] before lighting
+pub trait PbrExtension {
+    /// Uniform read from binding 11 of the material bind group
+    type Params;
+
+    fn extend(params: &Self::Params, input: &mut PbrInput);
+}
+
+/// Parameters for [`NoiseTint`].
+///
+/// Mirrors `NoiseTint` in the viewer's `extended_pbr` module.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct NoiseTintParams {
+    pub color: Vec4,
+    /// World-space frequency of the noise
+    pub scale: f32,
+    /// Blend factor towards `color` where the noise peaks
+    pub strength: f32,
+}
+
+/// Blends base color towards a tint by fractal noise over world position
+pub enum NoiseTint {}
+
+impl PbrExtension for NoiseTint {
+    type Params = NoiseTintParams;
+
+    fn extend(params: &NoiseTintParams, input: &mut PbrInput) {
+        let noise =
+            Fractal::default().fbm_3d(input.world_position.xyz() * params.scale, simplex_3d);
+        let t = (noise.value * 0.5 + 0.5) * params.strength;
+        let base_color = input.material.base_color;
+        input.material.base_color = base_color
+            .xyz()
+            .lerp(params.color.xyz(), t)
+            .extend(base_color.w);
+    }
+}
+
+/// Parameters for [`HeightTint`].
+///
+/// Mirrors `HeightTint` in the viewer's `extended_pbr` module.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct HeightTintParams {
+    pub low_color: Vec4,
+    pub high_color: Vec4,
+    /// World-space height at which `low_color` is fully applied
+    pub min_height: f32,
+    /// World-space height at which `high_color` is fully applied
+    pub max_height: f32,
+}
+
+/// Multiplies base color by a gradient over world-space height, and roughens low areas
+pub enum HeightTint {}
+
+impl PbrExtension for HeightTint {
+    type Params = HeightTintParams;
+
+    fn extend(params: &HeightTintParams, input: &mut PbrInput) {
+        let t = ((input.world_position.y - params.min_height)
+            / (params.max_height - params.min_height))
+            .clamp(0.0, 1.0);
+        let tint = params.low_color.lerp(params.high_color, t);
+        input.material.base_color *= tint;
+        input.material.perceptual_roughness = input.material.perceptual_roughness.max(1.0 - t);
+    }
+}
+
+#[permutate(
+    parameters = {
+        normal_map: some | none
+    },
+    constants = {},
+    types = {},
+    permutations = [
+        file("../../entry_points.json", "extended_pbr"),
+        env("RUST_GPU_EXTENDED_PBR_VERTEX_PERMUTATIONS", "extended_pbr")
+    ]
+)]
+#[spirv(vertex)]
+pub fn vertex_extended_pbr(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
+
+    in_position: Vec3,
+    in_normal: Vec3,
+    in_uv: Vec2,
+    #[permutate(normal_map = some)] in_tangent: Vec4,
+
+    #[spirv(position)] out_clip_position: &mut Vec4,
+    out_world_position: &mut Vec4,
+    out_world_normal: &mut Vec3,
+    out_uv: &mut Vec2,
+    #[permutate(normal_map = some)] out_world_tangent: &mut Vec4,
+) {
+    let position_world = mesh.model * in_position.extend(1.0);
+
+    *out_clip_position = view.view_proj * position_world;
+    *out_world_position = position_world;
+    *out_world_normal = Mat3::from_mat4(mesh.inverse_transpose_model) * in_normal;
+    *out_uv = in_uv;
+
+    #[permutate(normal_map = some)]
+    *out_world_tangent = (Mat3::from_mat4(mesh.model) * in_tangent.xyz())
+        .normalize()
+        .extend(in_tangent.w);
+}
+
+#[permutate(
+    parameters = {
+        normal_map: some | none
+    },
+    constants = {},
+    types = {
+        Extension
+    },
+    permutations = [
+        file("../../entry_points.json", "extended_pbr"),
+        env("RUST_GPU_EXTENDED_PBR_FRAGMENT_PERMUTATIONS", "extended_pbr")
+    ]
+)]
+#[spirv(fragment)]
+pub fn fragment_extended_pbr(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 0, binding = 1)] lights: &Lights,
+    #[spirv(descriptor_set = 0, binding = 2)] point_shadow_textures: &TextureDepthCubeArray,
+    #[spirv(descriptor_set = 0, binding = 3)] point_shadow_textures_sampler: &Sampler,
+    #[spirv(descriptor_set = 0, binding = 4)] directional_shadow_textures: &TextureDepth2dArray,
+    #[spirv(descriptor_set = 0, binding = 5)] directional_shadow_textures_sampler: &Sampler,
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 6)] point_lights: &PointLightsStorage,
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 7)]
+    cluster_light_index_lists: &ClusterLightIndexListsStorage,
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 8)]
+    cluster_offsets_and_counts: &ClusterOffsetsAndCountsStorage,
+    #[spirv(uniform, descriptor_set = 0, binding = 10)] fog: &Fog,
+
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] material: &StandardMaterial,
+    #[spirv(descriptor_set = 1, binding = 1)] base_color_texture: &Image2d,
+    #[spirv(descriptor_set = 1, binding = 2)] base_color_sampler: &Sampler,
+    #[spirv(descriptor_set = 1, binding = 3)] emissive_texture: &Image2d,
+    #[spirv(descriptor_set = 1, binding = 4)] emissive_sampler: &Sampler,
+    #[spirv(descriptor_set = 1, binding = 5)] metallic_roughness_texture: &Image2d,
+    #[spirv(descriptor_set = 1, binding = 6)] metallic_roughness_sampler: &Sampler,
+    #[spirv(descriptor_set = 1, binding = 7)] occlusion_texture: &Image2d,
+    #[spirv(descriptor_set = 1, binding = 8)] occlusion_sampler: &Sampler,
+    #[permutate(normal_map = some)]
+    #[spirv(descriptor_set = 1, binding = 9)]
+    normal_map_texture: &Image2d,
+    #[permutate(normal_map = some)]
+    #[spirv(descriptor_set = 1, binding = 10)]
+    normal_map_sampler: &Sampler,
+    #[spirv(uniform, descriptor_set = 1, binding = 11)]
+    extension: &<Extension as PbrExtension>::Params,
+
+    #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
+
+    #[spirv(frag_coord)] in_frag_coord: Vec4,
+    #[spirv(front_facing)] in_is_front: bool,
+    in_world_position: Vec4,
+    in_world_normal: Vec3,
+    in_uv: Vec2,
+    #[permutate(normal_map = some)] in_world_tangent: Vec4,
+    out_color: &mut Vec4,
+) {
+    let samples = TextureSamples {
+        base_color: base_color_texture.sample(*base_color_sampler, in_uv),
+        emissive: emissive_texture.sample(*emissive_sampler, in_uv),
+        metallic_roughness: metallic_roughness_texture.sample(*metallic_roughness_sampler, in_uv),
+        occlusion: occlusion_texture.sample(*occlusion_sampler, in_uv),
+    };
+
+    let mut input = prepare_pbr_input(
+        material,
+        &samples,
+        view,
+        in_frag_coord,
+        in_world_position,
+        in_world_normal,
+        in_is_front,
+    );
+
+    #[permutate(normal_map = some)]
+    let normal = apply_normal_mapping(
+        material.flags,
+        input.world_normal,
+        in_world_tangent,
+        in_uv,
+        normal_map_texture,
+        normal_map_sampler,
+    );
+    #[permutate(normal_map = none)]
+    let normal = input.world_normal;
+    input.n = normal;
+    input.flags = mesh.flags;
+
+    <Extension as PbrExtension>::extend(extension, &mut input);
+
+    if material.flags & STANDARD_MATERIAL_FLAGS_ALPHA_MODE_RESERVED_BITS
+        == STANDARD_MATERIAL_FLAGS_ALPHA_MODE_MASK
+        && input.material.base_color.w < material.alpha_cutoff
+    {
+        kill();
+    }
+
+    let color = if material.flags & STANDARD_MATERIAL_FLAGS_UNLIT == 0 {
+        pbr(
+            view,
+            lights,
+            point_lights,
+            cluster_light_index_lists,
+            cluster_offsets_and_counts,
+            directional_shadow_textures,
+            directional_shadow_textures_sampler,
+            point_shadow_textures,
+            point_shadow_textures_sampler,
+            &input,
+        )
+    } else {
+        input.material.base_color
+    };
+
+    *out_color = if material.flags & STANDARD_MATERIAL_FLAGS_FOG_ENABLED != 0 {
+        let ray_delta = input.world_position.xyz() - view.world_position;
+        apply_fog(
+            fog,
+            lights,
+            color,
+            ray_delta.normalize(),
+            ray_delta.length(),
+        )
+    } else {
+        color
+    };
+}
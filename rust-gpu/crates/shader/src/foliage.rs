@@ -0,0 +1,156 @@
+//! Wind-animated foliage, drawn as many blades in a single call.
+//!
+//! The mesh holds a single blade, drawn once per instance,
+//! and [`vertex_foliage`] pulls each blade's placement from the instance storage buffer by instance index.
+
+use core::f32::consts::FRAC_1_PI;
+
+use bevy_pbr_rust::prelude::{Globals, Lights, Mesh, View};
+use shader_shared::fog::Fog;
+use spirv_std::{
+    glam::{Mat3, Vec2, Vec3, Vec4, Vec4Swizzles},
+    spirv,
+};
+
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+use crate::{apply_fog, noise::value_2d};
+
+/// Wind and color parameters.
+///
+/// Mirrors `FoliageMaterial` in the viewer's `foliage` example.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct FoliageMaterial {
+    pub base_color: Vec4,
+    pub tip_color: Vec4,
+    /// World-space XZ direction the wind blows towards
+    pub wind_direction: Vec2,
+    /// Tip displacement of a fully gusted blade, relative to its height
+    pub wind_strength: f32,
+    /// Speed at which gusts travel across the field, in world units per second
+    pub wind_speed: f32,
+    /// Frequency of the gust noise over the field
+    pub gust_scale: f32,
+}
+
+/// Placement of a single blade.
+///
+/// Mirrors `FoliageInstance` in the viewer's `foliage` example.
+#[derive(Debug, Default, Copy, Clone)]
+#[repr(C)]
+pub struct FoliageInstance {
+    /// Root position, relative to the foliage entity
+    pub position: Vec3,
+    pub scale: f32,
+    /// Rotation around Y in radians
+    pub rotation: f32,
+    /// Offset for the blade's flutter, in radians
+    pub phase: f32,
+    /// Multiplier on wind displacement, so blades can differ in stiffness
+    pub bend: f32,
+    pub _padding: f32,
+}
+
+#[spirv(vertex)]
+pub fn vertex_foliage(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 0, binding = 9)] globals: &Globals,
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] material: &FoliageMaterial,
+    #[spirv(storage_buffer, descriptor_set = 1, binding = 1)] instances: &[FoliageInstance],
+    #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
+    #[spirv(instance_index)] in_instance_index: u32,
+
+    in_position: Vec3,
+    in_normal: Vec3,
+    in_uv: Vec2,
+
+    #[spirv(position)] out_clip_position: &mut Vec4,
+    out_world_normal: &mut Vec3,
+    out_uv: &mut Vec2,
+    out_world_position: &mut Vec3,
+) {
+    let instance_index = in_instance_index as usize;
+    if instance_index >= instances.len() {
+        // Degenerate position for instances past the end of the instance buffer
+        *out_clip_position = Vec4::ZERO;
+        *out_world_normal = Vec3::Y;
+        *out_uv = in_uv;
+        *out_world_position = Vec3::ZERO;
+        return;
+    }
+
+    let instance = instances[instance_index];
+    let rotation = Mat3::from_rotation_y(instance.rotation);
+    let time = globals.time;
+
+    // Bend more towards the tip, with the root pinned in place
+    let weight = in_position.y * in_position.y;
+
+    // Large-scale gusts rolling across the field, plus per-blade flutter
+    let wind_direction = material.wind_direction.normalize_or_zero();
+    let gust_position = (Vec2::new(instance.position.x, instance.position.z)
+        - wind_direction * time * material.wind_speed)
+        * material.gust_scale;
+    let gust = value_2d(gust_position).value * 0.5 + 0.5;
+    let flutter = (time * 3.0 + instance.phase).sin() * 0.15;
+    let sway = (gust + flutter) * material.wind_strength * instance.bend * weight;
+
+    let mut local = rotation * in_position;
+    local.x += wind_direction.x * sway;
+    local.z += wind_direction.y * sway;
+    // Shorten the blade as it bends so it doesn't appear to stretch
+    local.y -= sway * sway * 0.5;
+
+    let position = instance.position + local * instance.scale;
+    let position_world = mesh.model * position.extend(1.0);
+
+    *out_clip_position = view.view_proj * position_world;
+    *out_world_normal =
+        (Mat3::from_mat4(mesh.inverse_transpose_model) * (rotation * in_normal)).normalize();
+    *out_uv = in_uv;
+    *out_world_position = position_world.xyz();
+}
+
+#[spirv(fragment)]
+pub fn fragment_foliage(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 0, binding = 1)] lights: &Lights,
+    #[spirv(uniform, descriptor_set = 0, binding = 10)] fog: &Fog,
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] material: &FoliageMaterial,
+    #[spirv(front_facing)] in_is_front: bool,
+    in_world_normal: Vec3,
+    in_uv: Vec2,
+    in_world_position: Vec3,
+    out_color: &mut Vec4,
+) {
+    let normal = if in_is_front {
+        in_world_normal
+    } else {
+        -in_world_normal
+    };
+
+    // Wrapped diffuse from the scene's directional lights, since thin blades pass light through,
+    // with a darkened root standing in for occlusion
+    let normal = normal.normalize();
+    let mut light = lights.ambient_color.xyz();
+    let mut i = 0;
+    while i < lights.n_directional_lights {
+        let directional = &lights.directional_lights[i as usize];
+        let wrap = 0.5 + 0.5 * normal.dot(directional.direction_to_light);
+        light += directional.color.xyz() * wrap * FRAC_1_PI;
+        i += 1;
+    }
+    let occlusion = 0.4 + 0.6 * in_uv.y;
+
+    let color = material.base_color.lerp(material.tip_color, in_uv.y);
+    let ray_delta = in_world_position - view.world_position;
+    *out_color = apply_fog(
+        fog,
+        lights,
+        (color.truncate() * light * occlusion).extend(color.w),
+        ray_delta.normalize(),
+        ray_delta.length(),
+    );
+}
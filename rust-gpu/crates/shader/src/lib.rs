@@ -2,10 +2,22 @@
 #![feature(asm_experimental_arch)]
 
 pub use bevy_pbr_rust;
+pub use shader_shared::{color, fog, noise};
+
+pub mod debug_draw;
+pub mod extended_pbr;
+pub mod foliage;
+pub mod msdf_text;
+pub mod procedural;
+pub mod sdf_sprite;
+pub mod shadertoy;
+pub mod sky;
+pub mod toon;
+pub mod water;
 
 use rust_gpu_bridge::glam;
 
-use bevy_pbr_rust::prelude::{Globals, Mesh, TextureDepth2d, View};
+use bevy_pbr_rust::prelude::{Globals, Lights, Mesh, TextureDepth2d, View};
 use permutate_macro::permutate;
 use rust_gpu_bridge::{Mix, Mod, SmoothStep};
 use rust_gpu_sdf::{
@@ -27,8 +39,12 @@ use rust_gpu_sdf::{
         },
     },
 };
+use shader_shared::{
+    fog::{Fog, FOG_MODE_OFF},
+    warp::{Warp, PBR_WARP},
+};
 use spirv_std::{
-    arch::{ddx, ddy},
+    arch::{ddx, ddy, kill},
     glam::{Mat3, Vec2, Vec3, Vec4, Vec4Swizzles},
     spirv,
 };
@@ -41,34 +57,102 @@ pub fn vertex_warp(
     #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
     #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
     #[spirv(uniform, descriptor_set = 0, binding = 9)] globals: &Globals,
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] warp: &Warp,
 
     in_position: Vec3,
     in_normal: Vec3,
 
     #[spirv(position)] out_clip_position: &mut Vec4,
     out_world_normal: &mut Vec3,
+    out_world_position: &mut Vec3,
 ) {
-    let mut position_local = in_position.extend(1.0);
-
-    position_local.x += position_local.x * position_local.z * globals.time.sin();
-    position_local.y += position_local.y * position_local.z * globals.time.cos();
-    position_local.z += position_local.z * globals.time.sin() * globals.time.cos();
+    let position_local = warp.position(in_position, globals.time).extend(1.0);
+    let normal_local = warp.normal(in_position, in_normal, globals.time);
 
     let position_world = mesh.model * position_local;
     let position_clip = view.view_proj * position_world;
 
     *out_clip_position = position_clip;
-    *out_world_normal = in_normal;
+    *out_world_normal = (Mat3::from_mat4(mesh.inverse_transpose_model) * normal_local).normalize();
+    *out_world_position = position_world.xyz();
+}
+
+/// [`vertex_warp`] for `StandardMaterial`, deforming by [`PBR_WARP`]
+/// and writing the world position, normal and UV inputs of the PBR fragment
+#[spirv(vertex)]
+pub fn vertex_warp_pbr(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
+    #[spirv(uniform, descriptor_set = 0, binding = 9)] globals: &Globals,
+
+    in_position: Vec3,
+    in_normal: Vec3,
+    in_uv: Vec2,
+
+    #[spirv(position)] out_clip_position: &mut Vec4,
+    out_world_position: &mut Vec4,
+    out_world_normal: &mut Vec3,
+    out_uv: &mut Vec2,
+) {
+    let position_local = PBR_WARP.position(in_position, globals.time).extend(1.0);
+    let normal_local = PBR_WARP.normal(in_position, in_normal, globals.time);
+
+    let position_world = mesh.model * position_local;
+
+    *out_clip_position = view.view_proj * position_world;
+    *out_world_position = position_world;
+    *out_world_normal = (Mat3::from_mat4(mesh.inverse_transpose_model) * normal_local).normalize();
+    *out_uv = in_uv;
+}
+
+/// [`vertex_warp`] for the depth and normal prepass,
+/// whose view bind group holds globals at binding 1
+#[spirv(vertex)]
+pub fn vertex_warp_prepass(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
+    #[spirv(uniform, descriptor_set = 0, binding = 1)] globals: &Globals,
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] warp: &Warp,
+
+    in_position: Vec3,
+    in_normal: Vec3,
+
+    #[spirv(position)] out_clip_position: &mut Vec4,
+    out_world_normal: &mut Vec3,
+) {
+    let position_local = warp.position(in_position, globals.time).extend(1.0);
+    let normal_local = warp.normal(in_position, in_normal, globals.time);
+
+    *out_clip_position = view.view_proj * (mesh.model * position_local);
+    *out_world_normal = (Mat3::from_mat4(mesh.inverse_transpose_model) * normal_local).normalize();
 }
 
 #[spirv(fragment)]
 #[allow(unused_variables)]
 pub fn fragment_normal(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 0, binding = 1)] lights: &Lights,
+    #[spirv(uniform, descriptor_set = 0, binding = 10)] fog: &Fog,
     #[spirv(frag_coord)] in_clip_position: Vec4,
     in_world_normal: Vec3,
+    in_world_position: Vec3,
     out_color: &mut Vec4,
 ) {
-    *out_color = in_world_normal.extend(1.0);
+    let ray_delta = in_world_position - view.world_position;
+    *out_color = apply_fog(
+        fog,
+        lights,
+        in_world_normal.extend(1.0),
+        ray_delta.normalize(),
+        ray_delta.length(),
+    );
+}
+
+/// Normal prepass output for vertex entry points writing a world normal, such as [`vertex_warp_prepass`]
+#[spirv(fragment)]
+pub fn fragment_normal_prepass(in_world_normal: Vec3, out_normal: &mut Vec4) {
+    // Bevy's normal prepass stores normals remapped into `[0, 1]`
+    *out_normal = (in_world_normal.normalize() * 0.5 + 0.5).extend(1.0);
 }
 
 #[spirv(vertex)]
@@ -83,6 +167,55 @@ pub fn vertex_sdf_2d(
     *out_clip_position = position_local;
 }
 
+#[spirv(vertex)]
+pub fn vertex_uv_2d(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
+
+    in_position: Vec3,
+    _in_normal: Vec3,
+    in_uv: Vec2,
+
+    #[spirv(position)] out_clip_position: &mut Vec4,
+    out_uv: &mut Vec2,
+) {
+    let position_world = mesh.model * in_position.extend(1.0);
+    *out_clip_position = view.view_proj * position_world;
+    *out_uv = in_uv;
+}
+
+/// Antialiased coverage for a signed distance `dist` (negative inside),
+/// using the same `smooth_step` boundary as `fragment_sdf_2d` and `fragment_sdf_3d`.
+///
+/// `width` is the size of the transition band in the same units as `dist`.
+pub fn coverage(dist: f32, width: f32) -> f32 {
+    dist.smooth_step(width * 0.5, -width * 0.5)
+}
+
+/// Bevy's distance fog over `color`, seen `distance` away along unit `view_direction`,
+/// with the glow of every directional light in `lights`
+pub fn apply_fog(
+    fog: &Fog,
+    lights: &Lights,
+    color: Vec4,
+    view_direction: Vec3,
+    distance: f32,
+) -> Vec4 {
+    if fog.mode == FOG_MODE_OFF {
+        return color;
+    }
+
+    let mut scattering = Vec3::ZERO;
+    let mut i = 0;
+    while i < lights.n_directional_lights {
+        let light = &lights.directional_lights[i as usize];
+        scattering += fog.scattering(view_direction, light.direction_to_light, light.color.xyz());
+        i += 1;
+    }
+
+    fog.apply(color, distance, scattering)
+}
+
 pub trait TriangleWave {
     fn triangle_wave(self) -> Self;
 }
@@ -234,6 +367,17 @@ pub fn vertex_sdf_3d(
     *out_world_position = position_world;
 }
 
+/// Raymarched SDF shading parameters.
+///
+/// Mirrors `Sdf3dMaterial` in the viewer's `sdf-3d-material` example.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct Sdf3dMaterial {
+    /// Emissive radiance of the step-count glow where rays graze the surface.
+    /// Written unclamped, so values above `1.0` bloom on HDR cameras
+    pub glow_intensity: f32,
+}
+
 #[permutate(
     parameters = {},
     constants = {},
@@ -248,8 +392,11 @@ pub fn vertex_sdf_3d(
 #[spirv(fragment)]
 pub fn fragment_sdf_3d(
     #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 0, binding = 1)] lights: &Lights,
     #[spirv(uniform, descriptor_set = 0, binding = 9)] globals: &Globals,
+    #[spirv(uniform, descriptor_set = 0, binding = 10)] fog: &Fog,
     #[spirv(descriptor_set = 0, binding = 16)] depth_prepass_texture: &TextureDepth2d,
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] material: &Sdf3dMaterial,
     #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
     #[spirv(frag_coord)] in_frag_coord: Vec4,
     #[spirv(front_facing)] in_is_front: bool,
@@ -394,7 +541,8 @@ pub fn fragment_sdf_3d(
         .normalize()
         .dot(inverse_transpose_rot * normal);
 
-    let glow = (out.steps as f32 / MAX_STEPS as f32).powf(4.0) * MAX_STEPS as f32;
+    // Rays that graze the surface take the most steps, so this peaks along silhouettes
+    let glow = (out.steps as f32 / MAX_STEPS as f32).powf(4.0) * material.glow_intensity;
 
     // Scale antialias width in correspondence with screen resolution
     // Roughly corresponds to 1px per K with a min bound of 2
@@ -411,7 +559,18 @@ pub fn fragment_sdf_3d(
     let glow_col = col * Vec3::splat(glow);
     let glow_col = glow_col * (1.0 - coverage);
 
-    let col = col * Vec3::splat(light);
+    // Scene ambient light, such as the sky estimate from the viewer's `SkyPlugin`
+    let col = col * Vec3::splat(light) + col * lights.ambient_color.xyz();
+
+    // Fog by distance to the raymarched hit rather than to the bounding mesh
+    let col = apply_fog(
+        fog,
+        lights,
+        col.extend(1.0),
+        ray_direction.truncate(),
+        out.closest_t,
+    )
+    .truncate();
 
     let col = col * coverage;
 
@@ -453,3 +612,84 @@ pub fn fragment_sdf_3d(
     }
     */
 }
+
+/// Depth and normal prepass counterpart of [`fragment_sdf_3d`].
+///
+/// Raymarches the same SDF and writes its hit depth and world normal,
+/// discarding fragments whose ray misses the surface.
+#[permutate(
+    parameters = {},
+    constants = {},
+    types = {
+        Sdf
+    },
+    permutations = [
+        file("../../entry_points.json", ""),
+        env("RUST_GPU_SDF_FRAGMENT_3D_PREPASS_PERMUTATIONS", "")
+    ]
+)]
+#[spirv(fragment(depth_replacing))]
+pub fn fragment_sdf_3d_prepass(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
+    #[spirv(frag_coord)] in_frag_coord: Vec4,
+    #[spirv(front_facing)] in_is_front: bool,
+    in_world_position: Vec4,
+    #[spirv(frag_depth)] out_depth: &mut f32,
+    out_normal: &mut Vec4,
+) {
+    let camera = view.view.col(3);
+    let ray_delta = in_world_position - camera;
+    let ray_direction = ray_delta.normalize();
+    let object = mesh.model.col(3);
+
+    let model_rot = Mat3::from_mat4(mesh.model);
+    let inv_model_rot = model_rot.transpose();
+
+    let mut start = 0.0;
+    let mut end = 1000.0;
+    if in_is_front {
+        start = ray_delta.length();
+    } else {
+        end = ray_delta.length();
+    }
+
+    let sdf = <Sdf>::default();
+
+    let dir = inv_model_rot * ray_direction.truncate();
+    let eye = inv_model_rot * (camera.truncate() - object.truncate());
+
+    let inverse_transpose_rot = Mat3::from_mat4(mesh.inverse_transpose_model);
+
+    let frag_size = ddx(in_frag_coord.x / (view.viewport.z - 1.0)).abs()
+        + ddy(in_frag_coord.y / (view.viewport.w - 1.0)).abs();
+
+    let context = (
+        inverse_transpose_rot,
+        frag_size,
+        RaycastInput {
+            start,
+            end,
+            eye,
+            dir,
+        },
+    );
+
+    let (out,) = sdf.field_attributes_register_cons::<(Raycast,)>(&context);
+    if out.closest_dist > frag_size {
+        kill();
+    }
+
+    let hit = eye + dir * out.closest_t;
+    let context = context.to_hlist().push_front(out).to_tlist();
+    let context = context.to_hlist().push_front(Position(hit)).to_tlist();
+
+    let (normal,) = sdf.field_attributes_register_cons::<(AttrNormal<Vec3>,)>(&context);
+    let normal = (inverse_transpose_rot * *normal).normalize();
+
+    let hit_world = object.truncate() + model_rot * hit;
+    let hit_clip = view.view_proj * hit_world.extend(1.0);
+
+    *out_depth = hit_clip.z / hit_clip.w;
+    *out_normal = (normal * 0.5 + 0.5).extend(1.0);
+}
@@ -0,0 +1,101 @@
+//! Multi-channel signed distance field text rendering.
+//!
+//! Glyph quads are generated on the CPU by the viewer's `msdf` module,
+//! which also bakes the atlas sampled here: RGB holds a multi-channel SDF
+//! for sharp corners, and A holds a true SDF for outlines and shadows.
+
+use rust_gpu_bridge::Mix;
+use spirv_std::{
+    arch::{ddx, ddy},
+    glam::{Vec2, Vec3, Vec4},
+    image::Image2d,
+    spirv, Sampler,
+};
+
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+use crate::coverage;
+
+/// Uniform parameters for [`fragment_msdf_text`].
+///
+/// Mirrors the `#[uniform(2)]` fields of `MsdfTextMaterial` in the viewer.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct MsdfTextMaterial {
+    pub fill_color: Vec4,
+    pub outline_color: Vec4,
+    pub shadow_color: Vec4,
+    /// Shadow offset in atlas texels
+    pub shadow_offset: Vec2,
+    /// Atlas dimensions in texels
+    pub atlas_size: Vec2,
+    /// Distance range the atlas was baked with, in atlas texels
+    pub distance_range: f32,
+    /// Outline width in atlas texels
+    pub outline_width: f32,
+    /// Shadow edge softness in atlas texels
+    pub shadow_softness: f32,
+}
+
+fn median(v: Vec4) -> f32 {
+    v.x.max(v.y).min(v.x.min(v.y).max(v.z))
+}
+
+#[spirv(fragment)]
+pub fn fragment_msdf_text(
+    #[spirv(descriptor_set = 1, binding = 0)] atlas: &Image2d,
+    #[spirv(descriptor_set = 1, binding = 1)] atlas_sampler: &Sampler,
+    #[spirv(uniform, descriptor_set = 1, binding = 2)] material: &MsdfTextMaterial,
+    in_uv: Vec2,
+    out_color: &mut Vec4,
+) {
+    // Number of screen pixels covered by one distance unit of the atlas
+    let unit_range = material.distance_range / material.atlas_size;
+    let screen_tex_size = Vec2::new(
+        1.0 / (ddx(in_uv.x).abs() + ddy(in_uv.x).abs()),
+        1.0 / (ddx(in_uv.y).abs() + ddy(in_uv.y).abs()),
+    );
+    let screen_px_range = (0.5 * unit_range.dot(screen_tex_size)).max(1.0);
+
+    // Screen-space texels per atlas texel, used to convert texel widths into pixels
+    let texel_to_px = screen_px_range / material.distance_range;
+
+    let sample: Vec4 = atlas.sample(*atlas_sampler, in_uv);
+
+    // Signed distances in screen pixels, positive outside the glyph
+    let dist_fill = (0.5 - median(sample)) * screen_px_range;
+    let dist_true = (0.5 - sample.w) * screen_px_range;
+
+    let fill = coverage(dist_fill, 1.0);
+    let outline = coverage(dist_true - material.outline_width * texel_to_px, 1.0);
+
+    let shadow_uv = in_uv - material.shadow_offset / material.atlas_size;
+    let shadow_sample: Vec4 = atlas.sample(*atlas_sampler, shadow_uv);
+    let dist_shadow =
+        (0.5 - shadow_sample.w) * screen_px_range - material.outline_width * texel_to_px;
+    let shadow = coverage(
+        dist_shadow,
+        (material.shadow_softness * texel_to_px).max(1.0),
+    );
+
+    // Fill over outline
+    let glyph_col = material
+        .outline_color
+        .truncate()
+        .mix(material.fill_color.truncate(), Vec3::splat(fill));
+    let glyph_alpha = outline * material.outline_color.w.mix(material.fill_color.w, fill);
+
+    // Glyph over shadow
+    let shadow_alpha = shadow * material.shadow_color.w;
+    let alpha = glyph_alpha + shadow_alpha * (1.0 - glyph_alpha);
+    let col = if alpha > 0.0 {
+        (glyph_col * glyph_alpha
+            + material.shadow_color.truncate() * shadow_alpha * (1.0 - glyph_alpha))
+            / alpha
+    } else {
+        glyph_col
+    };
+
+    *out_color = col.extend(alpha);
+}
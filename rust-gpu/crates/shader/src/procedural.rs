@@ -0,0 +1,152 @@
+//! Solid procedural materials.
+//!
+//! [`vertex_procedural`] passes object-space position and normal through to the fragment stage,
+//! where [`fragment_procedural`] evaluates a `shader_shared::procedural::Pattern` selected by permutation,
+//! fills a `PbrInput` with its base color, roughness, metallic and bump-mapped normal,
+//! and lights it with `bevy-pbr-rust`'s [`pbr`] like `StandardMaterial`.
+
+use bevy_pbr_rust::prelude::{
+    pbr, ClusterLightIndexListsStorage, ClusterOffsetsAndCountsStorage, Lights, Mesh,
+    PointLightsStorage, StandardMaterial, TextureDepth2dArray, TextureDepthCubeArray, View,
+};
+use permutate_macro::permutate;
+use shader_shared::{fog::Fog, procedural::Pattern as _};
+use spirv_std::{
+    glam::{Mat3, Vec3, Vec4, Vec4Swizzles},
+    spirv, Sampler,
+};
+
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+use crate::{
+    apply_fog,
+    extended_pbr::{prepare_pbr_input, TextureSamples},
+};
+
+// Candidates for the `Pattern` type parameter of `fragment_procedural`
+#[allow(unused_imports)]
+use shader_shared::procedural::{Marble, Rust, Strata, Wood};
+
+/// Pattern placement and bump parameters.
+///
+/// Mirrors `ProceduralMaterial` in the viewer's `procedural-material` example.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct ProceduralMaterial {
+    /// Object-space frequency of the pattern
+    pub scale: f32,
+    /// Strength of the normal perturbation from the pattern's height field
+    pub bump_strength: f32,
+}
+
+#[spirv(vertex)]
+pub fn vertex_procedural(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
+
+    in_position: Vec3,
+    in_normal: Vec3,
+
+    #[spirv(position)] out_clip_position: &mut Vec4,
+    out_world_position: &mut Vec4,
+    out_local_position: &mut Vec3,
+    out_local_normal: &mut Vec3,
+) {
+    let position_world = mesh.model * in_position.extend(1.0);
+
+    *out_clip_position = view.view_proj * position_world;
+    *out_world_position = position_world;
+    *out_local_position = in_position;
+    *out_local_normal = in_normal;
+}
+
+#[permutate(
+    parameters = {},
+    constants = {},
+    types = {
+        Pattern
+    },
+    permutations = [
+        file("../../entry_points.json", "procedural"),
+        env("RUST_GPU_PROCEDURAL_FRAGMENT_PERMUTATIONS", "procedural")
+    ]
+)]
+#[spirv(fragment)]
+pub fn fragment_procedural(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 0, binding = 1)] lights: &Lights,
+    #[spirv(descriptor_set = 0, binding = 2)] point_shadow_textures: &TextureDepthCubeArray,
+    #[spirv(descriptor_set = 0, binding = 3)] point_shadow_textures_sampler: &Sampler,
+    #[spirv(descriptor_set = 0, binding = 4)] directional_shadow_textures: &TextureDepth2dArray,
+    #[spirv(descriptor_set = 0, binding = 5)] directional_shadow_textures_sampler: &Sampler,
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 6)] point_lights: &PointLightsStorage,
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 7)]
+    cluster_light_index_lists: &ClusterLightIndexListsStorage,
+    #[spirv(storage_buffer, descriptor_set = 0, binding = 8)]
+    cluster_offsets_and_counts: &ClusterOffsetsAndCountsStorage,
+    #[spirv(uniform, descriptor_set = 0, binding = 10)] fog: &Fog,
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] material: &ProceduralMaterial,
+    #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
+    #[spirv(frag_coord)] in_frag_coord: Vec4,
+    #[spirv(front_facing)] in_is_front: bool,
+    in_world_position: Vec4,
+    in_local_position: Vec3,
+    in_local_normal: Vec3,
+    out_color: &mut Vec4,
+) {
+    let pattern = <Pattern>::default();
+    let surface = pattern.surface(in_local_position * material.scale);
+
+    // No texture flags are set, so the samples go unused
+    let standard = StandardMaterial {
+        base_color: surface.base_color.extend(1.0),
+        perceptual_roughness: surface.roughness,
+        metallic: surface.metallic,
+        ..Default::default()
+    };
+    let samples = TextureSamples {
+        base_color: Vec4::ONE,
+        emissive: Vec4::ONE,
+        metallic_roughness: Vec4::ONE,
+        occlusion: Vec4::ONE,
+    };
+
+    let world_normal = Mat3::from_mat4(mesh.inverse_transpose_model) * in_local_normal;
+    let mut input = prepare_pbr_input(
+        &standard,
+        &samples,
+        view,
+        in_frag_coord,
+        in_world_position,
+        world_normal,
+        in_is_front,
+    );
+
+    // Bump in object space, where the height gradient lives, then move to world space
+    let local_normal = surface.perturb_normal(in_local_normal.normalize(), material.bump_strength);
+    input.n = (Mat3::from_mat4(mesh.inverse_transpose_model) * local_normal).normalize();
+    input.flags = mesh.flags;
+
+    let color = pbr(
+        view,
+        lights,
+        point_lights,
+        cluster_light_index_lists,
+        cluster_offsets_and_counts,
+        directional_shadow_textures,
+        directional_shadow_textures_sampler,
+        point_shadow_textures,
+        point_shadow_textures_sampler,
+        &input,
+    );
+
+    let ray_delta = in_world_position.xyz() - view.world_position;
+    *out_color = apply_fog(
+        fog,
+        lights,
+        color,
+        ray_delta.normalize(),
+        ray_delta.length(),
+    );
+}
@@ -0,0 +1,93 @@
+//! Sprite rendering from baked signed distance textures.
+//!
+//! Textures are produced by the viewer's `sdf_bake` module:
+//! RGB holds the sprite's color dilated past its edges, and A holds its encoded signed distance.
+
+use rust_gpu_bridge::{Mix, SmoothStep};
+use spirv_std::{
+    arch::{ddx, ddy},
+    glam::{Vec2, Vec3, Vec4},
+    image::Image2d,
+    spirv, Sampler,
+};
+
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+use crate::{coverage, noise::value_2d};
+
+/// Uniform parameters for [`fragment_sdf_sprite`].
+///
+/// Mirrors the `#[uniform(2)]` fields of `SdfSpriteMaterial` in the viewer.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct SdfSpriteMaterial {
+    pub outline_color: Vec4,
+    pub glow_color: Vec4,
+    pub dissolve_color: Vec4,
+    /// Distance range the texture was baked with, in texels
+    pub spread: f32,
+    /// Outline width in texels
+    pub outline_width: f32,
+    /// Distance past the outline over which glow fades out, in texels
+    pub glow_radius: f32,
+    /// Dissolve progress, from 0 (intact) to 1 (gone)
+    pub dissolve: f32,
+    /// Frequency of the dissolve noise over the sprite's UV space
+    pub dissolve_scale: f32,
+    /// Width of the burning edge that leads the dissolve, in noise units
+    pub dissolve_edge_width: f32,
+}
+
+#[spirv(fragment)]
+pub fn fragment_sdf_sprite(
+    #[spirv(descriptor_set = 1, binding = 0)] texture: &Image2d,
+    #[spirv(descriptor_set = 1, binding = 1)] texture_sampler: &Sampler,
+    #[spirv(uniform, descriptor_set = 1, binding = 2)] material: &SdfSpriteMaterial,
+    in_uv: Vec2,
+    out_color: &mut Vec4,
+) {
+    let sample: Vec4 = texture.sample(*texture_sampler, in_uv);
+
+    // Signed distance in texels, positive outside the sprite
+    let dist = (0.5 - sample.w) * material.spread;
+    let aa = (ddx(dist).abs() + ddy(dist).abs()).max(0.0001);
+
+    let fill = coverage(dist, aa);
+    let outline = coverage(dist - material.outline_width, aa);
+
+    // Sprite over outline
+    let col = material
+        .outline_color
+        .truncate()
+        .mix(sample.truncate(), Vec3::splat(fill));
+    let alpha = outline * material.outline_color.w.mix(1.0, fill);
+
+    // Burn away from a noise threshold, leading with an edge color
+    let noise = value_2d(in_uv * material.dissolve_scale).value * 0.5 + 0.5;
+    let edge = material.dissolve_edge_width.max(0.0001);
+    let burn = (noise + edge - material.dissolve * (1.0 + edge)) / edge;
+    let burn_aa = (ddx(burn).abs() + ddy(burn).abs()).max(0.0001);
+
+    let col = material
+        .dissolve_color
+        .truncate()
+        .mix(col, Vec3::splat(burn.clamp(0.0, 1.0)));
+    let alpha = alpha * coverage(-burn, burn_aa);
+
+    // Glow fades outward from the outline, and burns away along with the sprite
+    let glow_dist = (dist - material.outline_width).max(0.0);
+    let glow = (1.0 - glow_dist.smooth_step(0.0, material.glow_radius.max(0.0001)))
+        * material.glow_color.w
+        * (1.0 - material.dissolve);
+
+    // Sprite over glow
+    let out_alpha = alpha + glow * (1.0 - alpha);
+    let col = if out_alpha > 0.0 {
+        (col * alpha + material.glow_color.truncate() * glow * (1.0 - alpha)) / out_alpha
+    } else {
+        col
+    };
+
+    *out_color = col.extend(out_alpha);
+}
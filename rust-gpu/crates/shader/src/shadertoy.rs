@@ -0,0 +1,101 @@
+//! Shadertoy-style fragment harness.
+//!
+//! Maps Bevy's `View` and `Globals`, plus a mouse uniform updated by the viewer,
+//! onto Shadertoy's `iResolution`, `iTime`, `iTimeDelta`, `iFrame` and `iMouse` inputs.
+//! Porting a shader means rewriting its `mainImage` as [`main_image`].
+
+use bevy_pbr_rust::prelude::{Globals, View};
+use rust_gpu_bridge::SmoothStep;
+use spirv_std::{
+    glam::{Vec2, Vec3, Vec4, Vec4Swizzles},
+    spirv,
+};
+
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+/// Mouse uniform, laid out like Shadertoy's `iMouse`.
+///
+/// `xy` is the cursor position while the button is held,
+/// `zw` is the position of the last click.
+/// `z` is negative while the button is up, `w` is negative except on the frame of a click.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct ShadertoyMouse {
+    pub mouse: Vec4,
+}
+
+/// Shadertoy-equivalent inputs, in pixels and seconds
+#[derive(Debug, Copy, Clone)]
+pub struct ShadertoyInputs {
+    /// `iResolution`: viewport size in pixels, with a pixel aspect ratio of 1.0 in `z`
+    pub resolution: Vec3,
+    /// `iTime`
+    pub time: f32,
+    /// `iTimeDelta`
+    pub time_delta: f32,
+    /// `iFrame`
+    pub frame: u32,
+    /// `iMouse`
+    pub mouse: Vec4,
+}
+
+impl ShadertoyInputs {
+    pub fn new(view: &View, globals: &Globals, mouse: &ShadertoyMouse) -> Self {
+        ShadertoyInputs {
+            resolution: view.viewport.zw().extend(1.0),
+            time: globals.time,
+            time_delta: globals.delta_time,
+            frame: globals.frame_count,
+            mouse: mouse.mouse,
+        }
+    }
+}
+
+/// Convert a `frag_coord` builtin into Shadertoy's viewport-relative, bottom-left origin convention
+pub fn frag_coord(view: &View, in_frag_coord: Vec4) -> Vec2 {
+    Vec2::new(
+        in_frag_coord.x - view.viewport.x,
+        view.viewport.w - (in_frag_coord.y - view.viewport.y),
+    )
+}
+
+/// Equivalent of Shadertoy's `mainImage`; replace this body with a ported shader
+pub fn main_image(frag_coord: Vec2, inputs: &ShadertoyInputs) -> Vec4 {
+    // Normalized pixel coordinates (from 0 to 1)
+    let uv = frag_coord / inputs.resolution.truncate();
+
+    // Time varying pixel color
+    let mut col = Vec3::new(
+        (inputs.time + uv.x).cos(),
+        (inputs.time + uv.y + 2.0).cos(),
+        (inputs.time + uv.x + 4.0).cos(),
+    ) * 0.5
+        + 0.5;
+
+    // Ring around the cursor while dragging, dot at the last click
+    let drag = (frag_coord - inputs.mouse.truncate().truncate()).length();
+    let click = (frag_coord - Vec2::new(inputs.mouse.z, inputs.mouse.w).abs()).length();
+
+    if inputs.mouse.z > 0.0 {
+        col += Vec3::splat(1.0 - (drag - 16.0).abs().smooth_step(0.0, 2.0));
+    }
+    if inputs.mouse.z != 0.0 {
+        col *= click.smooth_step(4.0, 6.0);
+    }
+
+    // Output to screen
+    col.extend(1.0)
+}
+
+#[spirv(fragment)]
+pub fn fragment_shadertoy(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 0, binding = 1)] globals: &Globals,
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] mouse: &ShadertoyMouse,
+    #[spirv(frag_coord)] in_frag_coord: Vec4,
+    out_color: &mut Vec4,
+) {
+    let inputs = ShadertoyInputs::new(view, globals, mouse);
+    *out_color = main_image(frag_coord(view, in_frag_coord), &inputs);
+}
@@ -0,0 +1,74 @@
+//! Procedural sky from `shader_shared::atmosphere`.
+//!
+//! [`vertex_sky`] pins a mesh surrounding the camera to the far plane,
+//! and [`fragment_sky`] evaluates single scattering along each view direction,
+//! lit by the scene's first directional light.
+//! Output stays in linear HDR, leaving tonemapping to the camera.
+
+use bevy_pbr_rust::prelude::{Lights, Mesh, View};
+use shader_shared::atmosphere::Atmosphere;
+use spirv_std::{
+    glam::{Vec3, Vec4, Vec4Swizzles},
+    spirv,
+};
+
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+/// Sky parameters.
+///
+/// Mirrors `SkyMaterial` in the viewer's `sky` module.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct SkyMaterial {
+    pub atmosphere: Atmosphere,
+    /// Multiplier applied to sky radiance, which is output as linear HDR for the camera to tonemap
+    pub exposure: f32,
+    /// Angular radius of the sun disk in radians
+    pub sun_angular_radius: f32,
+}
+
+#[spirv(vertex)]
+pub fn vertex_sky(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
+
+    in_position: Vec3,
+
+    #[spirv(position)] out_clip_position: &mut Vec4,
+    out_view_direction: &mut Vec3,
+) {
+    let position_world = mesh.model * in_position.extend(1.0);
+    let clip = view.view_proj * position_world;
+
+    // Zero depth is infinitely far under Bevy's reversed-Z projection, behind all geometry
+    *out_clip_position = Vec4::new(clip.x, clip.y, 0.0, clip.w);
+    *out_view_direction = position_world.xyz() - view.world_position;
+}
+
+#[spirv(fragment)]
+pub fn fragment_sky(
+    #[spirv(uniform, descriptor_set = 0, binding = 1)] lights: &Lights,
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] material: &SkyMaterial,
+    in_view_direction: Vec3,
+    out_color: &mut Vec4,
+) {
+    let direction = in_view_direction.normalize();
+
+    // Without a directional light, the sun sits below the horizon
+    let sun = if lights.n_directional_lights > 0 {
+        lights.directional_lights[0].direction_to_light
+    } else {
+        -Vec3::Y
+    };
+
+    let atmosphere = material.atmosphere;
+    let mut radiance = atmosphere.sky(direction, sun);
+
+    // Sun disk, dimmed and reddened by the atmosphere in front of it
+    if direction.dot(sun) > material.sun_angular_radius.cos() && direction.y >= 0.0 {
+        radiance += atmosphere.transmittance(sun) * atmosphere.sun_intensity;
+    }
+
+    *out_color = (radiance * material.exposure).extend(1.0);
+}
@@ -0,0 +1,167 @@
+//! Cel shading with inverted-hull outlines.
+//!
+//! A toon object is drawn twice: once with [`vertex_toon`] and [`fragment_toon`],
+//! which quantize the scene's first directional light into `ramp_steps` flat bands,
+//! and once with [`vertex_toon_outline`] and [`fragment_toon_outline`],
+//! which push the mesh out along its normals and draw only its back faces as a solid-color hull.
+
+use bevy_pbr_rust::prelude::{Lights, Mesh, View};
+use shader_shared::fog::Fog;
+use spirv_std::{
+    glam::{Mat3, Vec2, Vec3, Vec4, Vec4Swizzles},
+    spirv,
+};
+
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+use crate::apply_fog;
+
+/// Cel shading parameters.
+///
+/// Mirrors `ToonMaterial` in the viewer's `toon` example.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct ToonMaterial {
+    /// Color of fully lit bands
+    pub base_color: Vec4,
+    /// Color of the unlit band
+    pub shadow_color: Vec4,
+    /// Number of bands between `shadow_color` and `base_color`
+    pub ramp_steps: u32,
+    /// Cosine of the angle around the reflected light inside which the hard highlight shows,
+    /// with `1.0` disabling it
+    pub specular_threshold: f32,
+}
+
+/// Outline parameters.
+///
+/// Mirrors `ToonOutlineMaterial` in the viewer's `toon` example.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct ToonOutlineMaterial {
+    pub outline_color: Vec4,
+    /// Outline thickness in pixels
+    pub outline_width: f32,
+}
+
+/// Quantize `x` in `[0, 1]` into `steps` evenly spaced levels, including both ends
+fn quantize(x: f32, steps: u32) -> f32 {
+    let levels = (steps.max(2) - 1) as f32;
+    (x.clamp(0.0, 1.0) * levels + 0.5).floor() / levels
+}
+
+#[spirv(vertex)]
+pub fn vertex_toon(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
+
+    in_position: Vec3,
+    in_normal: Vec3,
+
+    #[spirv(position)] out_clip_position: &mut Vec4,
+    out_world_position: &mut Vec3,
+    out_world_normal: &mut Vec3,
+) {
+    let position_world = mesh.model * in_position.extend(1.0);
+
+    *out_clip_position = view.view_proj * position_world;
+    *out_world_position = position_world.xyz();
+    *out_world_normal = (Mat3::from_mat4(mesh.inverse_transpose_model) * in_normal).normalize();
+}
+
+#[spirv(fragment)]
+pub fn fragment_toon(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 0, binding = 1)] lights: &Lights,
+    #[spirv(uniform, descriptor_set = 0, binding = 10)] fog: &Fog,
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] material: &ToonMaterial,
+    in_world_position: Vec3,
+    in_world_normal: Vec3,
+    out_color: &mut Vec4,
+) {
+    let normal = in_world_normal.normalize();
+
+    // Without a directional light, everything falls in the shadow band
+    let (to_light, tint) = if lights.n_directional_lights > 0 {
+        let light = lights.directional_lights[0];
+        // Light color is premultiplied by illuminance and exposure, so keep only its hue
+        let color = light.color.xyz();
+        (
+            light.direction_to_light,
+            color / color.max_element().max(f32::EPSILON),
+        )
+    } else {
+        (Vec3::ZERO, Vec3::ONE)
+    };
+
+    let n_dot_l = normal.dot(to_light).max(0.0);
+    let band = quantize(n_dot_l, material.ramp_steps);
+    let mut color = material
+        .shadow_color
+        .xyz()
+        .lerp(material.base_color.xyz() * tint, band);
+
+    // Hard-edged highlight, only on lit bands
+    let ray_delta = in_world_position - view.world_position;
+    let to_camera = -ray_delta.normalize();
+    let reflected = (normal * 2.0 * normal.dot(to_light) - to_light).normalize_or_zero();
+    if band > 0.0 && reflected.dot(to_camera) > material.specular_threshold {
+        color = tint;
+    }
+
+    *out_color = apply_fog(
+        fog,
+        lights,
+        color.extend(material.base_color.w),
+        -to_camera,
+        ray_delta.length(),
+    );
+}
+
+#[spirv(vertex)]
+pub fn vertex_toon_outline(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] material: &ToonOutlineMaterial,
+    #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
+
+    in_position: Vec3,
+    in_normal: Vec3,
+
+    #[spirv(position)] out_clip_position: &mut Vec4,
+    out_world_position: &mut Vec3,
+) {
+    let position_world = mesh.model * in_position.extend(1.0);
+    let position_clip = view.view_proj * position_world;
+
+    // Project the world normal to screen space, and offset by a constant pixel width there
+    let normal_world = Mat3::from_mat4(mesh.inverse_transpose_model) * in_normal;
+    let normal_clip = view.view_proj * normal_world.extend(0.0);
+    let direction = Vec2::new(normal_clip.x, normal_clip.y).normalize_or_zero();
+
+    // Clip space spans two units across the viewport, and is divided by w on rasterization
+    let offset = direction * material.outline_width * 2.0 / view.viewport.zw() * position_clip.w;
+
+    *out_clip_position = position_clip + offset.extend(0.0).extend(0.0);
+    *out_world_position = position_world.xyz();
+}
+
+#[spirv(fragment)]
+pub fn fragment_toon_outline(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 0, binding = 1)] lights: &Lights,
+    #[spirv(uniform, descriptor_set = 0, binding = 10)] fog: &Fog,
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] material: &ToonOutlineMaterial,
+    in_world_position: Vec3,
+    out_color: &mut Vec4,
+) {
+    // Fogged at the surface underneath, as the hull is only a few pixels wider
+    let ray_delta = in_world_position - view.world_position;
+    *out_color = apply_fog(
+        fog,
+        lights,
+        material.outline_color,
+        ray_delta.normalize(),
+        ray_delta.length(),
+    );
+}
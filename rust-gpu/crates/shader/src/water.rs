@@ -0,0 +1,151 @@
+//! Gerstner wave water.
+//!
+//! [`vertex_water`] displaces a flat, subdivided plane by a sum of Gerstner waves from the material uniform,
+//! using the analytic derivatives from `shader_shared::water` for its normals and tangents.
+//! [`fragment_water`] shades the surface with Fresnel sky reflection,
+//! color absorption based on the depth prepass, and shoreline and crest foam.
+
+use bevy_pbr_rust::prelude::{Globals, Lights, Mesh, TextureDepth2d, View};
+use rust_gpu_bridge::SmoothStep;
+use shader_shared::{
+    fog::Fog,
+    water::{gerstner, GerstnerWave, MAX_WAVES},
+};
+use spirv_std::{
+    glam::{Mat3, Vec2, Vec3, Vec4, Vec4Swizzles},
+    spirv,
+};
+
+#[allow(unused_imports)]
+use spirv_std::num_traits::Float;
+
+use crate::apply_fog;
+
+/// Water parameters.
+///
+/// Mirrors `WaterMaterial` in the viewer's `water` example.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct WaterMaterial {
+    /// Color of thin water, alpha controls its opacity
+    pub shallow_color: Vec4,
+    /// Color of thick water, alpha controls its opacity
+    pub deep_color: Vec4,
+    /// Color reflected at grazing angles
+    pub sky_color: Vec4,
+    /// Foam color, alpha controls its strength
+    pub foam_color: Vec4,
+    pub waves: [GerstnerWave; MAX_WAVES],
+    /// Number of entries in `waves` in use
+    pub wave_count: u32,
+    /// Water thickness over which `shallow_color` fades to `deep_color`
+    pub absorption_depth: f32,
+    /// Water thickness under which shoreline foam appears
+    pub foam_depth: f32,
+    /// Crest height at which wave foam appears, relative to the summed wave steepness
+    pub foam_crest: f32,
+    /// Strength of the small scrolling ripples added to the wave normal
+    pub ripple_strength: f32,
+}
+
+/// Schlick's approximation for an air-water interface
+fn fresnel(cos_theta: f32) -> f32 {
+    const F0: f32 = 0.02;
+    F0 + (1.0 - F0) * (1.0 - cos_theta.max(0.0)).powf(5.0)
+}
+
+#[spirv(vertex)]
+pub fn vertex_water(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 0, binding = 9)] globals: &Globals,
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] material: &WaterMaterial,
+    #[spirv(uniform, descriptor_set = 2, binding = 0)] mesh: &Mesh,
+
+    in_position: Vec3,
+
+    #[spirv(position)] out_clip_position: &mut Vec4,
+    out_world_position: &mut Vec3,
+    out_world_normal: &mut Vec3,
+    out_world_tangent: &mut Vec3,
+    out_crest: &mut f32,
+) {
+    let sample = gerstner(
+        &material.waves,
+        material.wave_count,
+        in_position,
+        globals.time,
+    );
+
+    let position_world = mesh.model * sample.position.extend(1.0);
+
+    *out_clip_position = view.view_proj * position_world;
+    *out_world_position = position_world.xyz();
+    *out_world_normal =
+        (Mat3::from_mat4(mesh.inverse_transpose_model) * sample.normal()).normalize();
+    *out_world_tangent = (Mat3::from_mat4(mesh.model) * sample.tangent).normalize();
+    *out_crest = sample.crest;
+}
+
+#[spirv(fragment)]
+pub fn fragment_water(
+    #[spirv(uniform, descriptor_set = 0, binding = 0)] view: &View,
+    #[spirv(uniform, descriptor_set = 0, binding = 1)] lights: &Lights,
+    #[spirv(uniform, descriptor_set = 0, binding = 9)] globals: &Globals,
+    #[spirv(uniform, descriptor_set = 0, binding = 10)] fog: &Fog,
+    #[spirv(descriptor_set = 0, binding = 16)] depth_prepass_texture: &TextureDepth2d,
+    #[spirv(uniform, descriptor_set = 1, binding = 0)] material: &WaterMaterial,
+    #[spirv(frag_coord)] in_frag_coord: Vec4,
+    in_world_position: Vec3,
+    in_world_normal: Vec3,
+    in_world_tangent: Vec3,
+    in_crest: f32,
+    out_color: &mut Vec4,
+) {
+    // Tangent frame of the wave surface
+    let n = in_world_normal.normalize();
+    let t = (in_world_tangent - n * n.dot(in_world_tangent)).normalize();
+    let b = n.cross(t);
+
+    // Perturb by scrolling ripples too small for the mesh to resolve
+    let p = Vec2::new(in_world_position.x, in_world_position.z);
+    let time = globals.time;
+    let ripple = Vec2::new(
+        (p.x * 3.1 + time * 1.7).sin() + (p.y * 2.3 - time * 1.3).sin() * 0.5,
+        (p.y * 2.9 + time * 1.5).cos() + (p.x * 1.9 - time * 1.1).cos() * 0.5,
+    ) * material.ripple_strength;
+    let normal = (n + t * ripple.x + b * ripple.y).normalize();
+
+    // Water thickness along the view ray, from the view-space depth of the opaque scene behind
+    let ndc = Vec2::new(
+        (in_frag_coord.x - view.viewport.x) / view.viewport.z * 2.0 - 1.0,
+        1.0 - (in_frag_coord.y - view.viewport.y) / view.viewport.w * 2.0,
+    );
+    let depth = depth_prepass_texture.fetch(in_frag_coord.xy().as_ivec2()).x;
+    let floor = view.inverse_projection * ndc.extend(depth).extend(1.0);
+    let floor_z = floor.z / floor.w;
+    let surface_z = (view.inverse_view * in_world_position.extend(1.0)).z;
+    let thickness = (surface_z - floor_z).max(0.0);
+
+    // Beer-Lambert style fade from shallow to deep color
+    let absorption = 1.0 - (-thickness / material.absorption_depth.max(f32::EPSILON)).exp();
+    let body = material.shallow_color.lerp(material.deep_color, absorption);
+
+    let ray_delta = in_world_position - view.world_position;
+    let to_camera = -ray_delta.normalize();
+    let reflectance = fresnel(normal.dot(to_camera));
+    let mut color = body.xyz().lerp(material.sky_color.xyz(), reflectance);
+
+    // Foam along shorelines and on wave crests
+    let shore = 1.0 - thickness.smooth_step(0.0, material.foam_depth);
+    let crest = in_crest.smooth_step(material.foam_crest, material.foam_crest + 0.2);
+    let foam = shore.max(crest) * material.foam_color.w;
+    color = color.lerp(material.foam_color.xyz(), foam);
+
+    *out_color = apply_fog(
+        fog,
+        lights,
+        color.extend(body.w.max(reflectance).max(foam)),
+        -to_camera,
+        ray_delta.length(),
+    );
+}